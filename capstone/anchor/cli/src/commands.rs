@@ -0,0 +1,712 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{instruction::Instruction, program_pack::Pack};
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::state::Account as TokenAccount;
+use researchchain_client::instructions::Closable;
+use researchchain_client::{
+    accounts, instructions, pda, Allowlist, AllowlistProof, Attachment, ContributionLimits,
+    Contributor, Pledge, ProgramError, Proposal, Review, StretchGoal, TeamMember, Vote, PROGRAM_ID,
+};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Error as ClientError;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{output, show, CliResult, CreateArgs, EvidenceArgs, RoundArgs};
+
+/// Offset of `Vote.milestone` and `Contributor.proposal`: discriminator,
+/// version, then a wallet key. Accounts still in the unversioned layout don't
+/// match until migrated.
+const PARENT_OFFSET: usize = 8 + 1 + 32;
+
+/// Accounts `sweep` closes per transaction, votes take three account slots.
+const CLOSES_PER_TX: usize = 8;
+
+pub struct Context {
+    pub client: RpcClient,
+    keypair_path: String,
+}
+
+impl Context {
+    pub fn new(url: String, keypair: Option<&str>) -> CliResult<Self> {
+        let keypair_path = match keypair {
+            Some(path) => path.to_string(),
+            None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+        };
+        Ok(Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            keypair_path,
+        })
+    }
+
+    /// Loaded on demand so read-only commands work without a keypair.
+    pub fn signer(&self) -> CliResult<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|err| format!("reading keypair {}: {err}", self.keypair_path).into())
+    }
+
+    pub fn proposal(&self, address: &Pubkey) -> CliResult<Proposal> {
+        let data = self.client.get_account_data(address)?;
+        Ok(accounts::decode_proposal(&data)?)
+    }
+
+    pub fn pledge(&self, contributor: &Pubkey, proposal: &Pubkey) -> CliResult<Pledge> {
+        let data = self
+            .client
+            .get_account_data(&pda::pledge(contributor, proposal).0)?;
+        Ok(accounts::decode_pledge(&data)?)
+    }
+
+    pub fn token_account(&self, address: &Pubkey) -> CliResult<TokenAccount> {
+        let data = self.client.get_account_data(address)?;
+        Ok(TokenAccount::unpack(&data)?)
+    }
+
+    /// Program accounts of type `T` whose parent key is `parent`.
+    fn children<T: Discriminator>(&self, parent: &Pubkey) -> CliResult<Vec<(Pubkey, Vec<u8>)>> {
+        let found = self.client.get_program_accounts_with_config(
+            &PROGRAM_ID,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, T::DISCRIMINATOR.to_vec())),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                        PARENT_OFFSET,
+                        parent.to_bytes().to_vec(),
+                    )),
+                ]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )?;
+        Ok(found
+            .into_iter()
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    pub fn votes(&self, milestone: &Pubkey) -> CliResult<Vec<(Pubkey, Vote)>> {
+        self.children::<Vote>(milestone)?
+            .into_iter()
+            .map(|(address, data)| Ok((address, accounts::decode_vote(&data)?)))
+            .collect()
+    }
+
+    pub fn contributors(&self, proposal: &Pubkey) -> CliResult<Vec<(Pubkey, Contributor)>> {
+        self.children::<Contributor>(proposal)?
+            .into_iter()
+            .map(|(address, data)| Ok((address, accounts::decode_contributor(&data)?)))
+            .collect()
+    }
+
+    /// Mint of the token the proposal's escrow holds.
+    pub fn escrow_mint(&self, proposal: &Pubkey) -> CliResult<Pubkey> {
+        Ok(self.token_account(&pda::escrow(proposal).0)?.mint)
+    }
+
+    pub fn send(&self, payer: &Keypair, ixs: &[Instruction], extra: &[&Keypair]) -> CliResult {
+        let mut signers: Vec<&Keypair> = vec![payer];
+        signers.extend_from_slice(extra);
+        let blockhash = self.client.get_latest_blockhash()?;
+        let tx =
+            Transaction::new_signed_with_payer(ixs, Some(&payer.pubkey()), &signers, blockhash);
+        let signature: Signature = self
+            .client
+            .send_and_confirm_transaction(&tx)
+            .map_err(decode_error)?;
+        println!("{signature}");
+        Ok(())
+    }
+}
+
+/// Replaces a failed transaction's custom code with the program error behind it.
+fn decode_error(err: ClientError) -> Box<dyn std::error::Error> {
+    match err.get_transaction_error() {
+        Some(TransactionError::InstructionError(_, error)) => {
+            match ProgramError::from_instruction_error(&error) {
+                Some(error) => error.into(),
+                None => err.into(),
+            }
+        }
+        _ => err.into(),
+    }
+}
+
+pub fn create(ctx: &Context, args: CreateArgs) -> CliResult {
+    let payer = ctx.signer()?;
+    let researcher = payer.pubkey();
+    let token_account = args
+        .token_account
+        .unwrap_or_else(|| get_associated_token_address(&researcher, &args.mint));
+    let (proposal, _) = pda::proposal(&researcher, &args.title);
+
+    let ix = instructions::create_proposal(
+        &researcher,
+        &args.mint,
+        &token_account,
+        instructions::CreateProposalArgs {
+            title: args.title,
+            abstract_text: args.abstract_text,
+            ipfs_hash: args.ipfs_hash,
+            amount_asked: args.amount_asked,
+            total_milestones: args.milestones,
+            security_deposit: args.security_deposit,
+            challenge_period: args.challenge_period,
+            allowlist_root: args
+                .allowlist
+                .map(|csv| load_allowlist(&csv))
+                .transpose()?
+                .map(|allowlist| allowlist.root()),
+            contribution_limits: ContributionLimits {
+                min: args.min_contribution,
+                max: args.max_contribution,
+                hard_cap: args.hard_cap,
+            },
+            stretch_goals: args
+                .stretch_goals
+                .iter()
+                .map(|goal| parse_stretch_goal(goal))
+                .collect::<CliResult<_>>()?,
+        },
+    );
+    ctx.send(&payer, &[ix], &[])?;
+    println!("proposal: {proposal}");
+    Ok(())
+}
+
+pub fn fund(
+    ctx: &Context,
+    proposal: &Pubkey,
+    amount: u64,
+    token_account: Option<Pubkey>,
+    round: Option<Pubkey>,
+    allowlist: Option<PathBuf>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let mint = ctx.escrow_mint(proposal)?;
+    let token_account =
+        token_account.unwrap_or_else(|| get_associated_token_address(&payer.pubkey(), &mint));
+
+    let ix = match (allowance(allowlist, &payer.pubkey())?, round) {
+        (Some(allowance), round) => instructions::fund_proposal_allowlisted(
+            &payer.pubkey(),
+            proposal,
+            &mint,
+            &token_account,
+            amount,
+            round.as_ref(),
+            allowance,
+        ),
+        (None, Some(round)) => instructions::fund_proposal_in_round(
+            &payer.pubkey(),
+            proposal,
+            &mint,
+            &token_account,
+            amount,
+            &round,
+        ),
+        (None, None) => {
+            instructions::fund_proposal(&payer.pubkey(), proposal, &mint, &token_account, amount)
+        }
+    };
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn fund_for(
+    ctx: &Context,
+    proposal: &Pubkey,
+    beneficiary: &Pubkey,
+    amount: u64,
+    token_account: Option<Pubkey>,
+    memo: Option<String>,
+    allowlist: Option<PathBuf>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let mint = ctx.escrow_mint(proposal)?;
+    let token_account =
+        token_account.unwrap_or_else(|| get_associated_token_address(&payer.pubkey(), &mint));
+    let ix = instructions::fund_proposal_for(
+        &payer.pubkey(),
+        beneficiary,
+        proposal,
+        &mint,
+        &token_account,
+        amount,
+        memo,
+        allowance(allowlist, beneficiary)?,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn pledge(
+    ctx: &Context,
+    proposal: &Pubkey,
+    amount: u64,
+    installments: u16,
+    token_account: Option<Pubkey>,
+    allowlist: Option<PathBuf>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let mint = ctx.escrow_mint(proposal)?;
+    let token_account =
+        token_account.unwrap_or_else(|| get_associated_token_address(&payer.pubkey(), &mint));
+    let ix = instructions::create_pledge(
+        &payer.pubkey(),
+        proposal,
+        &mint,
+        &token_account,
+        amount,
+        installments,
+        allowance(allowlist, &payer.pubkey())?,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn execute_pledge(ctx: &Context, proposal: &Pubkey, contributor: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let pledge = ctx.pledge(contributor, proposal)?;
+    let ix = instructions::execute_pledge(
+        &payer.pubkey(),
+        proposal,
+        contributor,
+        &pledge.token_account,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn cancel_pledge(ctx: &Context, proposal: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let pledge = ctx.pledge(&payer.pubkey(), proposal)?;
+    let ix = instructions::cancel_pledge(&payer.pubkey(), proposal, &pledge.token_account);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn allowlist(csv: &Path, wallet: Option<Pubkey>) -> CliResult {
+    let allowlist = load_allowlist(csv)?;
+    let mut fields = vec![
+        ("Root", output::hex(&allowlist.root())),
+        ("Wallets", allowlist.entries().len().to_string()),
+    ];
+    if let Some(wallet) = wallet {
+        let proof = allowlist
+            .proof(&wallet)
+            .ok_or_else(|| format!("{wallet} is not on the allowlist"))?;
+        fields.push(("Cap", proof.cap.to_string()));
+        fields.extend(proof.proof.iter().map(|node| ("Proof", output::hex(node))));
+    }
+    output::fields(&fields);
+    Ok(())
+}
+
+fn load_allowlist(csv: &Path) -> CliResult<Allowlist> {
+    let data = std::fs::read_to_string(csv)
+        .map_err(|err| format!("reading allowlist {}: {err}", csv.display()))?;
+    Ok(Allowlist::from_csv(&data)?)
+}
+
+/// `wallet`'s proof from the allowlist CSV, if one was given.
+fn allowance(csv: Option<PathBuf>, wallet: &Pubkey) -> CliResult<Option<AllowlistProof>> {
+    let Some(csv) = csv else { return Ok(None) };
+    let proof = load_allowlist(&csv)?
+        .proof(wallet)
+        .ok_or_else(|| format!("{wallet} is not on the allowlist"))?;
+    Ok(Some(proof))
+}
+
+pub fn evidence(ctx: &Context, args: EvidenceArgs) -> CliResult {
+    let payer = ctx.signer()?;
+    let ix = instructions::add_evidence(
+        &payer.pubkey(),
+        &payer.pubkey(),
+        &args.proposal,
+        args.milestone,
+        Attachment {
+            content_type: args.content_type,
+            cid: args.cid,
+            size: args.size,
+            sha256: args.sha256,
+        },
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn submit(
+    ctx: &Context,
+    proposal: &Pubkey,
+    milestone: u8,
+    evidence_hash: String,
+    commit_reveal: bool,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let ix = instructions::submit_milestone(
+        &payer.pubkey(),
+        &payer.pubkey(),
+        proposal,
+        milestone,
+        evidence_hash,
+        commit_reveal,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn vote(
+    ctx: &Context,
+    proposal: &Pubkey,
+    milestone: u8,
+    approved: bool,
+    review: Option<Review>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let ix =
+        instructions::validate_milestone(&payer.pubkey(), proposal, &milestone, approved, review);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn commit(
+    ctx: &Context,
+    proposal: &Pubkey,
+    milestone: u8,
+    approved: bool,
+    salt: [u8; 32],
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let commitment = Vote::commitment(&milestone, &payer.pubkey(), approved, &salt);
+    let ix = instructions::commit_vote(&payer.pubkey(), proposal, &milestone, commitment);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn reveal(
+    ctx: &Context,
+    proposal: &Pubkey,
+    milestone: u8,
+    approved: bool,
+    salt: [u8; 32],
+    review: Option<Review>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let ix = instructions::reveal_vote(
+        &payer.pubkey(),
+        proposal,
+        &milestone,
+        approved,
+        salt,
+        review,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn forfeit(ctx: &Context, proposal: &Pubkey, milestone: u8, validator: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let ix =
+        instructions::penalize_unrevealed_vote(&payer.pubkey(), proposal, &milestone, validator);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn challenge(
+    ctx: &Context,
+    proposal: &Pubkey,
+    milestone: u8,
+    token_account: Option<Pubkey>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let mint = ctx.escrow_mint(proposal)?;
+    let token_account =
+        token_account.unwrap_or_else(|| get_associated_token_address(&payer.pubkey(), &mint));
+    let ix = instructions::challenge_milestone(
+        &payer.pubkey(),
+        proposal,
+        milestone,
+        &mint,
+        &token_account,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+/// The bond goes to the associated token account of the challenger or the
+/// proposal's authority, whichever the outcome favours.
+pub fn resolve(ctx: &Context, proposal: &Pubkey, milestone: u8) -> CliResult {
+    let payer = ctx.signer()?;
+    let state = ctx.proposal(proposal)?;
+    let mint = ctx.escrow_mint(proposal)?;
+    let data = ctx
+        .client
+        .get_account_data(&pda::milestone(proposal, milestone).0)?;
+    let challenger = accounts::decode_milestone(&data)?
+        .challenger
+        .ok_or("milestone was never challenged")?;
+    let ix = instructions::resolve_challenge(
+        &payer.pubkey(),
+        proposal,
+        milestone,
+        &challenger,
+        &get_associated_token_address(&challenger, &mint),
+        &get_associated_token_address(&state.authority, &mint),
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn release(ctx: &Context, proposal: &Pubkey, milestone: u8) -> CliResult {
+    let payer = ctx.signer()?;
+    let authority = payer.pubkey();
+    let state = ctx.proposal(proposal)?;
+    let mint = ctx.escrow_mint(proposal)?;
+
+    // Every member other than the authority is paid into their associated account
+    let members: Vec<Pubkey> = state
+        .team
+        .iter()
+        .filter(|member| member.wallet != authority)
+        .map(|member| get_associated_token_address(&member.wallet, &mint))
+        .collect();
+
+    let ix = instructions::release_funds(
+        &authority,
+        proposal,
+        &pda::milestone(proposal, milestone).0,
+        &get_associated_token_address(&authority, &mint),
+        &members,
+    );
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn penalize(ctx: &Context, proposal: &Pubkey, milestone: u8, treasury: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let ix = instructions::apply_penalty(&payer.pubkey(), proposal, &milestone, treasury);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn refund(
+    ctx: &Context,
+    proposal: &Pubkey,
+    wallet: Option<Pubkey>,
+    token_account: Option<Pubkey>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let wallet = wallet.unwrap_or(payer.pubkey());
+    let token_account = match token_account {
+        Some(account) => account,
+        None => get_associated_token_address(&wallet, &ctx.escrow_mint(proposal)?),
+    };
+    let ix = instructions::withdraw_funds(&wallet, proposal, &token_account);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn cancel(ctx: &Context, proposal: &Pubkey, token_account: Option<Pubkey>) -> CliResult {
+    let payer = ctx.signer()?;
+    let token_account = match token_account {
+        Some(account) => account,
+        None => get_associated_token_address(&payer.pubkey(), &ctx.escrow_mint(proposal)?),
+    };
+    let ix = instructions::cancel_proposal(&payer.pubkey(), proposal, &token_account);
+    ctx.send(&payer, &[ix], &[])
+}
+
+/// Closes every milestone, evidence, vote and contributor account of a
+/// finished proposal that can be closed, rent goes back to whoever paid it.
+pub fn sweep(ctx: &Context, proposal: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let state = ctx.proposal(proposal)?;
+    let numbers: Vec<u8> = (1..=state.milestone_count()).collect();
+
+    // Votes go before their milestone, closing them reads it
+    let mut closables = Vec::new();
+    let milestones: Vec<Pubkey> = numbers
+        .iter()
+        .map(|n| pda::milestone(proposal, *n).0)
+        .collect();
+    for ((number, address), account) in numbers
+        .iter()
+        .zip(&milestones)
+        .zip(ctx.client.get_multiple_accounts(&milestones)?)
+    {
+        let Some(account) = account else {
+            continue;
+        };
+        for (_, vote) in ctx.votes(address)? {
+            // Unrevealed votes are left to `forfeit`
+            if show::is_revealed(&vote) {
+                closables.push(Closable::Vote {
+                    milestone_number: *number,
+                    validator: vote.validator,
+                });
+            }
+        }
+        closables.push(Closable::Milestone {
+            number: *number,
+            payer: accounts::decode_milestone(&account.data)?.rent_payer(&state.authority),
+        });
+    }
+
+    let evidence: Vec<Pubkey> = numbers
+        .iter()
+        .map(|n| pda::evidence(proposal, *n).0)
+        .collect();
+    for (number, account) in numbers
+        .iter()
+        .zip(ctx.client.get_multiple_accounts(&evidence)?)
+    {
+        if let Some(account) = account {
+            closables.push(Closable::Evidence {
+                milestone_number: *number,
+                payer: accounts::decode_milestone_evidence(&account.data)?
+                    .rent_payer(&state.authority),
+            });
+        }
+    }
+
+    // Contributors with a refund to claim keep their account until they do
+    let escrow = ctx.token_account(&pda::escrow(proposal).0)?;
+    let refundable = state.refundable(escrow.amount) > 0;
+    for (_, contributor) in ctx.contributors(proposal)? {
+        if !refundable || contributor.amount == 0 {
+            closables.push(Closable::Contributor {
+                wallet: contributor.wallet,
+                payer: contributor.rent_payer(),
+            });
+        }
+    }
+
+    for batch in closables.chunks(CLOSES_PER_TX) {
+        let ix = instructions::close_accounts(&payer.pubkey(), proposal, batch);
+        ctx.send(&payer, &[ix], &[])?;
+    }
+    println!("Closed {} accounts", closables.len());
+    Ok(())
+}
+
+pub fn mint(ctx: &Context, name: String, symbol: String, uri: String) -> CliResult {
+    let payer = ctx.signer()?;
+    let mint = Keypair::new();
+    let ix = instructions::mint_ip_nft(&payer.pubkey(), &mint.pubkey(), name, symbol, uri);
+    ctx.send(&payer, &[ix], &[&mint])?;
+    println!("mint: {}", mint.pubkey());
+    Ok(())
+}
+
+pub fn close(ctx: &Context, proposal: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let researcher = ctx.proposal(proposal)?.researcher;
+    let ix = instructions::close_proposal(&payer.pubkey(), proposal, &researcher);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn team(
+    ctx: &Context,
+    proposal: &Pubkey,
+    members: &[String],
+    approvers: &[String],
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let members = members
+        .iter()
+        .map(|member| parse_member(member))
+        .collect::<CliResult<Vec<_>>>()?;
+    let approvers = approvers
+        .iter()
+        .map(|path| {
+            read_keypair_file(path).map_err(|err| format!("reading keypair {path}: {err}").into())
+        })
+        .collect::<CliResult<Vec<_>>>()?;
+    let approver_keys: Vec<Pubkey> = approvers.iter().map(|a| a.pubkey()).collect();
+
+    let ix = instructions::update_team(&payer.pubkey(), proposal, members, &approver_keys);
+    ctx.send(&payer, &[ix], &approvers.iter().collect::<Vec<_>>())
+}
+
+pub fn propose_authority(
+    ctx: &Context,
+    proposal: &Pubkey,
+    new_authority: Option<Pubkey>,
+) -> CliResult {
+    let payer = ctx.signer()?;
+    let ix = instructions::propose_authority(&payer.pubkey(), proposal, new_authority);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn accept_authority(ctx: &Context, proposal: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let ix = instructions::accept_authority(&payer.pubkey(), proposal);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn migrate(ctx: &Context, account: &Pubkey) -> CliResult {
+    let payer = ctx.signer()?;
+    let ix = instructions::migrate_account(&payer.pubkey(), account);
+    ctx.send(&payer, &[ix], &[])
+}
+
+pub fn round(ctx: &Context, args: RoundArgs) -> CliResult {
+    let payer = ctx.signer()?;
+    let sponsor = payer.pubkey();
+    let token_account = args
+        .token_account
+        .unwrap_or_else(|| get_associated_token_address(&sponsor, &args.mint));
+    let (round, _) = pda::funding_round(&sponsor, args.id);
+
+    let ix = instructions::create_funding_round(
+        &sponsor,
+        &args.mint,
+        &token_account,
+        instructions::CreateFundingRoundArgs {
+            round_id: args.id,
+            matching_pool: args.matching_pool,
+            starts_at: args.starts_at,
+            ends_at: args.ends_at,
+            proposals: args.proposals,
+        },
+    );
+    ctx.send(&payer, &[ix], &[])?;
+    println!("round: {round}");
+    Ok(())
+}
+
+pub fn distribute(ctx: &Context, round: &Pubkey, token_account: Option<Pubkey>) -> CliResult {
+    let payer = ctx.signer()?;
+    let state = accounts::decode_funding_round(&ctx.client.get_account_data(round)?)?;
+    let token_account =
+        token_account.unwrap_or_else(|| get_associated_token_address(&state.sponsor, &state.mint));
+    let proposals: Vec<Pubkey> = state.entries.iter().map(|entry| entry.proposal).collect();
+
+    let ix = instructions::distribute_matching(round, &token_account, &proposals);
+    ctx.send(&payer, &[ix], &[])
+}
+
+fn parse_member(member: &str) -> CliResult<TeamMember> {
+    let (wallet, share) = member
+        .split_once(':')
+        .ok_or_else(|| format!("expected WALLET:BASIS_POINTS, got {member}"))?;
+    Ok(TeamMember {
+        wallet: Pubkey::from_str(wallet)?,
+        share_bps: share.parse()?,
+    })
+}
+
+fn parse_stretch_goal(goal: &str) -> CliResult<StretchGoal> {
+    let (threshold, budget) = goal
+        .split_once(':')
+        .ok_or_else(|| format!("expected THRESHOLD:BUDGET, got {goal}"))?;
+    Ok(StretchGoal {
+        threshold: threshold.parse()?,
+        budget: budget.parse()?,
+    })
+}
@@ -0,0 +1,529 @@
+mod commands;
+mod output;
+mod show;
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Args, Parser, Subcommand};
+use researchchain_client::{ContentId, ContentType, Review, ReviewScores};
+
+pub type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "researchchain", about = "Operate ResearchChain proposals")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// Keypair that signs and pays, defaults to the Solana CLI keypair
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+
+    /// Print read-only output as JSON instead of tables
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a proposal and deposit its security deposit
+    Create(CreateArgs),
+    /// Contribute tokens to a proposal
+    Fund {
+        proposal: Pubkey,
+        amount: u64,
+        /// Source token account, defaults to the signer's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+        /// Funding round to count the contribution towards
+        #[arg(long)]
+        round: Option<Pubkey>,
+        /// The proposal's allowlist CSV, proves the credited wallet is on it
+        #[arg(long)]
+        allowlist: Option<PathBuf>,
+    },
+    /// Contribute on behalf of another wallet, which gets the receipt and any refund
+    FundFor {
+        proposal: Pubkey,
+        beneficiary: Pubkey,
+        amount: u64,
+        /// Source token account, defaults to the signer's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+        /// Note recorded with the contribution
+        #[arg(long)]
+        memo: Option<String>,
+        /// The proposal's allowlist CSV, proves the credited wallet is on it
+        #[arg(long)]
+        allowlist: Option<PathBuf>,
+    },
+    /// Pledge a contribution every 30 days, paying the first installment now
+    Pledge {
+        proposal: Pubkey,
+        amount: u64,
+        installments: u16,
+        /// Source token account, defaults to the signer's associated account.
+        /// A token account has a single delegate, so use one per pledge.
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+        /// The proposal's allowlist CSV, proves the credited wallet is on it
+        #[arg(long)]
+        allowlist: Option<PathBuf>,
+    },
+    /// Pull a contributor's due pledge installment
+    ExecutePledge {
+        proposal: Pubkey,
+        contributor: Pubkey,
+    },
+    /// Stop your pledge to a proposal, or revoke what an ended one has left
+    CancelPledge { proposal: Pubkey },
+    /// Print the Merkle root of an allowlist CSV of wallet,cap lines
+    Allowlist {
+        csv: PathBuf,
+        /// Also print this wallet's cap and proof
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Attach evidence to the next milestone before submitting it
+    Evidence(EvidenceArgs),
+    /// Submit evidence for the next milestone
+    Submit {
+        proposal: Pubkey,
+        milestone: u8,
+        evidence_hash: String,
+        /// Validators commit hidden votes and reveal them later
+        #[arg(long)]
+        commit_reveal: bool,
+    },
+    /// Vote on a submitted milestone
+    Vote {
+        proposal: Pubkey,
+        milestone: u8,
+        #[arg(long, conflicts_with = "reject", required_unless_present = "reject")]
+        approve: bool,
+        #[arg(long)]
+        reject: bool,
+        #[command(flatten)]
+        review: ReviewArgs,
+    },
+    /// Commit a hidden vote on a commit-reveal milestone
+    Commit(HiddenVoteArgs),
+    /// Reveal a committed vote, with the same choice and salt
+    Reveal {
+        #[command(flatten)]
+        vote: HiddenVoteArgs,
+        #[command(flatten)]
+        review: ReviewArgs,
+    },
+    /// Close the vote of a validator who committed but never revealed
+    Forfeit {
+        proposal: Pubkey,
+        milestone: u8,
+        validator: Pubkey,
+    },
+    /// Release a validated milestone's funds to the team
+    Release { proposal: Pubkey, milestone: u8 },
+    /// Post a bond to dispute a validated milestone during its challenge period
+    Challenge {
+        proposal: Pubkey,
+        milestone: u8,
+        /// Source of the bond, defaults to the signer's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Settle a disputed milestone once the validators' second vote is over
+    Resolve { proposal: Pubkey, milestone: u8 },
+    /// Penalize the researcher for a failed milestone
+    Penalize {
+        proposal: Pubkey,
+        milestone: u8,
+        /// Token account that receives the penalty
+        #[arg(long)]
+        treasury: Pubkey,
+    },
+    /// Withdraw a contributor's refund
+    Refund {
+        proposal: Pubkey,
+        /// Contributor to refund, defaults to the signer
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+        /// Destination token account, defaults to the wallet's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Mint an IP-NFT
+    Mint {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        uri: String,
+    },
+    /// Close a proposal account
+    Close { proposal: Pubkey },
+    /// Reclaim the rent of a finished proposal's milestones, votes and contributors
+    Sweep { proposal: Pubkey },
+    /// Cancel a proposal, forfeiting part of the deposit to the contributors
+    Cancel {
+        proposal: Pubkey,
+        /// Token account for the returned deposit, defaults to the signer's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Replace the team roster
+    Team {
+        proposal: Pubkey,
+        /// New member as WALLET:BASIS_POINTS, repeat for every member
+        #[arg(long = "member", required = true)]
+        members: Vec<String>,
+        /// Keypair files of the current members approving the change
+        #[arg(long = "approver")]
+        approvers: Vec<String>,
+    },
+    /// Offer a proposal's authority to another wallet or multisig
+    ProposeAuthority {
+        proposal: Pubkey,
+        /// New authority, omit to cancel a pending transfer
+        new_authority: Option<Pubkey>,
+    },
+    /// Take over a proposal's authority offered to the signer
+    AcceptAuthority { proposal: Pubkey },
+    /// Upgrade an account to the program's current layout, paying the extra rent
+    Migrate { account: Pubkey },
+    /// Open a quadratic-funding round and deposit its matching pool
+    Round(RoundArgs),
+    /// Pay out a finished round's matching pool
+    Distribute {
+        round: Pubkey,
+        /// Receives the unallocated pool, defaults to the sponsor's associated account
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Show a proposal's state and escrow balance
+    Status { proposal: Pubkey },
+    /// List a proposal's submitted milestones
+    Milestones { proposal: Pubkey },
+    /// List the votes cast on a milestone
+    Votes { proposal: Pubkey, milestone: u8 },
+}
+
+#[derive(Args)]
+struct HiddenVoteArgs {
+    proposal: Pubkey,
+    milestone: u8,
+    #[arg(long, conflicts_with = "reject", required_unless_present = "reject")]
+    approve: bool,
+    #[arg(long)]
+    reject: bool,
+    /// Secret 32-byte salt as hex, keep it until the reveal
+    #[arg(long, value_parser = parse_bytes32)]
+    salt: [u8; 32],
+}
+
+/// A review gives all three scores and the rationale, or nothing.
+#[derive(Args)]
+struct ReviewArgs {
+    /// Reproducibility score from 0 to 10
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(0..=10),
+        requires_all = ["completeness", "quality", "rationale"],
+    )]
+    reproducibility: Option<u8>,
+    /// Completeness score from 0 to 10
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=10), requires = "reproducibility")]
+    completeness: Option<u8>,
+    /// Quality score from 0 to 10
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=10), requires = "reproducibility")]
+    quality: Option<u8>,
+    /// IPFS CID or Arweave transaction id of the written rationale
+    #[arg(long, value_parser = parse_content_id, requires = "reproducibility")]
+    rationale: Option<ContentId>,
+}
+
+impl ReviewArgs {
+    fn review(self) -> Option<Review> {
+        Some(Review {
+            scores: ReviewScores {
+                reproducibility: self.reproducibility?,
+                completeness: self.completeness?,
+                quality: self.quality?,
+            },
+            rationale: self.rationale?,
+        })
+    }
+}
+
+#[derive(Args)]
+struct EvidenceArgs {
+    proposal: Pubkey,
+    milestone: u8,
+    /// report, dataset, code, image or other
+    #[arg(long = "type", value_parser = parse_content_type)]
+    content_type: ContentType,
+    /// IPFS CID or Arweave transaction id of the content
+    #[arg(long, value_parser = parse_content_id)]
+    cid: ContentId,
+    /// Content size in bytes
+    #[arg(long)]
+    size: u64,
+    /// SHA-256 digest of the content as hex
+    #[arg(long, value_parser = parse_bytes32)]
+    sha256: [u8; 32],
+}
+
+fn parse_content_type(name: &str) -> Result<ContentType, String> {
+    match name {
+        "report" => Ok(ContentType::Report),
+        "dataset" => Ok(ContentType::Dataset),
+        "code" => Ok(ContentType::Code),
+        "image" => Ok(ContentType::Image),
+        "other" => Ok(ContentType::Other),
+        _ => Err(format!("unknown content type `{name}`")),
+    }
+}
+
+fn parse_content_id(cid: &str) -> Result<ContentId, String> {
+    ContentId::parse(cid).map_err(|err| match err {
+        anchor_lang::error::Error::AnchorError(err) => err.error_msg,
+        err => err.to_string(),
+    })
+}
+
+fn parse_bytes32(hex: &str) -> Result<[u8; 32], String> {
+    let bytes = hex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("expected hex")?;
+    bytes
+        .try_into()
+        .map_err(|_| "expected 32 bytes, 64 hex digits".to_string())
+}
+
+#[derive(Args)]
+struct CreateArgs {
+    #[arg(long)]
+    title: String,
+    #[arg(long = "abstract")]
+    abstract_text: String,
+    #[arg(long)]
+    ipfs_hash: String,
+    #[arg(long)]
+    amount_asked: u64,
+    #[arg(long)]
+    milestones: u8,
+    #[arg(long)]
+    security_deposit: u64,
+    /// Seconds contributors can challenge a validated milestone for, 0 releases right away
+    #[arg(long, default_value_t = 0)]
+    challenge_period: i64,
+    /// CSV of the wallet,cap pairs allowed to contribute, anyone can if unset
+    #[arg(long)]
+    allowlist: Option<PathBuf>,
+    /// Smallest total a contributor can hold, 0 for no minimum
+    #[arg(long, default_value_t = 0)]
+    min_contribution: u64,
+    /// Largest total a contributor can hold, 0 for no maximum
+    #[arg(long, default_value_t = 0)]
+    max_contribution: u64,
+    /// Trim contributions beyond the amount asked instead of accepting them
+    #[arg(long)]
+    hard_cap: bool,
+    /// Stretch milestone as THRESHOLD:BUDGET, unlocked once the proposal raises THRESHOLD.
+    /// Repeat in threshold order
+    #[arg(long = "stretch")]
+    stretch_goals: Vec<String>,
+    /// Token mint the proposal raises in
+    #[arg(long)]
+    mint: Pubkey,
+    /// Source of the deposit, defaults to the signer's associated account
+    #[arg(long)]
+    token_account: Option<Pubkey>,
+}
+
+#[derive(Args)]
+struct RoundArgs {
+    /// Sponsor's own round number, part of the round address
+    #[arg(long)]
+    id: u64,
+    #[arg(long)]
+    matching_pool: u64,
+    /// Unix time contributions start counting
+    #[arg(long)]
+    starts_at: i64,
+    /// Unix time the round closes
+    #[arg(long)]
+    ends_at: i64,
+    /// Eligible proposal, repeat for every proposal
+    #[arg(long = "proposal", required = true)]
+    proposals: Vec<Pubkey>,
+    /// Token mint of the matching pool
+    #[arg(long)]
+    mint: Pubkey,
+    /// Source of the pool, defaults to the signer's associated account
+    #[arg(long)]
+    token_account: Option<Pubkey>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> CliResult {
+    let ctx = commands::Context::new(cli.url, cli.keypair.as_deref())?;
+
+    match cli.command {
+        Command::Create(args) => commands::create(&ctx, args),
+        Command::Fund {
+            proposal,
+            amount,
+            token_account,
+            round,
+            allowlist,
+        } => commands::fund(&ctx, &proposal, amount, token_account, round, allowlist),
+        Command::FundFor {
+            proposal,
+            beneficiary,
+            amount,
+            token_account,
+            memo,
+            allowlist,
+        } => commands::fund_for(
+            &ctx,
+            &proposal,
+            &beneficiary,
+            amount,
+            token_account,
+            memo,
+            allowlist,
+        ),
+        Command::Pledge {
+            proposal,
+            amount,
+            installments,
+            token_account,
+            allowlist,
+        } => commands::pledge(
+            &ctx,
+            &proposal,
+            amount,
+            installments,
+            token_account,
+            allowlist,
+        ),
+        Command::ExecutePledge {
+            proposal,
+            contributor,
+        } => commands::execute_pledge(&ctx, &proposal, &contributor),
+        Command::CancelPledge { proposal } => commands::cancel_pledge(&ctx, &proposal),
+        Command::Allowlist { csv, wallet } => commands::allowlist(&csv, wallet),
+        Command::Evidence(args) => commands::evidence(&ctx, args),
+        Command::Submit {
+            proposal,
+            milestone,
+            evidence_hash,
+            commit_reveal,
+        } => commands::submit(&ctx, &proposal, milestone, evidence_hash, commit_reveal),
+        Command::Vote {
+            proposal,
+            milestone,
+            approve,
+            review,
+            ..
+        } => commands::vote(&ctx, &proposal, milestone, approve, review.review()),
+        Command::Commit(args) => commands::commit(
+            &ctx,
+            &args.proposal,
+            args.milestone,
+            args.approve,
+            args.salt,
+        ),
+        Command::Reveal { vote, review } => commands::reveal(
+            &ctx,
+            &vote.proposal,
+            vote.milestone,
+            vote.approve,
+            vote.salt,
+            review.review(),
+        ),
+        Command::Forfeit {
+            proposal,
+            milestone,
+            validator,
+        } => commands::forfeit(&ctx, &proposal, milestone, &validator),
+        Command::Release {
+            proposal,
+            milestone,
+        } => commands::release(&ctx, &proposal, milestone),
+        Command::Challenge {
+            proposal,
+            milestone,
+            token_account,
+        } => commands::challenge(&ctx, &proposal, milestone, token_account),
+        Command::Resolve {
+            proposal,
+            milestone,
+        } => commands::resolve(&ctx, &proposal, milestone),
+        Command::Penalize {
+            proposal,
+            milestone,
+            treasury,
+        } => commands::penalize(&ctx, &proposal, milestone, &treasury),
+        Command::Refund {
+            proposal,
+            wallet,
+            token_account,
+        } => commands::refund(&ctx, &proposal, wallet, token_account),
+        Command::Mint { name, symbol, uri } => commands::mint(&ctx, name, symbol, uri),
+        Command::Close { proposal } => commands::close(&ctx, &proposal),
+        Command::Sweep { proposal } => commands::sweep(&ctx, &proposal),
+        Command::Cancel {
+            proposal,
+            token_account,
+        } => commands::cancel(&ctx, &proposal, token_account),
+        Command::Team {
+            proposal,
+            members,
+            approvers,
+        } => commands::team(&ctx, &proposal, &members, &approvers),
+        Command::ProposeAuthority {
+            proposal,
+            new_authority,
+        } => commands::propose_authority(&ctx, &proposal, new_authority),
+        Command::AcceptAuthority { proposal } => commands::accept_authority(&ctx, &proposal),
+        Command::Migrate { account } => commands::migrate(&ctx, &account),
+        Command::Round(args) => commands::round(&ctx, args),
+        Command::Distribute {
+            round,
+            token_account,
+        } => commands::distribute(&ctx, &round, token_account),
+        Command::Status { proposal } => show::status(&ctx, &proposal, cli.json),
+        Command::Milestones { proposal } => show::milestones(&ctx, &proposal, cli.json),
+        Command::Votes {
+            proposal,
+            milestone,
+        } => show::votes(&ctx, &proposal, milestone, cli.json),
+    }
+}
@@ -0,0 +1,312 @@
+use anchor_lang::prelude::Pubkey;
+use researchchain_client::accounts::{self, milestone_status_name, proposal_status_name};
+use researchchain_client::{
+    pda, ContentId, ContributionLimits, Milestone, Proposal, ReviewScores, Vote,
+};
+use serde_json::{json, Value};
+
+use crate::{commands::Context, output, CliResult};
+
+pub fn status(ctx: &Context, proposal: &Pubkey, json: bool) -> CliResult {
+    let state = ctx.proposal(proposal)?;
+    let (escrow, _) = pda::escrow(proposal);
+    let escrow_balance = ctx.token_account(&escrow)?.amount;
+
+    if json {
+        let team: Vec<Value> = state
+            .team
+            .iter()
+            .map(|m| json!({ "wallet": m.wallet.to_string(), "share_bps": m.share_bps }))
+            .collect();
+        let stretch_goals: Vec<Value> = state
+            .stretch_goals
+            .iter()
+            .enumerate()
+            .map(|(index, goal)| {
+                json!({
+                    "threshold": goal.threshold,
+                    "budget": goal.budget,
+                    "unlocked": index < state.stretch_goals_unlocked as usize,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "address": proposal.to_string(),
+                "title": state.title,
+                "researcher": state.researcher.to_string(),
+                "authority": state.authority.to_string(),
+                "pending_authority": state.pending_authority.map(|key| key.to_string()),
+                "status": proposal_status_name(&state.status),
+                "amount_asked": state.amount_asked,
+                "amount_raised": state.amount_raised,
+                "escrow": escrow.to_string(),
+                "escrow_balance": escrow_balance,
+                "contributors_count": state.contributors_count,
+                "current_milestone": state.current_milestone,
+                "total_milestones": state.total_milestones,
+                "initial_security_deposit": state.initial_security_deposit,
+                "current_security_deposit": state.current_security_deposit,
+                "penalty_counter": state.penalty_counter,
+                "challenge_period": state.challenge_period,
+                "allowlist_root": state.allowlist_root.map(|root| output::hex(&root)),
+                "min_contribution": state.contribution_limits.min,
+                "max_contribution": state.contribution_limits.max,
+                "hard_cap": state.contribution_limits.hard_cap,
+                "stretch_goals": stretch_goals,
+                "ipfs_hash": state.ipfs_hash.as_ref().map(ToString::to_string),
+                "team": team,
+                "created_at": state.created_at,
+                "updated_at": state.updated_at,
+            }))?
+        );
+        return Ok(());
+    }
+
+    output::fields(&[
+        ("Proposal", proposal.to_string()),
+        ("Title", state.title.clone()),
+        ("Researcher", state.researcher.to_string()),
+        ("Authority", state.authority.to_string()),
+        (
+            "Pending authority",
+            state
+                .pending_authority
+                .map_or("-".to_string(), |key| key.to_string()),
+        ),
+        ("Status", proposal_status_name(&state.status).to_string()),
+        (
+            "Raised",
+            format!("{} / {}", state.amount_raised, state.amount_asked),
+        ),
+        ("Escrow", format!("{escrow} ({escrow_balance})")),
+        ("Contributors", state.contributors_count.to_string()),
+        (
+            "Milestones",
+            format!("{} / {}", state.current_milestone, state.milestone_count()),
+        ),
+        ("Stretch goals", stretch_goals(&state)),
+        (
+            "Security deposit",
+            format!(
+                "{} / {}",
+                state.current_security_deposit, state.initial_security_deposit
+            ),
+        ),
+        ("Penalties", state.penalty_counter.to_string()),
+        ("Challenge period", format!("{}s", state.challenge_period)),
+        (
+            "Allowlist root",
+            state
+                .allowlist_root
+                .map_or("-".to_string(), |root| output::hex(&root)),
+        ),
+        (
+            "Contribution limits",
+            contribution_limits(&state.contribution_limits),
+        ),
+        ("IPFS", content_id(&state.ipfs_hash)),
+    ]);
+    println!();
+    output::table(
+        &["MEMBER", "SHARE (BPS)"],
+        &state
+            .team
+            .iter()
+            .map(|m| vec![m.wallet.to_string(), m.share_bps.to_string()])
+            .collect::<Vec<_>>(),
+    );
+    Ok(())
+}
+
+pub fn milestones(ctx: &Context, proposal: &Pubkey, json: bool) -> CliResult {
+    let state = ctx.proposal(proposal)?;
+    let addresses: Vec<Pubkey> = (1..=state.current_milestone)
+        .map(|n| pda::milestone(proposal, n).0)
+        .collect();
+
+    let mut milestones: Vec<(Pubkey, Milestone)> = Vec::new();
+    for (address, account) in addresses
+        .iter()
+        .zip(ctx.client.get_multiple_accounts(&addresses)?)
+    {
+        if let Some(account) = account {
+            milestones.push((*address, accounts::decode_milestone(&account.data)?));
+        }
+    }
+
+    if json {
+        let list: Vec<Value> = milestones
+            .iter()
+            .map(|(address, m)| {
+                json!({
+                    "address": address.to_string(),
+                    "milestone_number": m.milestone_number,
+                    "status": milestone_status_name(&m.status),
+                    "validation_votes": m.validation_votes,
+                    "rejection_votes": m.rejection_votes,
+                    "total_validators": m.total_validators,
+                    "funds_released": m.funds_released,
+                    "evidence_hash": m.evidence_hash.as_ref().map(ToString::to_string),
+                    "deadline": m.deadline,
+                    "validated_at": m.validated_at,
+                    "challenger": m.challenger.map(|c| c.to_string()),
+                    "reviews": m.reviews,
+                    "average_scores": (m.reviews > 0).then(|| {
+                        let totals = &m.review_totals;
+                        json!({
+                            "reproducibility": average(totals.reproducibility, m.reviews),
+                            "completeness": average(totals.completeness, m.reviews),
+                            "quality": average(totals.quality, m.reviews),
+                        })
+                    }),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&list)?);
+        return Ok(());
+    }
+
+    output::table(
+        &[
+            "#", "STATUS", "FOR", "AGAINST", "RELEASED", "DEADLINE", "SCORES", "EVIDENCE",
+        ],
+        &milestones
+            .iter()
+            .map(|(_, m)| {
+                vec![
+                    m.milestone_number.to_string(),
+                    milestone_status_name(&m.status).to_string(),
+                    format!("{}/{}", m.validation_votes, m.total_validators),
+                    format!("{}/{}", m.rejection_votes, m.total_validators),
+                    m.funds_released.to_string(),
+                    m.deadline.to_string(),
+                    average_scores(m),
+                    content_id(&m.evidence_hash),
+                ]
+            })
+            .collect::<Vec<_>>(),
+    );
+    Ok(())
+}
+
+pub fn votes(ctx: &Context, proposal: &Pubkey, milestone: u8, json: bool) -> CliResult {
+    let (milestone, _) = pda::milestone(proposal, milestone);
+    let mut votes = ctx.votes(&milestone)?;
+    votes.sort_by_key(|(_, vote)| vote.voted_at);
+
+    if json {
+        let list: Vec<Value> = votes
+            .iter()
+            .map(|(address, v)| {
+                json!({
+                    "address": address.to_string(),
+                    "validator": v.validator.to_string(),
+                    "approved": is_revealed(v).then_some(v.approved),
+                    "voted_at": v.voted_at,
+                    "review": v.review.as_ref().map(|review| json!({
+                        "reproducibility": review.scores.reproducibility,
+                        "completeness": review.scores.completeness,
+                        "quality": review.scores.quality,
+                        "rationale": review.rationale.to_string(),
+                    })),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&list)?);
+        return Ok(());
+    }
+
+    output::table(
+        &["VALIDATOR", "APPROVED", "VOTED AT", "SCORES", "RATIONALE"],
+        &votes
+            .iter()
+            .map(|(_, v)| {
+                vec![
+                    v.validator.to_string(),
+                    if is_revealed(v) {
+                        v.approved.to_string()
+                    } else {
+                        "hidden".to_string()
+                    },
+                    v.voted_at.to_string(),
+                    v.review
+                        .as_ref()
+                        .map_or("-".to_string(), |review| scores(&review.scores)),
+                    v.review
+                        .as_ref()
+                        .map_or("-".to_string(), |review| review.rationale.to_string()),
+                ]
+            })
+            .collect::<Vec<_>>(),
+    );
+    Ok(())
+}
+
+/// Reproducibility, completeness and quality, out of 10.
+fn scores(scores: &ReviewScores) -> String {
+    format!(
+        "{}/{}/{}",
+        scores.reproducibility, scores.completeness, scores.quality
+    )
+}
+
+fn average(total: u32, reviews: u32) -> f64 {
+    total as f64 / reviews as f64
+}
+
+/// Averages over the milestone's reviews in the order of [`scores`].
+fn average_scores(milestone: &Milestone) -> String {
+    if milestone.reviews == 0 {
+        return "-".to_string();
+    }
+    let totals = &milestone.review_totals;
+    format!(
+        "{:.1}/{:.1}/{:.1}",
+        average(totals.reproducibility, milestone.reviews),
+        average(totals.completeness, milestone.reviews),
+        average(totals.quality, milestone.reviews)
+    )
+}
+
+/// Commit-reveal votes don't count until they are revealed.
+pub fn is_revealed(vote: &Vote) -> bool {
+    vote.commitment == [0; 32] || vote.revealed
+}
+
+/// Legacy hashes that never parsed as a CID show as "-".
+fn content_id(id: &Option<ContentId>) -> String {
+    id.as_ref()
+        .map_or_else(|| "-".to_string(), ToString::to_string)
+}
+
+fn contribution_limits(limits: &ContributionLimits) -> String {
+    let bound = |value: u64| match value {
+        0 => "-".to_string(),
+        value => value.to_string(),
+    };
+    let cap = if limits.hard_cap { ", hard cap" } else { "" };
+    format!("{} to {}{cap}", bound(limits.min), bound(limits.max))
+}
+
+/// Budget and threshold of every stretch goal, "-" without any.
+fn stretch_goals(proposal: &Proposal) -> String {
+    if proposal.stretch_goals.is_empty() {
+        return "-".to_string();
+    }
+    proposal
+        .stretch_goals
+        .iter()
+        .enumerate()
+        .map(|(index, goal)| {
+            let unlocked = if index < proposal.stretch_goals_unlocked as usize {
+                " (unlocked)"
+            } else {
+                ""
+            };
+            format!("{} at {}{unlocked}", goal.budget, goal.threshold)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
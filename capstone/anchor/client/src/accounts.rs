@@ -0,0 +1,122 @@
+//! Decoders for the accounts owned by the ResearchChain program.
+
+use anchor::{
+    ContentType, Contributor, FundingRound, Milestone, MilestoneEvidence, MilestoneStatus, Pledge,
+    PledgeEnd, Proposal, ProposalStatus, RoundContribution, Vote,
+};
+use anchor_lang::{AccountDeserialize, Discriminator};
+
+/// Decodes raw account data, discriminator included, into `T`.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> anchor_lang::Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_proposal(data: &[u8]) -> anchor_lang::Result<Proposal> {
+    decode(data)
+}
+
+pub fn decode_milestone(data: &[u8]) -> anchor_lang::Result<Milestone> {
+    decode(data)
+}
+
+pub fn decode_contributor(data: &[u8]) -> anchor_lang::Result<Contributor> {
+    decode(data)
+}
+
+pub fn decode_vote(data: &[u8]) -> anchor_lang::Result<Vote> {
+    decode(data)
+}
+
+pub fn decode_funding_round(data: &[u8]) -> anchor_lang::Result<FundingRound> {
+    decode(data)
+}
+
+pub fn decode_round_contribution(data: &[u8]) -> anchor_lang::Result<RoundContribution> {
+    decode(data)
+}
+
+pub fn decode_milestone_evidence(data: &[u8]) -> anchor_lang::Result<MilestoneEvidence> {
+    decode(data)
+}
+
+pub fn decode_pledge(data: &[u8]) -> anchor_lang::Result<Pledge> {
+    decode(data)
+}
+
+/// Any account type owned by the program, selected by its discriminator.
+// Decoded accounts are short-lived, boxing the proposal isn't worth it
+#[allow(clippy::large_enum_variant)]
+pub enum ProgramAccount {
+    Proposal(Proposal),
+    Milestone(Milestone),
+    Contributor(Contributor),
+    Vote(Vote),
+    FundingRound(FundingRound),
+    RoundContribution(RoundContribution),
+    MilestoneEvidence(MilestoneEvidence),
+    Pledge(Pledge),
+}
+
+impl ProgramAccount {
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        if discriminator == Proposal::DISCRIMINATOR {
+            decode(data).ok().map(Self::Proposal)
+        } else if discriminator == Milestone::DISCRIMINATOR {
+            decode(data).ok().map(Self::Milestone)
+        } else if discriminator == Contributor::DISCRIMINATOR {
+            decode(data).ok().map(Self::Contributor)
+        } else if discriminator == Vote::DISCRIMINATOR {
+            decode(data).ok().map(Self::Vote)
+        } else if discriminator == FundingRound::DISCRIMINATOR {
+            decode(data).ok().map(Self::FundingRound)
+        } else if discriminator == RoundContribution::DISCRIMINATOR {
+            decode(data).ok().map(Self::RoundContribution)
+        } else if discriminator == MilestoneEvidence::DISCRIMINATOR {
+            decode(data).ok().map(Self::MilestoneEvidence)
+        } else if discriminator == Pledge::DISCRIMINATOR {
+            decode(data).ok().map(Self::Pledge)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn proposal_status_name(status: &ProposalStatus) -> &'static str {
+    match status {
+        ProposalStatus::Draft => "Draft",
+        ProposalStatus::Pending => "Pending",
+        ProposalStatus::Active => "Active",
+        ProposalStatus::Completed => "Completed",
+        ProposalStatus::Failed => "Failed",
+    }
+}
+
+pub fn milestone_status_name(status: &MilestoneStatus) -> &'static str {
+    match status {
+        MilestoneStatus::Pending => "Pending",
+        MilestoneStatus::Active => "Active",
+        MilestoneStatus::Validated => "Validated",
+        MilestoneStatus::Failed => "Failed",
+        MilestoneStatus::Disputed => "Disputed",
+    }
+}
+
+pub fn content_type_name(content_type: &ContentType) -> &'static str {
+    match content_type {
+        ContentType::Report => "Report",
+        ContentType::Dataset => "Dataset",
+        ContentType::Code => "Code",
+        ContentType::Image => "Image",
+        ContentType::Other => "Other",
+    }
+}
+
+pub fn pledge_end_name(end: &PledgeEnd) -> &'static str {
+    match end {
+        PledgeEnd::Completed => "Completed",
+        PledgeEnd::ProposalInactive => "ProposalInactive",
+        PledgeEnd::Cancelled => "Cancelled",
+        PledgeEnd::GoalReached => "GoalReached",
+    }
+}
@@ -0,0 +1,141 @@
+//! Contributor allowlists for proposals created with an `allowlist_root`.
+//!
+//! An [`Allowlist`] is built from the wallets allowed to contribute and their
+//! caps, usually read from a CSV with one `wallet,cap` pair per line. Its
+//! [`root`](Allowlist::root) goes into the proposal, each contributor then
+//! funds with their own [`proof`](Allowlist::proof). Hashing follows
+//! [`anchor::allowlist`], so the program accepts every proof built here.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anchor::allowlist::{allowlist_leaf, allowlist_node};
+use anchor::AllowlistProof;
+use anchor_lang::prelude::Pubkey;
+
+/// Why an allowlist couldn't be built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AllowlistError {
+    /// A CSV line that isn't `wallet,cap`, numbered from 1.
+    InvalidLine {
+        line: usize,
+        reason: String,
+    },
+    DuplicateWallet(Pubkey),
+    Empty,
+}
+
+impl std::fmt::Display for AllowlistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLine { line, reason } => write!(f, "line {line}: {reason}"),
+            Self::DuplicateWallet(wallet) => write!(f, "{wallet} is listed twice"),
+            Self::Empty => write!(f, "allowlist has no wallets"),
+        }
+    }
+}
+
+impl std::error::Error for AllowlistError {}
+
+/// Merkle tree of allowed wallets and their caps.
+pub struct Allowlist {
+    entries: Vec<(Pubkey, u64)>,
+    // Leaves first, sorted so the root doesn't depend on the input order.
+    // A node without a sibling moves up unchanged.
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl Allowlist {
+    pub fn new(entries: impl IntoIterator<Item = (Pubkey, u64)>) -> Result<Self, AllowlistError> {
+        let entries: Vec<_> = entries.into_iter().collect();
+        let mut seen = HashSet::new();
+        if let Some((wallet, _)) = entries.iter().find(|(wallet, _)| !seen.insert(*wallet)) {
+            return Err(AllowlistError::DuplicateWallet(*wallet));
+        }
+
+        let mut leaves: Vec<_> = entries
+            .iter()
+            .map(|(wallet, cap)| allowlist_leaf(wallet, *cap))
+            .collect();
+        if leaves.is_empty() {
+            return Err(AllowlistError::Empty);
+        }
+        leaves.sort_unstable();
+
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let next = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => allowlist_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Ok(Self { entries, layers })
+    }
+
+    /// Reads `wallet,cap` lines. Blank lines, `#` comments and a leading
+    /// `wallet,cap` header are skipped.
+    pub fn from_csv(csv: &str) -> Result<Self, AllowlistError> {
+        let mut entries = Vec::new();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: String| AllowlistError::InvalidLine {
+                line: index + 1,
+                reason,
+            };
+            let (wallet, cap) = line
+                .split_once(',')
+                .ok_or_else(|| invalid("expected wallet,cap".to_string()))?;
+            let (wallet, cap) = (wallet.trim(), cap.trim());
+            if entries.is_empty() && wallet.eq_ignore_ascii_case("wallet") {
+                continue;
+            }
+            let wallet =
+                Pubkey::from_str(wallet).map_err(|err| invalid(format!("{wallet}: {err}")))?;
+            let cap = cap
+                .parse()
+                .map_err(|err| invalid(format!("{cap}: {err}")))?;
+            entries.push((wallet, cap));
+        }
+        Self::new(entries)
+    }
+
+    /// Goes into the proposal's `allowlist_root`.
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().expect("at least one leaf")[0]
+    }
+
+    pub fn entries(&self) -> &[(Pubkey, u64)] {
+        &self.entries
+    }
+
+    pub fn cap(&self, wallet: &Pubkey) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry == wallet)
+            .map(|(_, cap)| *cap)
+    }
+
+    /// What `wallet` passes when funding, `None` if it isn't on the list.
+    pub fn proof(&self, wallet: &Pubkey) -> Option<AllowlistProof> {
+        let cap = self.cap(wallet)?;
+        let leaf = allowlist_leaf(wallet, cap);
+        let mut index = self.layers[0].binary_search(&leaf).ok()?;
+
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(AllowlistProof { cap, proof })
+    }
+}
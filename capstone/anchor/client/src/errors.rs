@@ -0,0 +1,59 @@
+//! Decoding of the program's error codes.
+//!
+//! The program returns its errors as custom instruction error codes, see
+//! [`anchor::error`] for the numbering. Codes below 6000 come from Anchor
+//! itself, e.g. failed account constraints.
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::instruction::InstructionError;
+
+pub use anchor::error::ErrorCode;
+
+/// A custom error code returned by the program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramError {
+    /// One of the program's own errors.
+    Program(ErrorCode),
+    /// An error raised by the Anchor framework, e.g. a failed constraint.
+    Framework(u32),
+    /// A code from the program's range that this SDK doesn't know yet.
+    Unknown(u32),
+}
+
+impl ProgramError {
+    pub fn from_code(code: u32) -> Self {
+        if code < ERROR_CODE_OFFSET {
+            return Self::Framework(code);
+        }
+        ErrorCode::from_code(code)
+            .map(Self::Program)
+            .unwrap_or(Self::Unknown(code))
+    }
+
+    /// Decodes an instruction error, `None` unless it carries a custom code.
+    pub fn from_instruction_error(error: &InstructionError) -> Option<Self> {
+        match error {
+            InstructionError::Custom(code) => Some(Self::from_code(*code)),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Program(error) => error.code(),
+            Self::Framework(code) | Self::Unknown(code) => *code,
+        }
+    }
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Program(error) => write!(f, "{} ({}): {error}", error.name(), error.code()),
+            Self::Framework(code) => write!(f, "Anchor error {code}"),
+            Self::Unknown(code) => write!(f, "Unknown program error {code}"),
+        }
+    }
+}
+
+impl std::error::Error for ProgramError {}
@@ -0,0 +1,876 @@
+//! Typed builders for every ResearchChain instruction.
+//!
+//! Builders derive all program addresses themselves, callers only pass the
+//! wallets, mints and token accounts that cannot be derived.
+
+use anchor::{
+    accounts, instruction, AllowlistProof, Attachment, ContributionLimits, Review, StretchGoal,
+    TeamMember,
+};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+use crate::{pda, PROGRAM_ID};
+
+fn build(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: impl IntoIterator<Item = AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining_accounts);
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+/// Arguments of [`create_proposal`].
+#[derive(Clone, Debug)]
+pub struct CreateProposalArgs {
+    pub title: String,
+    pub abstract_text: String,
+    pub ipfs_hash: String,
+    pub amount_asked: u64,
+    pub total_milestones: u8,
+    pub security_deposit: u64,
+    /// Seconds contributors can challenge a validated milestone for.
+    pub challenge_period: i64,
+    /// Root of the contributor allowlist, see [`crate::allowlist`].
+    pub allowlist_root: Option<[u8; 32]>,
+    /// Per-contributor bounds and the hard cap, all unset by default.
+    pub contribution_limits: ContributionLimits,
+    /// Milestones unlocked by raising past `amount_asked`, in threshold order.
+    pub stretch_goals: Vec<StretchGoal>,
+}
+
+pub fn create_proposal(
+    researcher: &Pubkey,
+    mint: &Pubkey,
+    researcher_token_account: &Pubkey,
+    args: CreateProposalArgs,
+) -> Instruction {
+    let (proposal, _) = pda::proposal(researcher, &args.title);
+    build(
+        accounts::CreateProposal {
+            researcher: *researcher,
+            proposal,
+            escrow: pda::escrow(&proposal).0,
+            mint: *mint,
+            researcher_token_account: *researcher_token_account,
+            system_program: system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateProposal {
+            title: args.title,
+            abstract_text: args.abstract_text,
+            ipfs_hash: args.ipfs_hash,
+            amount_asked: args.amount_asked,
+            total_milestones: args.total_milestones,
+            security_deposit: args.security_deposit,
+            challenge_period: args.challenge_period,
+            allowlist_root: args.allowlist_root,
+            contribution_limits: args.contribution_limits,
+            stretch_goals: args.stretch_goals,
+        },
+        [],
+    )
+}
+
+pub fn fund_proposal(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    contributor_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    fund(
+        contributor,
+        proposal,
+        mint,
+        contributor_token_account,
+        amount,
+        None,
+        None,
+    )
+}
+
+/// Contribution to a proposal with an allowlist, optionally counted towards
+/// a funding round like [`fund_proposal_in_round`].
+pub fn fund_proposal_allowlisted(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    contributor_token_account: &Pubkey,
+    amount: u64,
+    funding_round: Option<&Pubkey>,
+    allowance: AllowlistProof,
+) -> Instruction {
+    fund(
+        contributor,
+        proposal,
+        mint,
+        contributor_token_account,
+        amount,
+        funding_round,
+        Some(allowance),
+    )
+}
+
+/// Like [`fund_proposal`], also counting the contribution towards a
+/// quadratic-funding round the proposal is part of.
+pub fn fund_proposal_in_round(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    contributor_token_account: &Pubkey,
+    amount: u64,
+    funding_round: &Pubkey,
+) -> Instruction {
+    fund(
+        contributor,
+        proposal,
+        mint,
+        contributor_token_account,
+        amount,
+        Some(funding_round),
+        None,
+    )
+}
+
+fn fund(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    contributor_token_account: &Pubkey,
+    amount: u64,
+    funding_round: Option<&Pubkey>,
+    allowance: Option<AllowlistProof>,
+) -> Instruction {
+    let contributor_account = pda::contributor(contributor, proposal).0;
+    let receipt_mint = pda::receipt(&contributor_account).0;
+    build(
+        accounts::FundProposal {
+            contributor: *contributor,
+            proposal: *proposal,
+            contributor_account,
+            escrow: pda::escrow(proposal).0,
+            mint: *mint,
+            contributor_token_account: *contributor_token_account,
+            receipt_mint,
+            receipt_token_account: pda::receipt_token_account(contributor, &receipt_mint),
+            funding_round: funding_round.copied(),
+            round_contribution: funding_round
+                .map(|round| pda::round_contribution(round, &contributor_account).0),
+            token_program: anchor_spl::token::ID,
+            token_2022_program: anchor_spl::token_2022::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::FundProposal { amount, allowance },
+        [],
+    )
+}
+
+/// Contribution paid by `sponsor` and credited to `beneficiary`, who holds
+/// the refund rights and the receipt.
+#[allow(clippy::too_many_arguments)]
+pub fn fund_proposal_for(
+    sponsor: &Pubkey,
+    beneficiary: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    sponsor_token_account: &Pubkey,
+    amount: u64,
+    memo: Option<String>,
+    allowance: Option<AllowlistProof>,
+) -> Instruction {
+    let contributor_account = pda::contributor(beneficiary, proposal).0;
+    let receipt_mint = pda::receipt(&contributor_account).0;
+    build(
+        accounts::FundProposalFor {
+            sponsor: *sponsor,
+            beneficiary: *beneficiary,
+            proposal: *proposal,
+            contributor_account,
+            escrow: pda::escrow(proposal).0,
+            mint: *mint,
+            sponsor_token_account: *sponsor_token_account,
+            receipt_mint,
+            receipt_token_account: pda::receipt_token_account(beneficiary, &receipt_mint),
+            token_program: anchor_spl::token::ID,
+            token_2022_program: anchor_spl::token_2022::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::FundProposalFor {
+            amount,
+            memo,
+            allowance,
+        },
+        [],
+    )
+}
+
+/// Pays the first of `installments` and lets the pledge pull the others from
+/// `contributor_token_account`, which should back no other pledge.
+pub fn create_pledge(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    mint: &Pubkey,
+    contributor_token_account: &Pubkey,
+    amount: u64,
+    installments: u16,
+    allowance: Option<AllowlistProof>,
+) -> Instruction {
+    let contributor_account = pda::contributor(contributor, proposal).0;
+    let receipt_mint = pda::receipt(&contributor_account).0;
+    build(
+        accounts::CreatePledge {
+            contributor: *contributor,
+            proposal: *proposal,
+            pledge: pda::pledge(contributor, proposal).0,
+            contributor_account,
+            escrow: pda::escrow(proposal).0,
+            mint: *mint,
+            contributor_token_account: *contributor_token_account,
+            receipt_mint,
+            receipt_token_account: pda::receipt_token_account(contributor, &receipt_mint),
+            token_program: anchor_spl::token::ID,
+            token_2022_program: anchor_spl::token_2022::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreatePledge {
+            amount,
+            installments,
+            allowance,
+        },
+        [],
+    )
+}
+
+/// `token_account` is the pledge's, see [`crate::Pledge::token_account`].
+pub fn execute_pledge(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    contributor: &Pubkey,
+    token_account: &Pubkey,
+) -> Instruction {
+    let contributor_account = pda::contributor(contributor, proposal).0;
+    let receipt_mint = pda::receipt(&contributor_account).0;
+    build(
+        accounts::ExecutePledge {
+            cranker: *cranker,
+            proposal: *proposal,
+            pledge: pda::pledge(contributor, proposal).0,
+            contributor: *contributor,
+            contributor_account,
+            escrow: pda::escrow(proposal).0,
+            token_account: *token_account,
+            receipt_mint,
+            receipt_token_account: pda::receipt_token_account(contributor, &receipt_mint),
+            token_program: anchor_spl::token::ID,
+            token_2022_program: anchor_spl::token_2022::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExecutePledge {},
+        [],
+    )
+}
+
+pub fn cancel_pledge(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CancelPledge {
+            contributor: *contributor,
+            pledge: pda::pledge(contributor, proposal).0,
+            token_account: *token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::CancelPledge {},
+        [],
+    )
+}
+
+/// Appends to the evidence of the milestone that is submitted next.
+/// `payer` covers the rent, separate from `authority` for multisig PDAs.
+pub fn add_evidence(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    attachment: Attachment,
+) -> Instruction {
+    build(
+        accounts::AddEvidence {
+            authority: *authority,
+            payer: *payer,
+            proposal: *proposal,
+            evidence: pda::evidence(proposal, milestone_number).0,
+            system_program: system_program::ID,
+        },
+        instruction::AddEvidence {
+            milestone_number,
+            attachment,
+        },
+        [],
+    )
+}
+
+pub fn submit_milestone(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    evidence_hash: String,
+    commit_reveal: bool,
+) -> Instruction {
+    build(
+        accounts::SubmitMilestone {
+            authority: *authority,
+            payer: *payer,
+            proposal: *proposal,
+            milestone: pda::milestone(proposal, milestone_number).0,
+            evidence: pda::evidence(proposal, milestone_number).0,
+            system_program: system_program::ID,
+        },
+        instruction::SubmitMilestone {
+            milestone_number,
+            evidence_hash,
+            commit_reveal,
+        },
+        [],
+    )
+}
+
+/// Casts an open vote, `review` adds scores and a link to the rationale.
+pub fn validate_milestone(
+    validator: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    approved: bool,
+    review: Option<Review>,
+) -> Instruction {
+    build(
+        accounts::ValidateMilestone {
+            validator: *validator,
+            milestone: *milestone,
+            proposal: *proposal,
+            vote: pda::vote(validator, milestone).0,
+            system_program: system_program::ID,
+        },
+        instruction::ValidateMilestone { approved, review },
+        [],
+    )
+}
+
+/// Commits to a hidden vote, `commitment` is [`Vote::commitment`] of the
+/// vote and a secret salt the validator keeps until the reveal.
+///
+/// [`Vote::commitment`]: anchor::Vote::commitment
+pub fn commit_vote(
+    validator: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    commitment: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::CommitVote {
+            validator: *validator,
+            milestone: *milestone,
+            proposal: *proposal,
+            vote: pda::vote(validator, milestone).0,
+            system_program: system_program::ID,
+        },
+        instruction::CommitVote { commitment },
+        [],
+    )
+}
+
+pub fn reveal_vote(
+    validator: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    approved: bool,
+    salt: [u8; 32],
+    review: Option<Review>,
+) -> Instruction {
+    build(
+        accounts::RevealVote {
+            validator: *validator,
+            milestone: *milestone,
+            proposal: *proposal,
+            vote: pda::vote(validator, milestone).0,
+        },
+        instruction::RevealVote {
+            approved,
+            salt,
+            review,
+        },
+        [],
+    )
+}
+
+/// Closes the vote of a `validator` who never revealed, paying its rent to
+/// `cranker`.
+pub fn penalize_unrevealed_vote(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    validator: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::PenalizeUnrevealedVote {
+            cranker: *cranker,
+            milestone: *milestone,
+            proposal: *proposal,
+            vote: pda::vote(validator, milestone).0,
+        },
+        instruction::PenalizeUnrevealedVote {},
+        [],
+    )
+}
+
+/// `member_token_accounts` are the token accounts of every team member other
+/// than the authority, in roster order.
+pub fn release_funds(
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    authority_token_account: &Pubkey,
+    member_token_accounts: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::ReleaseFunds {
+            authority: *authority,
+            proposal: *proposal,
+            escrow: pda::escrow(proposal).0,
+            milestone: *milestone,
+            authority_token_account: *authority_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ReleaseFunds {},
+        member_token_accounts
+            .iter()
+            .map(|account| AccountMeta::new(*account, false)),
+    )
+}
+
+pub fn apply_penalty(
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    milestone: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ApplyPenalty {
+            authority: *authority,
+            proposal: *proposal,
+            milestone: *milestone,
+            escrow: pda::escrow(proposal).0,
+            treasury: *treasury,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ApplyPenalty {},
+        [],
+    )
+}
+
+/// `contributor` doesn't sign, anyone can send a wallet its refund.
+pub fn withdraw_funds(
+    contributor: &Pubkey,
+    proposal: &Pubkey,
+    contributor_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::WithdrawFunds {
+            contributor: *contributor,
+            contributor_account: pda::contributor(contributor, proposal).0,
+            proposal: *proposal,
+            escrow: pda::escrow(proposal).0,
+            contributor_token_account: *contributor_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawFunds {},
+        [],
+    )
+}
+
+/// The deposit left after the penalty goes to `authority_token_account`.
+pub fn cancel_proposal(
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    authority_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CancelProposal {
+            authority: *authority,
+            proposal: *proposal,
+            escrow: pda::escrow(proposal).0,
+            authority_token_account: *authority_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::CancelProposal {},
+        [],
+    )
+}
+
+/// `mint` is a fresh keypair that must also sign the transaction.
+pub fn mint_ip_nft(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    name: String,
+    symbol: String,
+    metadata_uri: String,
+) -> Instruction {
+    build(
+        accounts::MintNft {
+            payer: *payer,
+            mint: *mint,
+            metadata: pda::metadata(mint).0,
+            master_edition: pda::master_edition(mint).0,
+            token_program: anchor_spl::token::ID,
+            metadata_program: mpl_token_metadata::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            sysvar_instructions: sysvar::instructions::ID,
+        },
+        instruction::MintIpNft {
+            name,
+            symbol,
+            metadata_uri,
+        },
+        [],
+    )
+}
+
+/// Rent goes back to `researcher`, who paid it when creating the proposal.
+pub fn close_proposal(authority: &Pubkey, proposal: &Pubkey, researcher: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseProposal {
+            proposal: *proposal,
+            authority: *authority,
+            researcher: *researcher,
+            escrow: pda::escrow(proposal).0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CloseProposal {},
+        [],
+    )
+}
+
+/// Also closes the milestone's evidence account when there is one, its rent
+/// goes to `evidence_payer`. Payers are read with `rent_payer` on the
+/// accounts.
+pub fn close_milestone(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    payer: &Pubkey,
+    evidence_payer: Option<&Pubkey>,
+    milestone_number: u8,
+) -> Instruction {
+    build(
+        accounts::CloseMilestone {
+            cranker: *cranker,
+            proposal: *proposal,
+            milestone: pda::milestone(proposal, milestone_number).0,
+            evidence: pda::evidence(proposal, milestone_number).0,
+            payer: *payer,
+            evidence_payer: evidence_payer.copied(),
+        },
+        instruction::CloseMilestone {},
+        [],
+    )
+}
+
+pub fn close_vote(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    validator: &Pubkey,
+) -> Instruction {
+    let milestone = pda::milestone(proposal, milestone_number).0;
+    build(
+        accounts::CloseVote {
+            cranker: *cranker,
+            proposal: *proposal,
+            milestone,
+            vote: pda::vote(validator, &milestone).0,
+            validator: *validator,
+        },
+        instruction::CloseVote {},
+        [],
+    )
+}
+
+pub fn close_contributor(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    wallet: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CloseContributor {
+            cranker: *cranker,
+            proposal: *proposal,
+            contributor_account: pda::contributor(wallet, proposal).0,
+            escrow: pda::escrow(proposal).0,
+            payer: *payer,
+        },
+        instruction::CloseContributor {},
+        [],
+    )
+}
+
+/// An account [`close_accounts`] closes, `payer` gets the rent back.
+#[derive(Clone, Copy, Debug)]
+pub enum Closable {
+    Milestone {
+        number: u8,
+        payer: Pubkey,
+    },
+    Evidence {
+        milestone_number: u8,
+        payer: Pubkey,
+    },
+    Vote {
+        milestone_number: u8,
+        validator: Pubkey,
+    },
+    Contributor {
+        wallet: Pubkey,
+        payer: Pubkey,
+    },
+}
+
+/// Closes every account in `closables`, close votes before their milestone.
+pub fn close_accounts(cranker: &Pubkey, proposal: &Pubkey, closables: &[Closable]) -> Instruction {
+    build(
+        accounts::CloseAccounts {
+            cranker: *cranker,
+            proposal: *proposal,
+            escrow: pda::escrow(proposal).0,
+        },
+        instruction::CloseAccounts {},
+        closables.iter().flat_map(|closable| match *closable {
+            Closable::Milestone { number, payer } => vec![
+                AccountMeta::new(pda::milestone(proposal, number).0, false),
+                AccountMeta::new(payer, false),
+            ],
+            Closable::Evidence {
+                milestone_number,
+                payer,
+            } => vec![
+                AccountMeta::new(pda::evidence(proposal, milestone_number).0, false),
+                AccountMeta::new(payer, false),
+            ],
+            Closable::Vote {
+                milestone_number,
+                validator,
+            } => {
+                let milestone = pda::milestone(proposal, milestone_number).0;
+                vec![
+                    AccountMeta::new(pda::vote(&validator, &milestone).0, false),
+                    AccountMeta::new(milestone, false),
+                    AccountMeta::new(validator, false),
+                ]
+            }
+            Closable::Contributor { wallet, payer } => vec![
+                AccountMeta::new(pda::contributor(&wallet, proposal).0, false),
+                AccountMeta::new(payer, false),
+            ],
+        }),
+    )
+}
+
+pub fn challenge_milestone(
+    challenger: &Pubkey,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    mint: &Pubkey,
+    challenger_token_account: &Pubkey,
+) -> Instruction {
+    let milestone = pda::milestone(proposal, milestone_number).0;
+    build(
+        accounts::ChallengeMilestone {
+            challenger: *challenger,
+            proposal: *proposal,
+            milestone,
+            contributor_account: pda::contributor(challenger, proposal).0,
+            escrow: pda::escrow(proposal).0,
+            mint: *mint,
+            bond_vault: pda::challenge_vault(&milestone).0,
+            challenger_token_account: *challenger_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ChallengeMilestone {},
+        [],
+    )
+}
+
+/// The bond goes to one of the two token accounts depending on the outcome,
+/// the vault's rent back to `challenger`.
+pub fn resolve_challenge(
+    cranker: &Pubkey,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    challenger: &Pubkey,
+    challenger_token_account: &Pubkey,
+    authority_token_account: &Pubkey,
+) -> Instruction {
+    let milestone = pda::milestone(proposal, milestone_number).0;
+    build(
+        accounts::ResolveChallenge {
+            cranker: *cranker,
+            proposal: *proposal,
+            milestone,
+            bond_vault: pda::challenge_vault(&milestone).0,
+            challenger_token_account: *challenger_token_account,
+            authority_token_account: *authority_token_account,
+            challenger: *challenger,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::ResolveChallenge {},
+        [],
+    )
+}
+
+/// `approvers` are the current team members other than the authority, who
+/// must all sign the transaction.
+pub fn update_team(
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    members: Vec<TeamMember>,
+    approvers: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::UpdateTeam {
+            authority: *authority,
+            proposal: *proposal,
+        },
+        instruction::UpdateTeam { members },
+        approvers
+            .iter()
+            .map(|approver| AccountMeta::new_readonly(*approver, true)),
+    )
+}
+
+/// Starts handing the proposal over to `new_authority`, `None` cancels.
+pub fn propose_authority(
+    authority: &Pubkey,
+    proposal: &Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ProposeAuthority {
+            authority: *authority,
+            proposal: *proposal,
+        },
+        instruction::ProposeAuthority { new_authority },
+        [],
+    )
+}
+
+pub fn accept_authority(new_authority: &Pubkey, proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAuthority {
+            new_authority: *new_authority,
+            proposal: *proposal,
+        },
+        instruction::AcceptAuthority {},
+        [],
+    )
+}
+
+/// Brings any of the program's accounts up to the current layout version,
+/// `payer` covers the extra rent.
+pub fn migrate_account(payer: &Pubkey, account: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateAccount {
+            payer: *payer,
+            account: *account,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateAccount {},
+        [],
+    )
+}
+
+/// Arguments of [`create_funding_round`].
+#[derive(Clone, Debug)]
+pub struct CreateFundingRoundArgs {
+    pub round_id: u64,
+    pub matching_pool: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub proposals: Vec<Pubkey>,
+}
+
+pub fn create_funding_round(
+    sponsor: &Pubkey,
+    mint: &Pubkey,
+    sponsor_token_account: &Pubkey,
+    args: CreateFundingRoundArgs,
+) -> Instruction {
+    let funding_round = pda::funding_round(sponsor, args.round_id).0;
+    build(
+        accounts::CreateFundingRound {
+            sponsor: *sponsor,
+            funding_round,
+            vault: pda::round_vault(&funding_round).0,
+            mint: *mint,
+            sponsor_token_account: *sponsor_token_account,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateFundingRound {
+            round_id: args.round_id,
+            matching_pool: args.matching_pool,
+            starts_at: args.starts_at,
+            ends_at: args.ends_at,
+            proposals: args.proposals,
+        },
+        [],
+    )
+}
+
+/// `proposals` must list the round's proposals in round order, their
+/// escrows are derived.
+pub fn distribute_matching(
+    funding_round: &Pubkey,
+    sponsor_token_account: &Pubkey,
+    proposals: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::DistributeMatching {
+            funding_round: *funding_round,
+            vault: pda::round_vault(funding_round).0,
+            sponsor_token_account: *sponsor_token_account,
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::DistributeMatching {},
+        proposals.iter().flat_map(|proposal| {
+            [
+                AccountMeta::new(*proposal, false),
+                AccountMeta::new(pda::escrow(proposal).0, false),
+            ]
+        }),
+    )
+}
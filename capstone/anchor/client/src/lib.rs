@@ -0,0 +1,24 @@
+//! Client SDK for the ResearchChain program.
+//!
+//! - [`pda`] derives every program address from its seeds.
+//! - [`instructions`] builds typed instructions for every handler.
+//! - [`accounts`] decodes the program's account data.
+//! - [`errors`] maps the program's error codes to typed values.
+//! - [`allowlist`] builds contributor allowlists and their proofs.
+
+pub mod accounts;
+pub mod allowlist;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+
+pub use accounts::ProgramAccount;
+pub use allowlist::{Allowlist, AllowlistError};
+pub use anchor::ID as PROGRAM_ID;
+pub use anchor::{
+    AllowlistProof, Attachment, ContentId, ContentType, ContributionLimits, Contributor,
+    FundingRound, Milestone, MilestoneEvidence, MilestoneStatus, Pledge, PledgeEnd, Proposal,
+    ProposalStatus, Review, ReviewScores, ReviewTotals, RoundContribution, RoundEntry, StretchGoal,
+    TeamMember, Vote,
+};
+pub use errors::{ErrorCode, ProgramError};
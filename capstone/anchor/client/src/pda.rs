@@ -0,0 +1,121 @@
+//! Program derived addresses used by the ResearchChain program.
+//!
+//! Every helper returns the address together with its bump, matching
+//! `Pubkey::find_program_address`.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::PROGRAM_ID;
+
+/// `[b"proposal", researcher, title]`
+pub fn proposal(researcher: &Pubkey, title: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"proposal", researcher.as_ref(), title.as_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"escrow", proposal]`
+pub fn escrow(proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", proposal.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"contributor", wallet, proposal]`
+pub fn contributor(wallet: &Pubkey, proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"contributor", wallet.as_ref(), proposal.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"receipt", contributor_account]`, the soulbound receipt mint.
+pub fn receipt(contributor_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt", contributor_account.as_ref()], &PROGRAM_ID)
+}
+
+/// Token-2022 associated account holding a contributor's receipt.
+pub fn receipt_token_account(wallet: &Pubkey, receipt: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, receipt, &anchor_spl::token_2022::ID)
+}
+
+/// `[b"round", sponsor, round_id]`
+pub fn funding_round(sponsor: &Pubkey, round_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"round", sponsor.as_ref(), &round_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"round_vault", funding_round]`, holds the matching pool.
+pub fn round_vault(funding_round: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"round_vault", funding_round.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"round_contribution", funding_round, contributor_account]`
+pub fn round_contribution(funding_round: &Pubkey, contributor_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"round_contribution",
+            funding_round.as_ref(),
+            contributor_account.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"milestone", proposal, milestone_number]`
+pub fn milestone(proposal: &Pubkey, milestone_number: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"milestone",
+            proposal.as_ref(),
+            &milestone_number.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"vote", validator, milestone]`
+pub fn vote(validator: &Pubkey, milestone: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vote", validator.as_ref(), milestone.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"evidence", proposal, milestone_number]`
+pub fn evidence(proposal: &Pubkey, milestone_number: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"evidence",
+            proposal.as_ref(),
+            &milestone_number.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// `[b"challenge", milestone]`, holds a challenger's bond.
+pub fn challenge_vault(milestone: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"challenge", milestone.as_ref()], &PROGRAM_ID)
+}
+
+/// `[b"pledge", wallet, proposal]`, also the delegate on the pledged token
+/// account.
+pub fn pledge(wallet: &Pubkey, proposal: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pledge", wallet.as_ref(), proposal.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Metaplex metadata account of an IP-NFT mint.
+pub fn metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::accounts::Metadata::find_pda(mint)
+}
+
+/// Metaplex master edition account of an IP-NFT mint.
+pub fn master_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::accounts::MasterEdition::find_pda(mint)
+}
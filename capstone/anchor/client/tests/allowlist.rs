@@ -0,0 +1,71 @@
+use anchor_lang::prelude::Pubkey;
+use researchchain_client::{Allowlist, AllowlistError};
+
+fn csv(entries: &[(Pubkey, u64)]) -> String {
+    let mut csv = "wallet,cap\n".to_string();
+    for (wallet, cap) in entries {
+        csv.push_str(&format!("{wallet},{cap}\n"));
+    }
+    csv
+}
+
+#[test]
+fn every_listed_wallet_proves_its_cap() {
+    for size in 1..=9 {
+        let entries: Vec<_> = (0..size)
+            .map(|i| (Pubkey::new_unique(), 100 * (i + 1)))
+            .collect();
+        let allowlist = Allowlist::from_csv(&csv(&entries)).unwrap();
+
+        for (wallet, cap) in &entries {
+            let proof = allowlist.proof(wallet).unwrap();
+            assert_eq!(proof.cap, *cap);
+            assert_eq!(proof.root(wallet), allowlist.root());
+        }
+        assert!(allowlist.proof(&Pubkey::new_unique()).is_none());
+    }
+}
+
+#[test]
+fn proofs_only_hold_for_their_own_wallet_and_cap() {
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let allowlist = Allowlist::new([(alice, 500), (bob, 200)]).unwrap();
+
+    let mut proof = allowlist.proof(&bob).unwrap();
+    assert_ne!(proof.root(&alice), allowlist.root());
+    proof.cap = 500;
+    assert_ne!(proof.root(&bob), allowlist.root());
+}
+
+#[test]
+fn the_root_ignores_input_order() {
+    let entries: Vec<_> = (0..5).map(|i| (Pubkey::new_unique(), i)).collect();
+    let reversed: Vec<_> = entries.iter().rev().copied().collect();
+    assert_eq!(
+        Allowlist::new(entries).unwrap().root(),
+        Allowlist::new(reversed).unwrap().root()
+    );
+}
+
+#[test]
+fn rejects_malformed_csv() {
+    let wallet = Pubkey::new_unique();
+
+    assert_eq!(
+        Allowlist::from_csv("# backers\n\n").err(),
+        Some(AllowlistError::Empty)
+    );
+    assert_eq!(
+        Allowlist::from_csv(&format!("{wallet},1\n{wallet},2\n")).err(),
+        Some(AllowlistError::DuplicateWallet(wallet))
+    );
+    assert!(matches!(
+        Allowlist::from_csv(&format!("{wallet},1\nnot-a-wallet,2\n")),
+        Err(AllowlistError::InvalidLine { line: 2, .. })
+    ));
+    assert!(matches!(
+        Allowlist::from_csv(&format!("{wallet}\n")),
+        Err(AllowlistError::InvalidLine { line: 1, .. })
+    ));
+}
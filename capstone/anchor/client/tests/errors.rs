@@ -0,0 +1,45 @@
+use anchor_lang::solana_program::instruction::InstructionError;
+use researchchain_client::{ErrorCode, ProgramError};
+
+#[test]
+fn error_codes_are_stable() {
+    assert_eq!(ErrorCode::MathOverflow.code(), 6000);
+    assert_eq!(ErrorCode::IllegalProposalTransition.code(), 6100);
+    assert_eq!(ErrorCode::ProposalNotActive.code(), 6102);
+    assert_eq!(ErrorCode::InvalidAmount.code(), 6200);
+    assert_eq!(ErrorCode::InvalidContribution.code(), 6300);
+    assert_eq!(ErrorCode::AlreadyWithdrawn.code(), 6301);
+    assert_eq!(ErrorCode::InvalidMilestoneOrder.code(), 6400);
+    assert_eq!(ErrorCode::MaxPenaltiesReached.code(), 6500);
+    assert_eq!(ErrorCode::InvalidTeamSize.code(), 6600);
+}
+
+#[test]
+fn every_code_maps_back_to_its_error() {
+    let mut codes: Vec<u32> = ErrorCode::ALL.iter().map(|e| e.code()).collect();
+    for error in ErrorCode::ALL {
+        assert_eq!(ErrorCode::from_code(error.code()), Some(*error));
+    }
+    let count = codes.len();
+    codes.dedup();
+    assert_eq!(codes.len(), count, "duplicate error codes");
+    assert!(codes.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn decodes_instruction_errors() {
+    assert_eq!(
+        ProgramError::from_instruction_error(&InstructionError::Custom(6402)),
+        Some(ProgramError::Program(ErrorCode::ValidationDeadlinePassed))
+    );
+    // ConstraintSeeds
+    assert_eq!(
+        ProgramError::from_instruction_error(&InstructionError::Custom(2006)),
+        Some(ProgramError::Framework(2006))
+    );
+    assert_eq!(ProgramError::from_code(6099), ProgramError::Unknown(6099));
+    assert_eq!(
+        ProgramError::from_instruction_error(&InstructionError::MissingRequiredSignature),
+        None
+    );
+}
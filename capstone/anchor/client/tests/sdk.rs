@@ -0,0 +1,387 @@
+use anchor_lang::{prelude::Pubkey, AccountSerialize, AnchorSerialize, Discriminator};
+use researchchain_client::instructions::Closable;
+use researchchain_client::{
+    accounts, instructions, pda, Attachment, ContentId, ContentType, ContributionLimits, Proposal,
+    ProposalStatus, TeamMember, Vote, PROGRAM_ID,
+};
+
+#[test]
+fn pdas_follow_the_program_seeds() {
+    let researcher = Pubkey::new_unique();
+    let wallet = Pubkey::new_unique();
+    let (proposal, _) = pda::proposal(&researcher, "Protein folding");
+
+    assert_eq!(
+        proposal,
+        Pubkey::find_program_address(
+            &[b"proposal", researcher.as_ref(), b"Protein folding"],
+            &PROGRAM_ID
+        )
+        .0
+    );
+    assert_eq!(
+        pda::escrow(&proposal).0,
+        Pubkey::find_program_address(&[b"escrow", proposal.as_ref()], &PROGRAM_ID).0
+    );
+    // Wallet first, proposal second
+    assert_ne!(
+        pda::contributor(&wallet, &proposal).0,
+        Pubkey::find_program_address(
+            &[b"contributor", proposal.as_ref(), wallet.as_ref()],
+            &PROGRAM_ID
+        )
+        .0
+    );
+    assert_eq!(
+        pda::milestone(&proposal, 2).0,
+        Pubkey::find_program_address(&[b"milestone", proposal.as_ref(), &[2]], &PROGRAM_ID).0
+    );
+}
+
+#[test]
+fn fund_proposal_targets_the_derived_accounts() {
+    let contributor = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+
+    let ix = instructions::fund_proposal(&contributor, &proposal, &mint, &token_account, 500);
+
+    assert_eq!(ix.program_id, PROGRAM_ID);
+    assert_eq!(ix.accounts[0].pubkey, contributor);
+    assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+    assert_eq!(
+        ix.accounts[2].pubkey,
+        pda::contributor(&contributor, &proposal).0
+    );
+    assert_eq!(ix.accounts[3].pubkey, pda::escrow(&proposal).0);
+    let receipt = pda::receipt(&pda::contributor(&contributor, &proposal).0).0;
+    assert_eq!(ix.accounts[6].pubkey, receipt);
+    assert_eq!(
+        ix.accounts[7].pubkey,
+        pda::receipt_token_account(&contributor, &receipt)
+    );
+    assert_eq!(
+        &ix.data[..8],
+        anchor::instruction::FundProposal::DISCRIMINATOR
+    );
+    assert_eq!(ix.data[8..16], 500u64.to_le_bytes());
+    // No allowlist proof
+    assert_eq!(ix.data[16..], [0]);
+}
+
+#[test]
+fn fund_proposal_allowlisted_carries_the_proof() {
+    let contributor = Pubkey::new_unique();
+    let allowlist =
+        researchchain_client::Allowlist::new([(contributor, 500), (Pubkey::new_unique(), 200)])
+            .unwrap();
+    let proof = allowlist.proof(&contributor).unwrap();
+
+    let ix = instructions::fund_proposal_allowlisted(
+        &contributor,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        500,
+        None,
+        proof.clone(),
+    );
+
+    let mut args = 500u64.to_le_bytes().to_vec();
+    args.push(1);
+    args.extend(proof.try_to_vec().unwrap());
+    assert_eq!(ix.data[8..], args);
+}
+
+#[test]
+fn fund_proposal_for_credits_the_beneficiary() {
+    let sponsor = Pubkey::new_unique();
+    let beneficiary = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+
+    let ix = instructions::fund_proposal_for(
+        &sponsor,
+        &beneficiary,
+        &proposal,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        500,
+        Some("grant".to_string()),
+        None,
+    );
+
+    assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+    assert_eq!(ix.accounts[1].pubkey, beneficiary);
+    assert!(!ix.accounts[1].is_signer);
+    let contributor = pda::contributor(&beneficiary, &proposal).0;
+    assert_eq!(ix.accounts[3].pubkey, contributor);
+    let receipt = pda::receipt(&contributor).0;
+    assert_eq!(
+        ix.accounts[8].pubkey,
+        pda::receipt_token_account(&beneficiary, &receipt)
+    );
+}
+
+#[test]
+fn execute_pledge_pulls_from_the_pledged_account() {
+    let cranker = Pubkey::new_unique();
+    let contributor = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+
+    let ix = instructions::execute_pledge(&cranker, &proposal, &contributor, &token_account);
+
+    assert!(ix.accounts[0].is_signer);
+    assert_eq!(
+        ix.accounts[2].pubkey,
+        pda::pledge(&contributor, &proposal).0
+    );
+    assert_eq!(ix.accounts[3].pubkey, contributor);
+    assert!(!ix.accounts[3].is_signer);
+    assert_eq!(ix.accounts[6].pubkey, token_account);
+    assert_ne!(
+        pda::pledge(&contributor, &proposal).0,
+        pda::contributor(&contributor, &proposal).0
+    );
+}
+
+#[test]
+fn release_funds_appends_member_token_accounts() {
+    let researcher = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+    let milestone = Pubkey::new_unique();
+    let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let ix = instructions::release_funds(
+        &researcher,
+        &proposal,
+        &milestone,
+        &Pubkey::new_unique(),
+        &members,
+    );
+
+    let tail: Vec<_> = ix.accounts.iter().rev().take(2).rev().collect();
+    assert_eq!(tail[0].pubkey, members[0]);
+    assert_eq!(tail[1].pubkey, members[1]);
+    assert!(tail.iter().all(|meta| meta.is_writable && !meta.is_signer));
+}
+
+#[test]
+fn migrate_account_lets_the_payer_fund_the_realloc() {
+    let payer = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+
+    let ix = instructions::migrate_account(&payer, &account);
+
+    assert_eq!(ix.accounts[0].pubkey, payer);
+    assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+    assert_eq!(ix.accounts[1].pubkey, account);
+    assert!(!ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+}
+
+#[test]
+fn close_accounts_pairs_each_account_with_its_rent_recipient() {
+    let cranker = Pubkey::new_unique();
+    let researcher = Pubkey::new_unique();
+    let validator = Pubkey::new_unique();
+    let wallet = Pubkey::new_unique();
+    let sponsor = Pubkey::new_unique();
+    let (proposal, _) = pda::proposal(&researcher, "Sweep");
+    let milestone = pda::milestone(&proposal, 1).0;
+
+    let ix = instructions::close_accounts(
+        &cranker,
+        &proposal,
+        &[
+            Closable::Vote {
+                milestone_number: 1,
+                validator,
+            },
+            Closable::Milestone {
+                number: 1,
+                payer: researcher,
+            },
+            Closable::Contributor {
+                wallet,
+                payer: sponsor,
+            },
+        ],
+    );
+
+    // The escrow tells whether contributors still have refunds to claim
+    assert_eq!(ix.accounts[2].pubkey, pda::escrow(&proposal).0);
+    let tail: Vec<_> = ix.accounts[3..].iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        tail,
+        [
+            pda::vote(&validator, &milestone).0,
+            milestone,
+            validator,
+            milestone,
+            researcher,
+            pda::contributor(&wallet, &proposal).0,
+            sponsor,
+        ]
+    );
+    // The vote takes itself off its milestone's open votes
+    assert!(ix.accounts[4].is_writable);
+    assert!(ix.accounts[6].is_writable && ix.accounts[7].is_writable);
+}
+
+#[test]
+fn distribute_matching_pairs_proposals_with_their_escrows() {
+    let round = pda::funding_round(&Pubkey::new_unique(), 7).0;
+    let proposals = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let ix = instructions::distribute_matching(&round, &Pubkey::new_unique(), &proposals);
+
+    assert_eq!(ix.accounts[1].pubkey, pda::round_vault(&round).0);
+    let pairs: Vec<_> = ix.accounts[4..].iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        pairs,
+        vec![
+            proposals[0],
+            pda::escrow(&proposals[0]).0,
+            proposals[1],
+            pda::escrow(&proposals[1]).0,
+        ]
+    );
+    assert!(ix.accounts[4..].iter().all(|meta| meta.is_writable));
+}
+
+#[test]
+fn vote_commitments_bind_the_milestone_and_validator() {
+    let milestone = Pubkey::new_unique();
+    let validator = Pubkey::new_unique();
+    let salt = [7; 32];
+    let commitment = Vote::commitment(&milestone, &validator, true, &salt);
+
+    assert_ne!(
+        commitment,
+        Vote::commitment(&milestone, &validator, false, &salt)
+    );
+    assert_ne!(
+        commitment,
+        Vote::commitment(&milestone, &validator, true, &[8; 32])
+    );
+    assert_ne!(
+        commitment,
+        Vote::commitment(&milestone, &Pubkey::new_unique(), true, &salt)
+    );
+    assert_ne!(
+        commitment,
+        Vote::commitment(&Pubkey::new_unique(), &validator, true, &salt)
+    );
+
+    let commit =
+        instructions::commit_vote(&validator, &Pubkey::new_unique(), &milestone, commitment);
+    let reveal = instructions::reveal_vote(
+        &validator,
+        &Pubkey::new_unique(),
+        &milestone,
+        true,
+        salt,
+        None,
+    );
+    let vote = pda::vote(&validator, &milestone).0;
+    assert_eq!(commit.accounts[3].pubkey, vote);
+    assert_eq!(reveal.accounts[3].pubkey, vote);
+    assert_eq!(commit.data[8..], commitment);
+}
+
+#[test]
+fn submit_milestone_passes_the_evidence_account() {
+    let authority = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let proposal = Pubkey::new_unique();
+    let evidence = pda::evidence(&proposal, 2).0;
+
+    let submit =
+        instructions::submit_milestone(&authority, &payer, &proposal, 2, "Qm".to_string(), false);
+    let add = instructions::add_evidence(
+        &authority,
+        &payer,
+        &proposal,
+        2,
+        Attachment {
+            content_type: ContentType::Code,
+            cid: ContentId::parse("bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq")
+                .unwrap(),
+            size: 1,
+            sha256: [0; 32],
+        },
+    );
+
+    assert_eq!(submit.accounts[4].pubkey, evidence);
+    assert!(!submit.accounts[4].is_writable);
+    assert_eq!(add.accounts[3].pubkey, evidence);
+    assert!(add.accounts[3].is_writable);
+
+    // Only the payer's lamports move, the authority may be a PDA
+    for ix in [&submit, &add] {
+        assert!(ix.accounts[0].is_signer && !ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts[1].pubkey, payer);
+        assert!(ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+    }
+}
+
+#[test]
+fn decodes_proposal_accounts() {
+    let researcher = Pubkey::new_unique();
+    let proposal = Proposal {
+        version: 8,
+        title: "Protein folding".to_string(),
+        abstract_text: "Abstract".to_string(),
+        researcher,
+        contributors_count: 2,
+        initial_security_deposit: 10,
+        current_security_deposit: 10,
+        amount_asked: 100,
+        amount_raised: 60,
+        funds_escrow: Pubkey::new_unique(),
+        total_milestones: 3,
+        current_milestone: 1,
+        ipfs_hash: ContentId::parse("QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR").ok(),
+        ip_nft_mint: None,
+        created_at: 1,
+        updated_at: 2,
+        status: ProposalStatus::Active,
+        penalty_counter: 0,
+        team: vec![TeamMember {
+            wallet: researcher,
+            share_bps: 10_000,
+        }],
+        matched_amount: 0,
+        matched_released: 0,
+        challenge_period: 3 * 86_400,
+        authority: researcher,
+        pending_authority: Some(Pubkey::new_unique()),
+        allowlist_root: Some([7; 32]),
+        contribution_limits: ContributionLimits {
+            min: 10,
+            max: 50,
+            hard_cap: true,
+        },
+        stretch_goals: Vec::new(),
+        stretch_goals_unlocked: 0,
+        open_accounts: 0,
+        milestones_released: Some(0),
+    };
+    let mut data = Vec::new();
+    proposal.try_serialize(&mut data).unwrap();
+
+    let decoded = accounts::decode_proposal(&data).unwrap();
+    assert_eq!(decoded.title, proposal.title);
+    assert_eq!(decoded.amount_raised, 60);
+    assert!(decoded.team == proposal.team);
+    assert_eq!(decoded.pending_authority, proposal.pending_authority);
+    assert_eq!(decoded.allowlist_root, Some([7; 32]));
+    assert_eq!(decoded.contribution_limits, proposal.contribution_limits);
+
+    assert!(matches!(
+        researchchain_client::ProgramAccount::decode(&data),
+        Some(researchchain_client::ProgramAccount::Proposal(_))
+    ));
+    assert!(accounts::decode_milestone(&data).is_err());
+}
@@ -0,0 +1,1014 @@
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use researchchain_client::accounts::{
+    content_type_name, milestone_status_name, pledge_end_name, proposal_status_name,
+};
+use researchchain_client::{Attachment, ContentId};
+
+use crate::decode::ProgramEvent;
+use crate::ProgramAccount;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER,
+    name        TEXT NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS proposals (
+    address                  TEXT PRIMARY KEY,
+    researcher               TEXT NOT NULL,
+    authority                TEXT NOT NULL,
+    pending_authority        TEXT,
+    escrow                   TEXT NOT NULL,
+    mint                     TEXT,
+    title                    TEXT NOT NULL,
+    ipfs_hash                TEXT NOT NULL,
+    amount_asked             INTEGER NOT NULL,
+    amount_raised            INTEGER NOT NULL DEFAULT 0,
+    amount_released          INTEGER NOT NULL DEFAULT 0,
+    escrow_balance           INTEGER NOT NULL DEFAULT 0,
+    initial_security_deposit INTEGER NOT NULL,
+    current_security_deposit INTEGER NOT NULL,
+    contributors_count       INTEGER NOT NULL DEFAULT 0,
+    total_milestones         INTEGER NOT NULL,
+    current_milestone        INTEGER NOT NULL DEFAULT 0,
+    penalty_counter          INTEGER NOT NULL DEFAULT 0,
+    status                   TEXT NOT NULL,
+    created_at               INTEGER NOT NULL,
+    updated_at               INTEGER NOT NULL,
+    closed_at                INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS team_members (
+    proposal  TEXT NOT NULL,
+    wallet    TEXT NOT NULL,
+    share_bps INTEGER NOT NULL,
+    PRIMARY KEY (proposal, wallet)
+);
+
+CREATE TABLE IF NOT EXISTS contributors (
+    address    TEXT PRIMARY KEY,
+    proposal   TEXT NOT NULL,
+    wallet     TEXT NOT NULL,
+    amount     INTEGER NOT NULL DEFAULT 0,
+    refunded   INTEGER NOT NULL DEFAULT 0,
+    first_at   INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS contributions (
+    signature TEXT NOT NULL,
+    proposal  TEXT NOT NULL,
+    wallet    TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    raised    INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sponsorships (
+    signature   TEXT NOT NULL,
+    proposal    TEXT NOT NULL,
+    sponsor     TEXT NOT NULL,
+    beneficiary TEXT NOT NULL,
+    amount      INTEGER NOT NULL,
+    memo        TEXT,
+    timestamp   INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pledges (
+    address           TEXT PRIMARY KEY,
+    proposal          TEXT NOT NULL,
+    contributor       TEXT NOT NULL,
+    amount            INTEGER NOT NULL,
+    installments      INTEGER NOT NULL,
+    installments_paid INTEGER NOT NULL,
+    next_due          INTEGER NOT NULL,
+    ended             TEXT,
+    created_at        INTEGER NOT NULL,
+    updated_at        INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS milestones (
+    address          TEXT PRIMARY KEY,
+    proposal         TEXT NOT NULL,
+    milestone_number INTEGER NOT NULL,
+    evidence_hash    TEXT NOT NULL,
+    validation_votes INTEGER NOT NULL DEFAULT 0,
+    total_validators INTEGER NOT NULL DEFAULT 0,
+    funds_released   INTEGER NOT NULL DEFAULT 0,
+    amount_released  INTEGER NOT NULL DEFAULT 0,
+    status           TEXT NOT NULL,
+    deadline         INTEGER NOT NULL,
+    created_at       INTEGER NOT NULL,
+    updated_at       INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS attachments (
+    proposal         TEXT NOT NULL,
+    milestone_number INTEGER NOT NULL,
+    position         INTEGER NOT NULL,
+    content_type     TEXT NOT NULL,
+    cid              TEXT NOT NULL,
+    size             INTEGER NOT NULL,
+    sha256           BLOB NOT NULL,
+    PRIMARY KEY (proposal, milestone_number, position)
+);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    signature     TEXT NOT NULL,
+    milestone     TEXT NOT NULL,
+    wallet        TEXT NOT NULL,
+    token_account TEXT NOT NULL,
+    amount        INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS votes (
+    address         TEXT PRIMARY KEY,
+    proposal        TEXT,
+    milestone       TEXT NOT NULL,
+    validator       TEXT NOT NULL,
+    approved        INTEGER NOT NULL,
+    voted_at        INTEGER NOT NULL,
+    reproducibility INTEGER,
+    completeness    INTEGER,
+    quality         INTEGER,
+    rationale       TEXT
+);
+
+CREATE TABLE IF NOT EXISTS vote_history (
+    signature TEXT NOT NULL,
+    vote      TEXT NOT NULL,
+    milestone TEXT NOT NULL,
+    validator TEXT NOT NULL,
+    approved  INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS missed_reveals (
+    signature TEXT NOT NULL,
+    proposal  TEXT NOT NULL,
+    milestone TEXT NOT NULL,
+    validator TEXT NOT NULL,
+    cranker   TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS challenges (
+    milestone        TEXT PRIMARY KEY,
+    proposal         TEXT NOT NULL,
+    challenger       TEXT NOT NULL,
+    bond             INTEGER NOT NULL,
+    dispute_deadline INTEGER NOT NULL,
+    upheld           INTEGER,
+    bond_recipient   TEXT,
+    challenged_at    INTEGER NOT NULL,
+    resolved_at      INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS penalties (
+    signature TEXT NOT NULL,
+    proposal  TEXT NOT NULL,
+    milestone TEXT NOT NULL,
+    treasury  TEXT NOT NULL,
+    amount    INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS ip_nfts (
+    mint      TEXT PRIMARY KEY,
+    payer     TEXT NOT NULL,
+    name      TEXT NOT NULL,
+    symbol    TEXT NOT NULL,
+    uri       TEXT NOT NULL,
+    minted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS funding_rounds (
+    address        TEXT PRIMARY KEY,
+    sponsor        TEXT NOT NULL,
+    mint           TEXT NOT NULL,
+    vault          TEXT NOT NULL,
+    round_id       INTEGER NOT NULL,
+    matching_pool  INTEGER NOT NULL,
+    starts_at      INTEGER NOT NULL,
+    ends_at        INTEGER NOT NULL,
+    returned       INTEGER,
+    distributed_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS round_entries (
+    funding_round TEXT NOT NULL,
+    proposal      TEXT NOT NULL,
+    contributors  INTEGER NOT NULL DEFAULT 0,
+    contributed   INTEGER NOT NULL DEFAULT 0,
+    matched       INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (funding_round, proposal)
+);
+
+CREATE TABLE IF NOT EXISTS cursor (
+    id        INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL
+);
+";
+
+/// SQLite store holding the normalized view of the program state.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Last transaction signature processed from RPC, used to resume.
+    pub fn cursor(&self) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()
+    }
+
+    pub fn set_cursor(&self, signature: &str) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO cursor (id, signature) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET signature = excluded.signature",
+            params![signature],
+        )?;
+        Ok(())
+    }
+
+    /// Applies every event of one transaction atomically.
+    ///
+    /// Events already recorded under the same signature and index are skipped,
+    /// so replaying the same logs twice leaves the tables unchanged.
+    pub fn apply_transaction(
+        &mut self,
+        signature: &str,
+        slot: Option<u64>,
+        events: &[ProgramEvent],
+    ) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut applied = 0;
+
+        for (index, event) in events.iter().enumerate() {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO events (signature, event_index, slot, name)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    signature,
+                    index as i64,
+                    slot.map(|s| s as i64),
+                    event.name()
+                ],
+            )?;
+            if inserted == 0 {
+                continue;
+            }
+            apply_event(&tx, signature, event)?;
+            applied += 1;
+        }
+
+        tx.commit()?;
+        Ok(applied)
+    }
+
+    /// Overwrites the rows of an account with its decoded on-chain data.
+    pub fn upsert_account(
+        &mut self,
+        address: &Pubkey,
+        account: &ProgramAccount,
+    ) -> rusqlite::Result<()> {
+        let address = address.to_string();
+        match account {
+            ProgramAccount::Proposal(p) => {
+                self.conn.execute(
+                    "INSERT INTO proposals (
+                        address, researcher, escrow, title, ipfs_hash, amount_asked,
+                        amount_raised, initial_security_deposit, current_security_deposit,
+                        contributors_count, total_milestones, current_milestone,
+                        penalty_counter, status, created_at, updated_at, authority,
+                        pending_authority
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                        ?17, ?18)
+                     ON CONFLICT (address) DO UPDATE SET
+                        authority = excluded.authority,
+                        pending_authority = excluded.pending_authority,
+                        amount_raised = excluded.amount_raised,
+                        current_security_deposit = excluded.current_security_deposit,
+                        contributors_count = excluded.contributors_count,
+                        current_milestone = excluded.current_milestone,
+                        penalty_counter = excluded.penalty_counter,
+                        status = excluded.status,
+                        updated_at = excluded.updated_at",
+                    params![
+                        address,
+                        p.researcher.to_string(),
+                        p.funds_escrow.to_string(),
+                        p.title,
+                        content_id(&p.ipfs_hash),
+                        p.amount_asked as i64,
+                        p.amount_raised as i64,
+                        p.initial_security_deposit as i64,
+                        p.current_security_deposit as i64,
+                        p.contributors_count,
+                        p.total_milestones,
+                        p.current_milestone,
+                        p.penalty_counter,
+                        proposal_status_name(&p.status),
+                        p.created_at,
+                        p.updated_at,
+                        p.authority.to_string(),
+                        p.pending_authority.map(|key| key.to_string()),
+                    ],
+                )?;
+                replace_team(
+                    &self.conn,
+                    &address,
+                    p.team.iter().map(|m| (m.wallet, m.share_bps)),
+                )?;
+            }
+            ProgramAccount::Milestone(m) => {
+                self.conn.execute(
+                    "INSERT INTO milestones (
+                        address, proposal, milestone_number, evidence_hash, validation_votes,
+                        total_validators, funds_released, status, deadline, created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                     ON CONFLICT (address) DO UPDATE SET
+                        validation_votes = excluded.validation_votes,
+                        total_validators = excluded.total_validators,
+                        funds_released = excluded.funds_released,
+                        status = excluded.status,
+                        updated_at = excluded.updated_at",
+                    params![
+                        address,
+                        m.proposal.to_string(),
+                        m.milestone_number,
+                        content_id(&m.evidence_hash),
+                        m.validation_votes,
+                        m.total_validators,
+                        m.funds_released,
+                        milestone_status_name(&m.status),
+                        m.deadline,
+                        m.created_at,
+                        m.updated_at,
+                    ],
+                )?;
+            }
+            ProgramAccount::Contributor(c) => {
+                self.conn.execute(
+                    "INSERT INTO contributors (address, proposal, wallet, amount, first_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                     ON CONFLICT (address) DO UPDATE SET amount = excluded.amount",
+                    params![
+                        address,
+                        c.proposal.to_string(),
+                        c.wallet.to_string(),
+                        c.amount as i64,
+                        c.timestamp,
+                    ],
+                )?;
+            }
+            ProgramAccount::FundingRound(r) => {
+                upsert_funding_round(
+                    &self.conn,
+                    &address,
+                    &r.sponsor,
+                    &r.mint,
+                    &r.vault,
+                    r.round_id,
+                    r.matching_pool,
+                    r.starts_at,
+                    r.ends_at,
+                )?;
+                for entry in &r.entries {
+                    self.conn.execute(
+                        "INSERT INTO round_entries (funding_round, proposal, contributors, contributed, matched)
+                         VALUES (?1, ?2, ?3, ?4, ?5)
+                         ON CONFLICT (funding_round, proposal) DO UPDATE SET
+                            contributors = excluded.contributors,
+                            contributed = excluded.contributed,
+                            matched = excluded.matched",
+                        params![
+                            address,
+                            entry.proposal.to_string(),
+                            entry.contributors,
+                            entry.contributed as i64,
+                            entry.matched as i64,
+                        ],
+                    )?;
+                }
+            }
+            // Per-contributor round totals are only needed on chain
+            ProgramAccount::RoundContribution(_) => {}
+            ProgramAccount::Pledge(p) => {
+                self.conn.execute(
+                    "INSERT INTO pledges (
+                        address, proposal, contributor, amount, installments,
+                        installments_paid, next_due, created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+                     ON CONFLICT (address) DO UPDATE SET
+                        installments_paid = excluded.installments_paid,
+                        next_due = excluded.next_due",
+                    params![
+                        address,
+                        p.proposal.to_string(),
+                        p.contributor.to_string(),
+                        p.amount as i64,
+                        p.installments,
+                        p.installments_paid,
+                        p.next_due,
+                        p.created_at,
+                    ],
+                )?;
+            }
+            ProgramAccount::MilestoneEvidence(e) => {
+                for (position, attachment) in e.attachments.iter().enumerate() {
+                    upsert_attachment(
+                        &self.conn,
+                        &e.proposal,
+                        e.milestone_number,
+                        position,
+                        attachment,
+                    )?;
+                }
+            }
+            // Hidden until revealed, the tally only holds revealed votes
+            ProgramAccount::Vote(v) if v.commitment != [0; 32] && !v.revealed => {}
+            ProgramAccount::Vote(v) => {
+                self.conn.execute(
+                    "INSERT INTO votes (address, milestone, validator, approved, voted_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT (address) DO UPDATE SET approved = excluded.approved",
+                    params![
+                        address,
+                        v.milestone.to_string(),
+                        v.validator.to_string(),
+                        v.approved,
+                        v.voted_at,
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn upsert_funding_round(
+    conn: &Connection,
+    address: &str,
+    sponsor: &Pubkey,
+    mint: &Pubkey,
+    vault: &Pubkey,
+    round_id: u64,
+    matching_pool: u64,
+    starts_at: i64,
+    ends_at: i64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO funding_rounds (
+            address, sponsor, mint, vault, round_id, matching_pool, starts_at, ends_at
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT (address) DO NOTHING",
+        params![
+            address,
+            sponsor.to_string(),
+            mint.to_string(),
+            vault.to_string(),
+            round_id as i64,
+            matching_pool as i64,
+            starts_at,
+            ends_at,
+        ],
+    )?;
+    Ok(())
+}
+
+fn replace_team(
+    conn: &Connection,
+    proposal: &str,
+    members: impl Iterator<Item = (Pubkey, u16)>,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM team_members WHERE proposal = ?1",
+        params![proposal],
+    )?;
+    for (wallet, share_bps) in members {
+        conn.execute(
+            "INSERT INTO team_members (proposal, wallet, share_bps) VALUES (?1, ?2, ?3)",
+            params![proposal, wallet.to_string(), share_bps],
+        )?;
+    }
+    Ok(())
+}
+
+/// Text form of a stored identifier, empty for legacy hashes that never
+/// parsed.
+fn content_id(id: &Option<ContentId>) -> String {
+    id.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn upsert_attachment(
+    conn: &Connection,
+    proposal: &Pubkey,
+    milestone_number: u8,
+    position: usize,
+    attachment: &Attachment,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO attachments (
+            proposal, milestone_number, position, content_type, cid, size, sha256
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            proposal.to_string(),
+            milestone_number,
+            position as i64,
+            content_type_name(&attachment.content_type),
+            attachment.cid.to_string(),
+            attachment.size as i64,
+            attachment.sha256,
+        ],
+    )?;
+    Ok(())
+}
+
+fn apply_event(conn: &Connection, signature: &str, event: &ProgramEvent) -> rusqlite::Result<()> {
+    match event {
+        ProgramEvent::ProposalCreated(e) => {
+            let proposal = e.proposal.to_string();
+            conn.execute(
+                "INSERT OR REPLACE INTO proposals (
+                    address, researcher, authority, escrow, mint, title, ipfs_hash, amount_asked,
+                    escrow_balance, initial_security_deposit, current_security_deposit,
+                    total_milestones, status, created_at, updated_at
+                 ) VALUES (?1, ?2, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?8, ?9, 'Active', ?10, ?10)",
+                params![
+                    proposal,
+                    e.researcher.to_string(),
+                    e.escrow.to_string(),
+                    e.mint.to_string(),
+                    e.title,
+                    e.ipfs_hash.to_string(),
+                    e.amount_asked as i64,
+                    e.security_deposit as i64,
+                    e.total_milestones,
+                    e.timestamp,
+                ],
+            )?;
+            replace_team(conn, &proposal, std::iter::once((e.researcher, 10_000)))?;
+        }
+        ProgramEvent::Funded(e) => {
+            conn.execute(
+                "UPDATE proposals SET amount_raised = ?2, contributors_count = ?3,
+                    escrow_balance = escrow_balance + ?4, updated_at = ?5
+                 WHERE address = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.amount_raised as i64,
+                    e.contributors_count,
+                    e.amount as i64,
+                    e.timestamp,
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO contributors (address, proposal, wallet, amount, first_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+                 ON CONFLICT (address) DO UPDATE SET amount = excluded.amount,
+                    updated_at = excluded.updated_at",
+                params![
+                    e.contributor_account.to_string(),
+                    e.proposal.to_string(),
+                    e.contributor.to_string(),
+                    e.contributor_total as i64,
+                    e.timestamp,
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO contributions (signature, proposal, wallet, amount, raised, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    signature,
+                    e.proposal.to_string(),
+                    e.contributor.to_string(),
+                    e.amount as i64,
+                    e.amount_raised as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        // `Funded` credits the beneficiary, this only records who paid
+        ProgramEvent::ContributionSponsored(e) => {
+            conn.execute(
+                "INSERT INTO sponsorships (signature, proposal, sponsor, beneficiary, amount, memo, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    signature,
+                    e.proposal.to_string(),
+                    e.sponsor.to_string(),
+                    e.beneficiary.to_string(),
+                    e.amount as i64,
+                    e.memo,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        // The first installment comes in its own `Funded`
+        ProgramEvent::PledgeCreated(e) => {
+            conn.execute(
+                "INSERT OR REPLACE INTO pledges (
+                    address, proposal, contributor, amount, installments, installments_paid,
+                    next_due, created_at, updated_at
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, ?7)",
+                params![
+                    e.pledge.to_string(),
+                    e.proposal.to_string(),
+                    e.contributor.to_string(),
+                    e.amount as i64,
+                    e.installments,
+                    e.next_due,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PledgeExecuted(e) => {
+            conn.execute(
+                "UPDATE pledges SET installments_paid = ?2, next_due = ?3, updated_at = ?4
+                 WHERE address = ?1",
+                params![
+                    e.pledge.to_string(),
+                    e.installments_paid,
+                    e.next_due,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PledgeEnded(e) => {
+            conn.execute(
+                "UPDATE pledges SET ended = ?2, updated_at = ?3 WHERE address = ?1",
+                params![
+                    e.pledge.to_string(),
+                    pledge_end_name(&e.reason),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MilestoneSubmitted(e) => {
+            conn.execute(
+                "INSERT OR REPLACE INTO milestones (
+                    address, proposal, milestone_number, evidence_hash, status, deadline,
+                    created_at, updated_at
+                 ) VALUES (?1, ?2, ?3, ?4, 'Active', ?5, ?6, ?6)",
+                params![
+                    e.milestone.to_string(),
+                    e.proposal.to_string(),
+                    e.milestone_number,
+                    e.evidence_hash.to_string(),
+                    e.deadline,
+                    e.timestamp,
+                ],
+            )?;
+            conn.execute(
+                "UPDATE proposals SET current_milestone = ?2, updated_at = ?3 WHERE address = ?1",
+                params![e.proposal.to_string(), e.milestone_number, e.timestamp],
+            )?;
+        }
+        ProgramEvent::VoteCast(e) => {
+            let scores = e.review.as_ref().map(|review| review.scores);
+            conn.execute(
+                "INSERT INTO votes (
+                    address, proposal, milestone, validator, approved, voted_at,
+                    reproducibility, completeness, quality, rationale
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT (address) DO UPDATE SET
+                    approved = excluded.approved,
+                    reproducibility = excluded.reproducibility,
+                    completeness = excluded.completeness,
+                    quality = excluded.quality,
+                    rationale = excluded.rationale",
+                params![
+                    e.vote.to_string(),
+                    e.proposal.to_string(),
+                    e.milestone.to_string(),
+                    e.validator.to_string(),
+                    e.approved,
+                    e.timestamp,
+                    scores.map(|s| s.reproducibility),
+                    scores.map(|s| s.completeness),
+                    scores.map(|s| s.quality),
+                    e.review.as_ref().map(|review| review.rationale.to_string()),
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO vote_history (signature, vote, milestone, validator, approved, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    signature,
+                    e.vote.to_string(),
+                    e.milestone.to_string(),
+                    e.validator.to_string(),
+                    e.approved,
+                    e.timestamp,
+                ],
+            )?;
+            conn.execute(
+                "UPDATE milestones SET validation_votes = ?2, total_validators = ?3, updated_at = ?4
+                 WHERE address = ?1",
+                params![
+                    e.milestone.to_string(),
+                    e.validation_votes,
+                    e.total_validators,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MilestoneValidated(e) => {
+            conn.execute(
+                "UPDATE milestones SET status = 'Validated', validation_votes = ?2, updated_at = ?3
+                 WHERE address = ?1",
+                params![e.milestone.to_string(), e.validation_votes, e.timestamp],
+            )?;
+        }
+        ProgramEvent::MilestoneFailed(e) => {
+            conn.execute(
+                "UPDATE milestones SET status = 'Failed', updated_at = ?2 WHERE address = ?1",
+                params![e.milestone.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::FundsReleased(e) => {
+            conn.execute(
+                "UPDATE milestones SET funds_released = 1, amount_released = ?2, updated_at = ?3
+                 WHERE address = ?1",
+                params![e.milestone.to_string(), e.amount as i64, e.timestamp],
+            )?;
+            conn.execute(
+                "UPDATE proposals SET amount_released = amount_released + ?2, escrow_balance = ?3,
+                    current_security_deposit = current_security_deposit - ?4,
+                    status = CASE WHEN ?5 THEN 'Completed' ELSE status END, updated_at = ?6
+                 WHERE address = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.amount as i64,
+                    e.escrow_balance as i64,
+                    e.deposit_returned as i64,
+                    e.proposal_completed,
+                    e.timestamp,
+                ],
+            )?;
+            for payout in &e.payouts {
+                conn.execute(
+                    "INSERT INTO payouts (signature, milestone, wallet, token_account, amount)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        signature,
+                        e.milestone.to_string(),
+                        payout.wallet.to_string(),
+                        payout.token_account.to_string(),
+                        payout.amount as i64,
+                    ],
+                )?;
+            }
+        }
+        ProgramEvent::PenaltyApplied(e) => {
+            conn.execute(
+                "UPDATE proposals SET penalty_counter = ?2, current_security_deposit = ?3,
+                    escrow_balance = escrow_balance - ?4,
+                    status = CASE WHEN ?5 THEN 'Failed' ELSE status END, updated_at = ?6
+                 WHERE address = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.penalty_counter,
+                    e.remaining_security_deposit as i64,
+                    e.amount as i64,
+                    e.proposal_failed,
+                    e.timestamp,
+                ],
+            )?;
+            conn.execute(
+                "INSERT INTO penalties (signature, proposal, milestone, treasury, amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    signature,
+                    e.proposal.to_string(),
+                    e.milestone.to_string(),
+                    e.treasury.to_string(),
+                    e.amount as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::RefundWithdrawn(e) => {
+            conn.execute(
+                "UPDATE contributors SET refunded = refunded + ?2, updated_at = ?3
+                 WHERE address = ?1",
+                params![
+                    e.contributor_account.to_string(),
+                    e.amount as i64,
+                    e.timestamp
+                ],
+            )?;
+            conn.execute(
+                "UPDATE proposals SET escrow_balance = ?2, updated_at = ?3 WHERE address = ?1",
+                params![e.proposal.to_string(), e.escrow_balance as i64, e.timestamp],
+            )?;
+        }
+        ProgramEvent::IpNftMinted(e) => {
+            conn.execute(
+                "INSERT OR REPLACE INTO ip_nfts (mint, payer, name, symbol, uri, minted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    e.mint.to_string(),
+                    e.payer.to_string(),
+                    e.name,
+                    e.symbol,
+                    e.uri,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::ProposalClosed(e) => {
+            conn.execute(
+                "UPDATE proposals SET closed_at = ?2, escrow_balance = ?3 WHERE address = ?1",
+                params![e.proposal.to_string(), e.timestamp, e.escrow_balance as i64],
+            )?;
+        }
+        ProgramEvent::TeamUpdated(e) => {
+            replace_team(
+                conn,
+                &e.proposal.to_string(),
+                e.members.iter().map(|m| (m.wallet, m.share_bps)),
+            )?;
+        }
+        // Layout changes only, the indexed fields stay the same
+        ProgramEvent::AccountMigrated(_) => {}
+        ProgramEvent::FundingRoundCreated(e) => {
+            let round = e.funding_round.to_string();
+            upsert_funding_round(
+                conn,
+                &round,
+                &e.sponsor,
+                &e.mint,
+                &e.vault,
+                e.round_id,
+                e.matching_pool,
+                e.starts_at,
+                e.ends_at,
+            )?;
+            for proposal in &e.proposals {
+                conn.execute(
+                    "INSERT OR IGNORE INTO round_entries (funding_round, proposal) VALUES (?1, ?2)",
+                    params![round, proposal.to_string()],
+                )?;
+            }
+        }
+        ProgramEvent::RoundContributionCounted(e) => {
+            conn.execute(
+                "UPDATE round_entries SET contributors = ?3, contributed = ?4
+                 WHERE funding_round = ?1 AND proposal = ?2",
+                params![
+                    e.funding_round.to_string(),
+                    e.proposal.to_string(),
+                    e.round_contributors,
+                    e.round_contributed as i64,
+                ],
+            )?;
+        }
+        ProgramEvent::MatchingDistributed(e) => {
+            let round = e.funding_round.to_string();
+            for m in &e.matches {
+                conn.execute(
+                    "UPDATE round_entries SET matched = ?3 WHERE funding_round = ?1 AND proposal = ?2",
+                    params![round, m.proposal.to_string(), m.amount as i64],
+                )?;
+            }
+            conn.execute(
+                "UPDATE funding_rounds SET returned = ?2, distributed_at = ?3 WHERE address = ?1",
+                params![round, e.returned as i64, e.timestamp],
+            )?;
+        }
+        // Sets only grow, so the new attachment is always the last one
+        ProgramEvent::EvidenceAdded(e) => {
+            upsert_attachment(
+                conn,
+                &e.proposal,
+                e.milestone_number,
+                e.set_version as usize - 1,
+                &e.attachment,
+            )?;
+        }
+        // Commitments stay hidden, the vote is indexed once revealed
+        ProgramEvent::VoteCommitted(_) => {}
+        ProgramEvent::VoteRevealMissed(e) => {
+            conn.execute(
+                "INSERT INTO missed_reveals (signature, proposal, milestone, validator, cranker, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    signature,
+                    e.proposal.to_string(),
+                    e.milestone.to_string(),
+                    e.validator.to_string(),
+                    e.cranker.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::ProposalCancelled(e) => {
+            conn.execute(
+                "UPDATE proposals SET status = 'Failed', current_security_deposit = 0,
+                    escrow_balance = ?2, updated_at = ?3
+                 WHERE address = ?1",
+                params![e.proposal.to_string(), e.escrow_balance as i64, e.timestamp],
+            )?;
+        }
+        // Closing only reclaims rent, indexed rows keep their history
+        ProgramEvent::AccountClosed(_) => {}
+        // The accepted part is indexed from the `Funded` that follows
+        ProgramEvent::ContributionTrimmed(_) => {}
+        // An unlocked stretch goal is one more milestone to deliver
+        ProgramEvent::StretchGoalUnlocked(e) => {
+            conn.execute(
+                "UPDATE proposals SET total_milestones = ?2, updated_at = ?3 WHERE address = ?1",
+                params![e.proposal.to_string(), e.milestone_number, e.timestamp],
+            )?;
+        }
+        ProgramEvent::MilestoneChallenged(e) => {
+            conn.execute(
+                "UPDATE milestones SET status = 'Disputed', deadline = ?2, updated_at = ?3
+                 WHERE address = ?1",
+                params![e.milestone.to_string(), e.dispute_deadline, e.timestamp],
+            )?;
+            conn.execute(
+                "INSERT OR REPLACE INTO challenges
+                    (milestone, proposal, challenger, bond, dispute_deadline, challenged_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    e.milestone.to_string(),
+                    e.proposal.to_string(),
+                    e.challenger.to_string(),
+                    e.bond as i64,
+                    e.dispute_deadline,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::AuthorityProposed(e) => {
+            conn.execute(
+                "UPDATE proposals SET pending_authority = ?2, updated_at = ?3 WHERE address = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.pending_authority.map(|key| key.to_string()),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::AuthorityTransferred(e) => {
+            conn.execute(
+                "UPDATE proposals SET authority = ?2, pending_authority = NULL, updated_at = ?3
+                 WHERE address = ?1",
+                params![e.proposal.to_string(), e.authority.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::ChallengeResolved(e) => {
+            let status = if e.upheld { "Failed" } else { "Validated" };
+            conn.execute(
+                "UPDATE milestones SET status = ?2, validation_votes = ?3, updated_at = ?4
+                 WHERE address = ?1",
+                params![
+                    e.milestone.to_string(),
+                    status,
+                    e.validation_votes,
+                    e.timestamp
+                ],
+            )?;
+            conn.execute(
+                "UPDATE challenges SET upheld = ?2, bond_recipient = ?3, resolved_at = ?4
+                 WHERE milestone = ?1",
+                params![
+                    e.milestone.to_string(),
+                    e.upheld,
+                    e.bond_recipient.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
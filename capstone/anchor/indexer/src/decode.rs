@@ -0,0 +1,171 @@
+use anchor::{
+    AccountClosed, AccountMigrated, AuthorityProposed, AuthorityTransferred, ChallengeResolved,
+    ContributionSponsored, ContributionTrimmed, EvidenceAdded, Funded, FundingRoundCreated,
+    FundsReleased, IpNftMinted, MatchingDistributed, MilestoneChallenged, MilestoneFailed,
+    MilestoneSubmitted, MilestoneValidated, PenaltyApplied, PledgeCreated, PledgeEnded,
+    PledgeExecuted, ProposalCancelled, ProposalClosed, ProposalCreated, RefundWithdrawn,
+    RoundContributionCounted, StretchGoalUnlocked, TeamUpdated, VoteCast, VoteCommitted,
+    VoteRevealMissed,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Every event the capstone program emits.
+pub enum ProgramEvent {
+    ProposalCreated(ProposalCreated),
+    Funded(Funded),
+    MilestoneSubmitted(MilestoneSubmitted),
+    VoteCast(VoteCast),
+    MilestoneValidated(MilestoneValidated),
+    MilestoneFailed(MilestoneFailed),
+    FundsReleased(FundsReleased),
+    PenaltyApplied(PenaltyApplied),
+    RefundWithdrawn(RefundWithdrawn),
+    IpNftMinted(IpNftMinted),
+    ProposalClosed(ProposalClosed),
+    TeamUpdated(TeamUpdated),
+    AccountMigrated(AccountMigrated),
+    FundingRoundCreated(FundingRoundCreated),
+    RoundContributionCounted(RoundContributionCounted),
+    MatchingDistributed(MatchingDistributed),
+    VoteCommitted(VoteCommitted),
+    VoteRevealMissed(VoteRevealMissed),
+    EvidenceAdded(EvidenceAdded),
+    ProposalCancelled(ProposalCancelled),
+    AccountClosed(AccountClosed),
+    MilestoneChallenged(MilestoneChallenged),
+    ChallengeResolved(ChallengeResolved),
+    AuthorityProposed(AuthorityProposed),
+    AuthorityTransferred(AuthorityTransferred),
+    ContributionSponsored(ContributionSponsored),
+    PledgeCreated(PledgeCreated),
+    PledgeExecuted(PledgeExecuted),
+    PledgeEnded(PledgeEnded),
+    ContributionTrimmed(ContributionTrimmed),
+    StretchGoalUnlocked(StretchGoalUnlocked),
+}
+
+fn decode_as<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let payload = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::try_from_slice(payload).ok()
+}
+
+impl ProgramEvent {
+    /// Decodes the payload of a `Program data:` log line, discriminator included.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        None.or_else(|| decode_as(data).map(Self::ProposalCreated))
+            .or_else(|| decode_as(data).map(Self::Funded))
+            .or_else(|| decode_as(data).map(Self::MilestoneSubmitted))
+            .or_else(|| decode_as(data).map(Self::VoteCast))
+            .or_else(|| decode_as(data).map(Self::MilestoneValidated))
+            .or_else(|| decode_as(data).map(Self::MilestoneFailed))
+            .or_else(|| decode_as(data).map(Self::FundsReleased))
+            .or_else(|| decode_as(data).map(Self::PenaltyApplied))
+            .or_else(|| decode_as(data).map(Self::RefundWithdrawn))
+            .or_else(|| decode_as(data).map(Self::IpNftMinted))
+            .or_else(|| decode_as(data).map(Self::ProposalClosed))
+            .or_else(|| decode_as(data).map(Self::TeamUpdated))
+            .or_else(|| decode_as(data).map(Self::AccountMigrated))
+            .or_else(|| decode_as(data).map(Self::FundingRoundCreated))
+            .or_else(|| decode_as(data).map(Self::RoundContributionCounted))
+            .or_else(|| decode_as(data).map(Self::MatchingDistributed))
+            .or_else(|| decode_as(data).map(Self::VoteCommitted))
+            .or_else(|| decode_as(data).map(Self::VoteRevealMissed))
+            .or_else(|| decode_as(data).map(Self::EvidenceAdded))
+            .or_else(|| decode_as(data).map(Self::ProposalCancelled))
+            .or_else(|| decode_as(data).map(Self::AccountClosed))
+            .or_else(|| decode_as(data).map(Self::MilestoneChallenged))
+            .or_else(|| decode_as(data).map(Self::ChallengeResolved))
+            .or_else(|| decode_as(data).map(Self::AuthorityProposed))
+            .or_else(|| decode_as(data).map(Self::AuthorityTransferred))
+            .or_else(|| decode_as(data).map(Self::ContributionSponsored))
+            .or_else(|| decode_as(data).map(Self::PledgeCreated))
+            .or_else(|| decode_as(data).map(Self::PledgeExecuted))
+            .or_else(|| decode_as(data).map(Self::PledgeEnded))
+            .or_else(|| decode_as(data).map(Self::ContributionTrimmed))
+            .or_else(|| decode_as(data).map(Self::StretchGoalUnlocked))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ProposalCreated(_) => "ProposalCreated",
+            Self::Funded(_) => "Funded",
+            Self::MilestoneSubmitted(_) => "MilestoneSubmitted",
+            Self::VoteCast(_) => "VoteCast",
+            Self::MilestoneValidated(_) => "MilestoneValidated",
+            Self::MilestoneFailed(_) => "MilestoneFailed",
+            Self::FundsReleased(_) => "FundsReleased",
+            Self::PenaltyApplied(_) => "PenaltyApplied",
+            Self::RefundWithdrawn(_) => "RefundWithdrawn",
+            Self::IpNftMinted(_) => "IpNftMinted",
+            Self::ProposalClosed(_) => "ProposalClosed",
+            Self::TeamUpdated(_) => "TeamUpdated",
+            Self::AccountMigrated(_) => "AccountMigrated",
+            Self::FundingRoundCreated(_) => "FundingRoundCreated",
+            Self::RoundContributionCounted(_) => "RoundContributionCounted",
+            Self::MatchingDistributed(_) => "MatchingDistributed",
+            Self::VoteCommitted(_) => "VoteCommitted",
+            Self::VoteRevealMissed(_) => "VoteRevealMissed",
+            Self::EvidenceAdded(_) => "EvidenceAdded",
+            Self::ProposalCancelled(_) => "ProposalCancelled",
+            Self::AccountClosed(_) => "AccountClosed",
+            Self::MilestoneChallenged(_) => "MilestoneChallenged",
+            Self::ChallengeResolved(_) => "ChallengeResolved",
+            Self::AuthorityProposed(_) => "AuthorityProposed",
+            Self::AuthorityTransferred(_) => "AuthorityTransferred",
+            Self::ContributionSponsored(_) => "ContributionSponsored",
+            Self::PledgeCreated(_) => "PledgeCreated",
+            Self::PledgeExecuted(_) => "PledgeExecuted",
+            Self::PledgeEnded(_) => "PledgeEnded",
+            Self::ContributionTrimmed(_) => "ContributionTrimmed",
+            Self::StretchGoalUnlocked(_) => "StretchGoalUnlocked",
+        }
+    }
+}
+
+/// Extracts the events emitted by `program_id` from a transaction's log messages.
+///
+/// Tracks the invocation stack so that `Program data:` lines written by other
+/// programs in the same transaction are ignored.
+pub fn events_from_logs(program_id: &Pubkey, logs: &[String]) -> Vec<ProgramEvent> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let line = line.trim();
+
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            if stack.last() == Some(&program.as_str()) {
+                if let Some(event) = STANDARD
+                    .decode(data)
+                    .ok()
+                    .and_then(|d| ProgramEvent::decode(&d))
+                {
+                    events.push(event);
+                }
+            }
+            continue;
+        }
+
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut parts = rest.split_whitespace();
+        let (Some(id), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        match action {
+            "invoke" => stack.push(id),
+            "success" | "failed:" => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
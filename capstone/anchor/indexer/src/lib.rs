@@ -0,0 +1,29 @@
+//! Off-chain indexer for the ResearchChain program.
+//!
+//! Decodes the events the program emits (and, when asked, its account data)
+//! and materializes them into normalized SQLite tables for dashboards.
+
+pub mod db;
+pub mod decode;
+pub mod source;
+
+use anchor_lang::prelude::Pubkey;
+
+pub use db::Store;
+pub use decode::{events_from_logs, ProgramEvent};
+pub use researchchain_client::ProgramAccount;
+pub use source::{parse_log_dump, LoggedTransaction};
+
+/// Decodes and stores the events of each transaction, returning how many were new.
+pub fn index_transactions(
+    store: &mut Store,
+    program_id: &Pubkey,
+    transactions: &[LoggedTransaction],
+) -> rusqlite::Result<usize> {
+    let mut applied = 0;
+    for tx in transactions {
+        let events = events_from_logs(program_id, &tx.logs);
+        applied += store.apply_transaction(&tx.signature, tx.slot, &events)?;
+    }
+    Ok(applied)
+}
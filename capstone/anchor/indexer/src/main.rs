@@ -0,0 +1,116 @@
+use std::{error::Error, path::PathBuf, str::FromStr, thread, time::Duration};
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Parser, Subcommand};
+use researchchain_indexer::{
+    index_transactions, parse_log_dump, source::fetch_transactions, ProgramAccount, Store,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+
+#[derive(Parser)]
+#[command(
+    name = "researchchain-indexer",
+    about = "Index ResearchChain events into SQLite"
+)]
+struct Cli {
+    /// SQLite database file, created if missing
+    #[arg(long, default_value = "researchchain.sqlite")]
+    db: String,
+
+    /// Program id to index
+    #[arg(long, default_value_t = anchor::ID.to_string())]
+    program_id: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay `solana logs` dumps or plain log files
+    Replay {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Pull transactions from an RPC node, resuming from the stored cursor
+    Rpc {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+
+        /// Maximum transactions fetched per pass
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+
+        /// Also load the current account data of every program account
+        #[arg(long)]
+        snapshot: bool,
+
+        /// Keep polling with this interval in seconds instead of exiting
+        #[arg(long)]
+        poll: Option<u64>,
+    },
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let program_id = Pubkey::from_str(&cli.program_id)?;
+    let mut store = Store::open(&cli.db)?;
+
+    match cli.command {
+        Command::Replay { files } => {
+            for file in files {
+                let text = std::fs::read_to_string(&file)?;
+                let transactions = parse_log_dump(&file.display().to_string(), &text);
+                let applied = index_transactions(&mut store, &program_id, &transactions)?;
+                println!(
+                    "{}: {} transactions, {} new events",
+                    file.display(),
+                    transactions.len(),
+                    applied
+                );
+            }
+        }
+        Command::Rpc {
+            url,
+            limit,
+            snapshot,
+            poll,
+        } => {
+            let client = RpcClient::new(url);
+
+            if snapshot {
+                let accounts = client.get_program_accounts(&program_id)?;
+                let mut loaded = 0;
+                for (address, account) in accounts {
+                    if let Some(decoded) = ProgramAccount::decode(&account.data) {
+                        store.upsert_account(&address, &decoded)?;
+                        loaded += 1;
+                    }
+                }
+                println!("snapshot: {loaded} accounts");
+            }
+
+            loop {
+                let cursor = store.cursor()?;
+                let transactions =
+                    fetch_transactions(&client, &program_id, cursor.as_deref(), limit)?;
+                let applied = index_transactions(&mut store, &program_id, &transactions)?;
+                if let Some(last) = transactions.last() {
+                    store.set_cursor(&last.signature)?;
+                }
+                println!(
+                    "{} transactions, {} new events",
+                    transactions.len(),
+                    applied
+                );
+
+                match poll {
+                    Some(secs) => thread::sleep(Duration::from_secs(secs)),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
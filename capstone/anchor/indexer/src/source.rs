@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use solana_rpc_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_rpc_client_api::{client_error::Error as ClientError, config::RpcTransactionConfig};
+use solana_signature::Signature;
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, UiTransactionEncoding,
+};
+
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Log messages of one successful transaction.
+pub struct LoggedTransaction {
+    pub signature: String,
+    pub slot: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+/// Parses the output of `solana logs` (or a bare list of log lines) into transactions.
+///
+/// Failed transactions are dropped since their state changes never landed.
+/// Transactions without a `Signature:` line are keyed by `source` and their position.
+pub fn parse_log_dump(source: &str, text: &str) -> Vec<LoggedTransaction> {
+    let mut transactions = Vec::new();
+    let mut current: Option<(LoggedTransaction, bool)> = None;
+
+    for (line_number, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(rest) = trimmed.strip_prefix("Transaction executed in slot ") {
+            finish(&mut current, &mut transactions);
+            let slot = rest.trim_end_matches(':').parse().ok();
+            current = Some((
+                LoggedTransaction {
+                    signature: format!("{source}:{line_number}"),
+                    slot,
+                    logs: Vec::new(),
+                },
+                true,
+            ));
+        } else if let Some(signature) = trimmed.strip_prefix("Signature: ") {
+            if let Some((tx, _)) = current.as_mut() {
+                tx.signature = signature.to_string();
+            }
+        } else if let Some(status) = trimmed.strip_prefix("Status: ") {
+            if let Some((_, ok)) = current.as_mut() {
+                *ok = status == "Ok";
+            }
+        } else if trimmed.starts_with("Program ") {
+            let (tx, _) = current.get_or_insert_with(|| {
+                (
+                    LoggedTransaction {
+                        signature: format!("{source}:{line_number}"),
+                        slot: None,
+                        logs: Vec::new(),
+                    },
+                    true,
+                )
+            });
+            tx.logs.push(trimmed.to_string());
+        }
+    }
+    finish(&mut current, &mut transactions);
+
+    transactions
+}
+
+fn finish(
+    current: &mut Option<(LoggedTransaction, bool)>,
+    transactions: &mut Vec<LoggedTransaction>,
+) {
+    if let Some((tx, ok)) = current.take() {
+        if ok && !tx.logs.is_empty() {
+            transactions.push(tx);
+        }
+    }
+}
+
+/// Fetches up to `limit` of the program's successful transactions newer than `until`,
+/// oldest first, so that repeated runs advance the cursor without gaps.
+pub fn fetch_transactions(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    until: Option<&str>,
+    limit: usize,
+) -> Result<Vec<LoggedTransaction>, Box<ClientError>> {
+    let until = until.and_then(|s| Signature::from_str(s).ok());
+    let mut before = None;
+    let mut statuses = Vec::new();
+
+    // Signatures come newest first, walk back page by page until the cursor
+    loop {
+        let page = client.get_signatures_for_address_with_config(
+            program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURE_PAGE_SIZE),
+                commitment: None,
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Signature::from_str(&last.signature).ok();
+        let exhausted = page.len() < SIGNATURE_PAGE_SIZE;
+        statuses.extend(page);
+        if exhausted {
+            break;
+        }
+    }
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: None,
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut transactions = Vec::new();
+    for status in statuses.into_iter().rev().take(limit) {
+        if status.err.is_some() {
+            continue;
+        }
+        let Ok(signature) = Signature::from_str(&status.signature) else {
+            continue;
+        };
+        let tx = client.get_transaction_with_config(&signature, config)?;
+        let logs = match tx.transaction.meta.map(|meta| meta.log_messages) {
+            Some(OptionSerializer::Some(logs)) => logs,
+            _ => continue,
+        };
+        transactions.push(LoggedTransaction {
+            signature: status.signature,
+            slot: Some(tx.slot),
+            logs,
+        });
+    }
+
+    Ok(transactions)
+}
@@ -0,0 +1,664 @@
+use anchor::{
+    ContentId, Funded, FundsReleased, MilestoneSubmitted, MilestoneValidated, Payout,
+    ProposalCreated, Review, ReviewScores, VoteCast,
+};
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use researchchain_indexer::{index_transactions, parse_log_dump, Store};
+
+fn transaction(slot: u64, signature: &str, status: &str, events: &[Vec<u8>]) -> String {
+    let program = anchor::ID;
+    let mut dump = format!(
+        "Transaction executed in slot {slot}:\n  Signature: {signature}\n  Status: {status}\n  Log Messages:\n    Program {program} invoke [1]\n"
+    );
+    for data in events {
+        dump.push_str(&format!("    Program data: {}\n", STANDARD.encode(data)));
+    }
+    dump.push_str(&format!("    Program {program} success\n"));
+    dump
+}
+
+struct Fixture {
+    proposal: Pubkey,
+    milestone: Pubkey,
+    researcher: Pubkey,
+    alice: Pubkey,
+    bob: Pubkey,
+    dump: String,
+}
+
+fn lifecycle() -> Fixture {
+    let proposal = Pubkey::new_unique();
+    let milestone = Pubkey::new_unique();
+    let escrow = Pubkey::new_unique();
+    let researcher = Pubkey::new_unique();
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let validator = Pubkey::new_unique();
+
+    let created = ProposalCreated {
+        proposal,
+        researcher,
+        escrow,
+        mint: Pubkey::new_unique(),
+        title: "Protein folding".to_string(),
+        ipfs_hash: ContentId::parse("QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR").unwrap(),
+        amount_asked: 900,
+        total_milestones: 3,
+        security_deposit: 90,
+        challenge_period: 0,
+        allowlist_root: None,
+        contribution_limits: Default::default(),
+        stretch_goals: Vec::new(),
+        timestamp: 100,
+    };
+    let funded = |contributor: Pubkey, amount, raised, count| Funded {
+        proposal,
+        contributor,
+        contributor_account: Pubkey::find_program_address(
+            &[b"contributor", contributor.as_ref(), proposal.as_ref()],
+            &anchor::ID,
+        )
+        .0,
+        amount,
+        contributor_total: amount,
+        amount_raised: raised,
+        contributors_count: count,
+        timestamp: 110,
+    };
+    let submitted = MilestoneSubmitted {
+        proposal,
+        milestone,
+        researcher,
+        milestone_number: 1,
+        evidence_hash: ContentId::parse(
+            "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq",
+        )
+        .unwrap(),
+        deadline: 120 + 14 * 86400,
+        commit_deadline: 0,
+        evidence_version: 0,
+        timestamp: 120,
+    };
+    let vote = VoteCast {
+        proposal,
+        milestone,
+        validator,
+        vote: Pubkey::new_unique(),
+        approved: true,
+        validation_votes: 2,
+        total_validators: 3,
+        evidence_version: 0,
+        review: Some(Review {
+            scores: ReviewScores {
+                reproducibility: 8,
+                completeness: 6,
+                quality: 9,
+            },
+            rationale: ContentId::parse(
+                "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq",
+            )
+            .unwrap(),
+        }),
+        timestamp: 130,
+    };
+    let validated = MilestoneValidated {
+        proposal,
+        milestone,
+        milestone_number: 1,
+        validation_votes: 2,
+        total_validators: 3,
+        challenge_ends_at: 130,
+        timestamp: 130,
+    };
+    let released = FundsReleased {
+        proposal,
+        milestone,
+        escrow,
+        milestone_number: 1,
+        amount: 300,
+        payouts: vec![Payout {
+            wallet: researcher,
+            token_account: Pubkey::new_unique(),
+            amount: 300,
+        }],
+        escrow_balance: 790,
+        deposit_returned: 0,
+        proposal_completed: false,
+        timestamp: 140,
+    };
+
+    let mut dump = String::from("Streaming transaction logs. Confirmed commitment\n");
+    dump += &transaction(1, "sig-create", "Ok", &[created.data()]);
+    dump += &transaction(2, "sig-alice", "Ok", &[funded(alice, 600, 600, 1).data()]);
+    dump += &transaction(
+        3,
+        "sig-failed",
+        "Error processing Instruction 0: custom program error: 0x1770",
+        &[funded(bob, 50, 650, 2).data()],
+    );
+    dump += &transaction(4, "sig-bob", "Ok", &[funded(bob, 400, 1000, 2).data()]);
+    dump += &transaction(5, "sig-submit", "Ok", &[submitted.data()]);
+    dump += &transaction(6, "sig-vote", "Ok", &[vote.data(), validated.data()]);
+    dump += &transaction(7, "sig-release", "Ok", &[released.data()]);
+
+    Fixture {
+        proposal,
+        milestone,
+        researcher,
+        alice,
+        bob,
+        dump,
+    }
+}
+
+#[test]
+fn replays_a_proposal_lifecycle_into_tables() {
+    let fixture = lifecycle();
+    let mut store = Store::open_in_memory().unwrap();
+
+    let transactions = parse_log_dump("fixture", &fixture.dump);
+    assert_eq!(transactions.len(), 6, "failed transaction must be skipped");
+
+    let applied = index_transactions(&mut store, &anchor::ID, &transactions).unwrap();
+    assert_eq!(applied, 7);
+
+    let conn = store.connection();
+    let (raised, count, released, escrow, status): (i64, i64, i64, i64, String) = conn
+        .query_row(
+            "SELECT amount_raised, contributors_count, amount_released, escrow_balance, status
+             FROM proposals WHERE address = ?1",
+            [fixture.proposal.to_string()],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!((raised, count, released, escrow), (1000, 2, 300, 790));
+    assert_eq!(status, "Active");
+
+    let amounts: Vec<(String, i64)> = conn
+        .prepare("SELECT wallet, amount FROM contributors ORDER BY amount DESC")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        amounts,
+        vec![
+            (fixture.alice.to_string(), 600),
+            (fixture.bob.to_string(), 400)
+        ]
+    );
+
+    let (status, votes, funds_released): (String, i64, bool) = conn
+        .query_row(
+            "SELECT status, validation_votes, funds_released FROM milestones WHERE address = ?1",
+            [fixture.milestone.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(
+        (status.as_str(), votes, funds_released),
+        ("Validated", 2, true)
+    );
+
+    let payout: (String, i64) = conn
+        .query_row("SELECT wallet, amount FROM payouts", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(payout, (fixture.researcher.to_string(), 300));
+
+    let history: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vote_history", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(history, 1);
+
+    let scores: (i64, i64, i64) = conn
+        .query_row(
+            "SELECT reproducibility, completeness, quality FROM votes",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!(scores, (8, 6, 9));
+}
+
+#[test]
+fn replaying_the_same_logs_is_idempotent() {
+    let fixture = lifecycle();
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_log_dump("fixture", &fixture.dump);
+
+    index_transactions(&mut store, &anchor::ID, &transactions).unwrap();
+    let applied = index_transactions(&mut store, &anchor::ID, &transactions).unwrap();
+    assert_eq!(applied, 0);
+
+    let contributions: i64 = store
+        .connection()
+        .query_row("SELECT COUNT(*) FROM contributions", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(contributions, 2);
+}
+
+#[test]
+fn ignores_data_logged_by_other_programs() {
+    let other = Pubkey::new_unique();
+    let created = ProposalCreated {
+        proposal: Pubkey::new_unique(),
+        researcher: Pubkey::new_unique(),
+        escrow: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        title: "Spoofed".to_string(),
+        ipfs_hash: ContentId::parse("QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR").unwrap(),
+        amount_asked: 1,
+        total_milestones: 1,
+        security_deposit: 0,
+        challenge_period: 0,
+        allowlist_root: None,
+        contribution_limits: Default::default(),
+        stretch_goals: Vec::new(),
+        timestamp: 0,
+    };
+    let dump = format!(
+        "Program {id} invoke [1]\nProgram {other} invoke [2]\nProgram data: {data}\nProgram {other} success\nProgram {id} success\n",
+        id = anchor::ID,
+        data = STANDARD.encode(created.data()),
+    );
+
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_log_dump("raw", &dump);
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        index_transactions(&mut store, &anchor::ID, &transactions).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn tracks_matching_rounds() {
+    let round = Pubkey::new_unique();
+    let proposals = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let created = anchor::FundingRoundCreated {
+        funding_round: round,
+        sponsor: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        vault: Pubkey::new_unique(),
+        round_id: 1,
+        matching_pool: 1_000,
+        starts_at: 100,
+        ends_at: 200,
+        proposals: proposals.to_vec(),
+        timestamp: 90,
+    };
+    let counted = anchor::RoundContributionCounted {
+        funding_round: round,
+        proposal: proposals[0],
+        contributor: Pubkey::new_unique(),
+        amount: 400,
+        contributor_total: 400,
+        round_contributors: 3,
+        round_contributed: 900,
+        timestamp: 150,
+    };
+    let distributed = anchor::MatchingDistributed {
+        funding_round: round,
+        matches: vec![
+            anchor::RoundMatch {
+                proposal: proposals[0],
+                amount: 999,
+            },
+            anchor::RoundMatch {
+                proposal: proposals[1],
+                amount: 0,
+            },
+        ],
+        returned: 1,
+        timestamp: 210,
+    };
+
+    let mut dump = transaction(1, "sig-round", "Ok", &[created.data()]);
+    dump += &transaction(2, "sig-fund", "Ok", &[counted.data()]);
+    dump += &transaction(3, "sig-distribute", "Ok", &[distributed.data()]);
+
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_log_dump("fixture", &dump);
+    index_transactions(&mut store, &anchor::ID, &transactions).unwrap();
+
+    let conn = store.connection();
+    let entries: Vec<(String, i64, i64, i64)> = conn
+        .prepare(
+            "SELECT proposal, contributors, contributed, matched FROM round_entries
+             ORDER BY matched DESC",
+        )
+        .unwrap()
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            (proposals[0].to_string(), 3, 900, 999),
+            (proposals[1].to_string(), 0, 0, 0),
+        ]
+    );
+
+    let (returned, distributed_at): (i64, i64) = conn
+        .query_row(
+            "SELECT returned, distributed_at FROM funding_rounds WHERE address = ?1",
+            [round.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((returned, distributed_at), (1, 210));
+}
+
+#[test]
+fn tracks_evidence_attachments() {
+    let proposal = Pubkey::new_unique();
+    let added = |set_version, content_type, cid: &str| anchor::EvidenceAdded {
+        proposal,
+        evidence: Pubkey::new_unique(),
+        milestone_number: 1,
+        attachment: anchor::Attachment {
+            content_type,
+            cid: ContentId::parse(cid).unwrap(),
+            size: 2_048,
+            sha256: [set_version as u8; 32],
+        },
+        set_version,
+        timestamp: 100,
+    };
+
+    let mut dump = transaction(
+        1,
+        "sig-report",
+        "Ok",
+        &[added(
+            1,
+            anchor::ContentType::Report,
+            "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR",
+        )
+        .data()],
+    );
+    dump += &transaction(
+        2,
+        "sig-dataset",
+        "Ok",
+        &[added(
+            2,
+            anchor::ContentType::Dataset,
+            "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq",
+        )
+        .data()],
+    );
+
+    let mut store = Store::open_in_memory().unwrap();
+    let transactions = parse_log_dump("fixture", &dump);
+    index_transactions(&mut store, &anchor::ID, &transactions).unwrap();
+
+    let attachments: Vec<(i64, String, String, Vec<u8>)> = store
+        .connection()
+        .prepare(
+            "SELECT position, content_type, cid, sha256 FROM attachments
+             WHERE proposal = ?1 AND milestone_number = 1 ORDER BY position",
+        )
+        .unwrap()
+        .query_map([proposal.to_string()], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        attachments,
+        vec![
+            (
+                0,
+                "Report".to_string(),
+                "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR".to_string(),
+                vec![1; 32]
+            ),
+            (
+                1,
+                "Dataset".to_string(),
+                "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq".to_string(),
+                vec![2; 32]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn tracks_milestone_challenges() {
+    let proposal = Pubkey::new_unique();
+    let milestone = Pubkey::new_unique();
+    let challenger = Pubkey::new_unique();
+    let submitted = MilestoneSubmitted {
+        proposal,
+        milestone,
+        researcher: Pubkey::new_unique(),
+        milestone_number: 1,
+        evidence_hash: ContentId::parse("QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR").unwrap(),
+        deadline: 100 + 14 * 86400,
+        commit_deadline: 0,
+        evidence_version: 0,
+        timestamp: 100,
+    };
+    let challenged = anchor::MilestoneChallenged {
+        proposal,
+        milestone,
+        challenger,
+        milestone_number: 1,
+        bond: 15,
+        dispute_deadline: 200 + 7 * 86400,
+        timestamp: 200,
+    };
+    let resolved = anchor::ChallengeResolved {
+        proposal,
+        milestone,
+        challenger,
+        milestone_number: 1,
+        upheld: true,
+        bond: 15,
+        bond_recipient: Pubkey::new_unique(),
+        validation_votes: 1,
+        rejection_votes: 2,
+        timestamp: 300 + 7 * 86400,
+    };
+
+    let mut dump = transaction(1, "sig-submit", "Ok", &[submitted.data()]);
+    dump += &transaction(2, "sig-challenge", "Ok", &[challenged.data()]);
+    let mut store = Store::open_in_memory().unwrap();
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+
+    let status = |store: &Store| -> String {
+        store
+            .connection()
+            .query_row(
+                "SELECT status FROM milestones WHERE address = ?1",
+                [milestone.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap()
+    };
+    assert_eq!(status(&store), "Disputed");
+
+    let dump = transaction(3, "sig-resolve", "Ok", &[resolved.data()]);
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+    assert_eq!(status(&store), "Failed");
+    let (bond, upheld): (i64, bool) = store
+        .connection()
+        .query_row(
+            "SELECT bond, upheld FROM challenges WHERE milestone = ?1",
+            [milestone.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((bond, upheld), (15, true));
+}
+
+#[test]
+fn tracks_authority_transfers() {
+    let fixture = lifecycle();
+    let multisig = Pubkey::new_unique();
+    let proposed = anchor::AuthorityProposed {
+        proposal: fixture.proposal,
+        authority: fixture.researcher,
+        pending_authority: Some(multisig),
+        timestamp: 1_000,
+    };
+    let transferred = anchor::AuthorityTransferred {
+        proposal: fixture.proposal,
+        previous_authority: fixture.researcher,
+        authority: multisig,
+        timestamp: 1_100,
+    };
+
+    let mut store = Store::open_in_memory().unwrap();
+    let authority = |store: &Store| -> (String, Option<String>) {
+        store
+            .connection()
+            .query_row(
+                "SELECT authority, pending_authority FROM proposals WHERE address = ?1",
+                [fixture.proposal.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+    };
+    index_transactions(
+        &mut store,
+        &anchor::ID,
+        &parse_log_dump("fixture", &fixture.dump),
+    )
+    .unwrap();
+    assert_eq!(authority(&store), (fixture.researcher.to_string(), None));
+
+    let dump = transaction(10, "sig-propose", "Ok", &[proposed.data()]);
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+    assert_eq!(
+        authority(&store),
+        (fixture.researcher.to_string(), Some(multisig.to_string()))
+    );
+
+    let dump = transaction(11, "sig-accept", "Ok", &[transferred.data()]);
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+    assert_eq!(authority(&store), (multisig.to_string(), None));
+}
+
+#[test]
+fn records_who_sponsored_a_contribution() {
+    let proposal = Pubkey::new_unique();
+    let sponsor = Pubkey::new_unique();
+    let beneficiary = Pubkey::new_unique();
+    let contributor_account = Pubkey::new_unique();
+    let funded = Funded {
+        proposal,
+        contributor: beneficiary,
+        contributor_account,
+        amount: 300,
+        contributor_total: 300,
+        amount_raised: 300,
+        contributors_count: 1,
+        timestamp: 100,
+    };
+    let sponsored = anchor::ContributionSponsored {
+        proposal,
+        sponsor,
+        beneficiary,
+        contributor_account,
+        amount: 300,
+        memo: Some("grant #12".to_string()),
+        timestamp: 100,
+    };
+
+    let dump = transaction(1, "sig-sponsor", "Ok", &[funded.data(), sponsored.data()]);
+    let mut store = Store::open_in_memory().unwrap();
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+
+    let wallet: String = store
+        .connection()
+        .query_row(
+            "SELECT wallet FROM contributors WHERE address = ?1",
+            [contributor_account.to_string()],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(wallet, beneficiary.to_string());
+    let (payer, memo): (String, Option<String>) = store
+        .connection()
+        .query_row(
+            "SELECT sponsor, memo FROM sponsorships WHERE beneficiary = ?1",
+            [beneficiary.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(payer, sponsor.to_string());
+    assert_eq!(memo.as_deref(), Some("grant #12"));
+}
+
+#[test]
+fn follows_a_pledge_until_it_ends() {
+    let proposal = Pubkey::new_unique();
+    let pledge = Pubkey::new_unique();
+    let contributor = Pubkey::new_unique();
+    let created = anchor::PledgeCreated {
+        proposal,
+        pledge,
+        contributor,
+        amount: 100,
+        installments: 3,
+        next_due: 200,
+        timestamp: 100,
+    };
+    let executed = anchor::PledgeExecuted {
+        proposal,
+        pledge,
+        contributor,
+        cranker: Pubkey::new_unique(),
+        amount: 100,
+        installments_paid: 2,
+        next_due: 300,
+        timestamp: 210,
+    };
+    let ended = anchor::PledgeEnded {
+        proposal,
+        pledge,
+        contributor,
+        installments_paid: 2,
+        reason: anchor::PledgeEnd::Cancelled,
+        timestamp: 250,
+    };
+
+    let mut dump = transaction(1, "sig-pledge", "Ok", &[created.data()]);
+    dump.push_str(&transaction(2, "sig-crank", "Ok", &[executed.data()]));
+    let mut store = Store::open_in_memory().unwrap();
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+
+    let state = |store: &Store| -> (i64, i64, Option<String>) {
+        store
+            .connection()
+            .query_row(
+                "SELECT installments_paid, next_due, ended FROM pledges WHERE address = ?1",
+                [pledge.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap()
+    };
+    assert_eq!(state(&store), (2, 300, None));
+
+    let dump = transaction(3, "sig-cancel", "Ok", &[ended.data()]);
+    index_transactions(&mut store, &anchor::ID, &parse_log_dump("fixture", &dump)).unwrap();
+    assert_eq!(state(&store), (2, 300, Some("Cancelled".to_string())));
+}
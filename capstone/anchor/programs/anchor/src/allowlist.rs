@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::error::ErrorCode;
+use crate::Proposal;
+
+// Contributor allowlists.
+//
+// A proposal created with an `allowlist_root` only accepts money from the
+// wallets in its Merkle tree, each up to its own cap. Leaves hash the wallet
+// and its cap, inner nodes hash their two children in sorted order so proofs
+// don't need to say which side each sibling is on. Leaves and nodes carry a
+// different prefix, so a node can't be passed off as a leaf.
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// A wallet's cap and the sibling hashes from its leaf up to the root.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AllowlistProof {
+    pub cap: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Leaf of `wallet`, allowed to contribute up to `cap` in total.
+pub fn allowlist_leaf(wallet: &Pubkey, cap: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, wallet.as_ref(), &cap.to_le_bytes()]).to_bytes()
+}
+
+/// Parent of two nodes, whichever order they come in.
+pub fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+impl AllowlistProof {
+    /// Root of the tree this proof places `wallet` in.
+    pub fn root(&self, wallet: &Pubkey) -> [u8; 32] {
+        self.proof
+            .iter()
+            .fold(allowlist_leaf(wallet, self.cap), |node, sibling| {
+                allowlist_node(&node, sibling)
+            })
+    }
+}
+
+/// Checks that `wallet` may hold `total` of the proposal's contributions.
+/// Proposals without an allowlist accept anyone.
+pub(crate) fn check_allowlist(
+    proposal: &Proposal,
+    wallet: &Pubkey,
+    total: u64,
+    allowance: Option<&AllowlistProof>,
+) -> Result<()> {
+    let Some(root) = proposal.allowlist_root else {
+        return Ok(());
+    };
+    let allowance = allowance.ok_or(ErrorCode::NotAllowlisted)?;
+    require!(allowance.root(wallet) == root, ErrorCode::NotAllowlisted);
+    require!(total <= allowance.cap, ErrorCode::AllowlistCapExceeded);
+    Ok(())
+}
@@ -2,3 +2,71 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+#[constant]
+pub const MAX_TEAM_MEMBERS: u8 = 5;
+
+#[constant]
+pub const TEAM_SHARE_TOTAL_BPS: u16 = 10_000;
+
+#[constant]
+pub const RECEIPT_SYMBOL: &str = "BACKED";
+
+/// Metadata key of the proposal a receipt was minted for.
+#[constant]
+pub const RECEIPT_PROPOSAL_KEY: &str = "proposal";
+
+/// Metadata key of the contributor's running total.
+#[constant]
+pub const RECEIPT_AMOUNT_KEY: &str = "amount";
+
+#[constant]
+pub const MAX_ROUND_PROPOSALS: u8 = 10;
+
+/// Length of the commit phase of a commit-reveal milestone vote, reveals run
+/// from its end until the milestone deadline.
+#[constant]
+pub const COMMIT_PHASE_SECONDS: i64 = 7 * 86_400;
+
+#[constant]
+pub const MAX_EVIDENCE_ATTACHMENTS: u8 = 8;
+
+/// Share of the initial security deposit a researcher forfeits to the
+/// contributors by cancelling before the first milestone is submitted.
+#[constant]
+pub const CANCEL_PENALTY_BPS: u16 = 1_000;
+
+/// Forfeited share once a milestone has been submitted.
+#[constant]
+pub const CANCEL_PENALTY_AFTER_SUBMISSION_BPS: u16 = 5_000;
+
+/// Longest challenge window a proposal can set between a milestone's
+/// validation and the release of its funds.
+#[constant]
+pub const MAX_CHALLENGE_PERIOD_SECONDS: i64 = 30 * 86_400;
+
+/// Bond a contributor posts to challenge a validated milestone, as a share of
+/// the milestone's payout.
+#[constant]
+pub const CHALLENGE_BOND_BPS: u16 = 500;
+
+/// How long validators have to vote again on a challenged milestone.
+#[constant]
+pub const DISPUTE_PERIOD_SECONDS: i64 = 7 * 86_400;
+
+/// Longest memo a sponsor can attach to a contribution made on someone
+/// else's behalf, in bytes.
+#[constant]
+pub const MAX_SPONSOR_MEMO_LEN: usize = 100;
+
+/// Time between two installments of a pledge.
+#[constant]
+pub const PLEDGE_INTERVAL_SECONDS: i64 = 30 * 86_400;
+
+/// Most stretch milestones a proposal can declare.
+#[constant]
+pub const MAX_STRETCH_GOALS: u8 = 3;
+
+/// Highest score a validator review can give on each criterion.
+#[constant]
+pub const MAX_REVIEW_SCORE: u8 = 10;
@@ -0,0 +1,322 @@
+//! Content identifiers for the research artifacts kept off chain.
+//!
+//! Proposals, milestone evidence and IP-NFT metadata point at IPFS or
+//! Arweave content. Identifiers are parsed from their text form, bare or as
+//! `ipfs://` and `ar://` URIs, and stored as the bytes that identify the
+//! content:
+//!
+//! | Input                                   | Stored as                         |
+//! |-----------------------------------------|-----------------------------------|
+//! | CIDv0, `Qm…` in base58btc               | `IpfsV0 { digest }`               |
+//! | CIDv1, `b…` base32 or `z…` base58btc    | `IpfsV1 { codec, hash, digest }`  |
+//! | Arweave transaction id, 43 base64url    | `Arweave { id }`                  |
+//!
+//! Only multihashes with a 32-byte digest are accepted, which covers
+//! sha2-256, blake2b-256, blake3 and keccak-256. CIDv1 always renders back in
+//! base32, its canonical form.
+
+use std::fmt;
+
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Longest accepted input, Metaplex's limit for metadata URIs.
+pub const MAX_URI_LEN: usize = 200;
+
+const IPFS_SCHEME: &str = "ipfs://";
+const ARWEAVE_SCHEME: &str = "ar://";
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Multicodec of sha2-256, the only hash CIDv0 can use.
+const SHA2_256: u64 = 0x12;
+const DIGEST_LEN: usize = 32;
+const CID_V0_LEN: usize = 46;
+const ARWEAVE_ID_LEN: usize = 43;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum ContentId {
+    /// sha2-256 digest of a dag-pb node
+    IpfsV0 { digest: [u8; 32] },
+    /// Multicodec of the content, multihash code and its digest
+    IpfsV1 {
+        codec: u64,
+        hash: u64,
+        digest: [u8; 32],
+    },
+    /// Transaction id, itself a sha-256 of the transaction signature
+    Arweave { id: [u8; 32] },
+}
+
+impl ContentId {
+    /// Parses a bare CID or Arweave id, or an `ipfs://` / `ar://` URI
+    /// without a path.
+    pub fn parse(input: &str) -> Result<Self> {
+        if input.contains("://") {
+            let (id, path) = Self::parse_uri(input)?;
+            require!(path.is_empty(), ErrorCode::UnexpectedUriPath);
+            return Ok(id);
+        }
+        check_len(input)?;
+
+        if input.len() == ARWEAVE_ID_LEN {
+            parse_arweave(input)
+        } else {
+            parse_cid(input)
+        }
+    }
+
+    /// Parses an `ipfs://` or `ar://` URI, returning the identifier and the
+    /// path after it, e.g. `/metadata.json`.
+    pub fn parse_uri(input: &str) -> Result<(Self, &str)> {
+        check_len(input)?;
+
+        let (rest, is_ipfs) = if let Some(rest) = input.strip_prefix(IPFS_SCHEME) {
+            (rest, true)
+        } else if let Some(rest) = input.strip_prefix(ARWEAVE_SCHEME) {
+            (rest, false)
+        } else {
+            return err!(ErrorCode::UnsupportedUriScheme);
+        };
+        let (id, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        require!(!id.is_empty(), ErrorCode::EmptyContentId);
+
+        let id = if is_ipfs {
+            parse_cid(id)?
+        } else {
+            parse_arweave(id)?
+        };
+        Ok((id, path))
+    }
+
+    /// `ipfs://` or `ar://` URI of the content.
+    pub fn uri(&self) -> String {
+        match self {
+            Self::Arweave { .. } => format!("{ARWEAVE_SCHEME}{self}"),
+            _ => format!("{IPFS_SCHEME}{self}"),
+        }
+    }
+}
+
+impl fmt::Display for ContentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IpfsV0 { digest } => {
+                let mut bytes = vec![SHA2_256 as u8, DIGEST_LEN as u8];
+                bytes.extend_from_slice(digest);
+                f.write_str(&encode_base58(&bytes))
+            }
+            Self::IpfsV1 {
+                codec,
+                hash,
+                digest,
+            } => {
+                let mut bytes = Vec::with_capacity(4 + 2 * 9 + DIGEST_LEN);
+                write_varint(&mut bytes, 1);
+                write_varint(&mut bytes, *codec);
+                write_varint(&mut bytes, *hash);
+                write_varint(&mut bytes, DIGEST_LEN as u64);
+                bytes.extend_from_slice(digest);
+                write!(f, "b{}", encode_base32(&bytes))
+            }
+            Self::Arweave { id } => f.write_str(&encode_base64url(id)),
+        }
+    }
+}
+
+fn check_len(input: &str) -> Result<()> {
+    require!(!input.is_empty(), ErrorCode::EmptyContentId);
+    require!(input.len() <= MAX_URI_LEN, ErrorCode::ContentIdTooLong);
+    Ok(())
+}
+
+fn parse_cid(input: &str) -> Result<ContentId> {
+    // CIDv0 has no multibase prefix, it is always 46 base58 characters
+    if input.len() == CID_V0_LEN && input.starts_with("Qm") {
+        let bytes = decode_base58(input)?;
+        let mut reader = &bytes[..];
+        let digest = read_multihash(&mut reader)?;
+        require!(reader.is_empty(), ErrorCode::InvalidCidLength);
+        return match digest {
+            (SHA2_256, digest) => Ok(ContentId::IpfsV0 { digest }),
+            _ => err!(ErrorCode::UnsupportedMultihash),
+        };
+    }
+
+    let bytes = if let Some(data) = input.strip_prefix('b') {
+        decode_base32(data)?
+    } else if let Some(data) = input.strip_prefix('z') {
+        decode_base58(data)?
+    } else {
+        return err!(ErrorCode::UnknownContentIdFormat);
+    };
+    let mut reader = &bytes[..];
+    require!(read_varint(&mut reader)? == 1, ErrorCode::InvalidCidVersion);
+    let codec = read_varint(&mut reader)?;
+    let (hash, digest) = read_multihash(&mut reader)?;
+    require!(reader.is_empty(), ErrorCode::InvalidCidLength);
+
+    Ok(ContentId::IpfsV1 {
+        codec,
+        hash,
+        digest,
+    })
+}
+
+fn parse_arweave(input: &str) -> Result<ContentId> {
+    require!(input.len() == ARWEAVE_ID_LEN, ErrorCode::InvalidArweaveId);
+    let bytes = decode_base64url(input)?;
+    let id: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| error!(ErrorCode::InvalidArweaveId))?;
+
+    // The last character carries two spare bits, only zero is canonical
+    require!(encode_base64url(&id) == input, ErrorCode::InvalidArweaveId);
+    Ok(ContentId::Arweave { id })
+}
+
+/// Reads a multihash, only 32-byte digests are supported.
+fn read_multihash(reader: &mut &[u8]) -> Result<(u64, [u8; 32])> {
+    let hash = read_varint(reader)?;
+    let len = read_varint(reader)?;
+    require!(len == DIGEST_LEN as u64, ErrorCode::UnsupportedMultihash);
+    require!(reader.len() >= DIGEST_LEN, ErrorCode::InvalidCidLength);
+
+    let (digest, rest) = reader.split_at(DIGEST_LEN);
+    *reader = rest;
+    Ok((hash, digest.try_into().unwrap()))
+}
+
+/// Unsigned LEB128, at most 9 bytes as multiformats require.
+fn read_varint(reader: &mut &[u8]) -> Result<u64> {
+    let mut value: u64 = 0;
+    for (i, byte) in reader.iter().copied().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            // The shortest encoding is the only valid one
+            require!(byte != 0 || i == 0, ErrorCode::InvalidVarint);
+            *reader = &reader[i + 1..];
+            return Ok(value);
+        }
+    }
+    err!(ErrorCode::InvalidVarint)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn decode_base58(input: &str) -> Result<Vec<u8>> {
+    // Big-endian base 256 digits, built up one base58 digit at a time
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(ErrorCode::InvalidBase58)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // Every leading '1' stands for a zero byte
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    let mut out = vec![0; zeros];
+    out.extend(bytes);
+    Ok(out)
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in bytes {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut().rev() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.insert(0, (carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    std::iter::repeat_n(b'1', zeros)
+        .chain(digits.iter().map(|&d| BASE58_ALPHABET[d as usize]))
+        .map(char::from)
+        .collect()
+}
+
+/// RFC 4648 lowercase base32 without padding, as multibase `b` uses it.
+fn decode_base32(input: &str) -> Result<Vec<u8>> {
+    let bytes = decode_bits(input, BASE32_ALPHABET, 5, ErrorCode::InvalidBase32)?;
+
+    // The last character can carry spare bits, only zero is canonical
+    require!(encode_base32(&bytes) == input, ErrorCode::InvalidBase32);
+    Ok(bytes)
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    encode_bits(bytes, BASE32_ALPHABET, 5)
+}
+
+/// RFC 4648 url-safe base64 without padding, as Arweave uses it.
+fn decode_base64url(input: &str) -> Result<Vec<u8>> {
+    decode_bits(input, BASE64URL_ALPHABET, 6, ErrorCode::InvalidBase64Url)
+}
+
+fn encode_base64url(bytes: &[u8]) -> String {
+    encode_bits(bytes, BASE64URL_ALPHABET, 6)
+}
+
+/// Decodes a power-of-two base, dropping the padding bits at the end.
+fn decode_bits(input: &str, alphabet: &[u8], bits: u32, error: ErrorCode) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * bits as usize / 8);
+    let mut buffer: u32 = 0;
+    let mut pending = 0;
+    for c in input.bytes() {
+        let value = alphabet.iter().position(|&a| a == c).ok_or(error)? as u32;
+        buffer = (buffer << bits) | value;
+        pending += bits;
+        if pending >= 8 {
+            pending -= 8;
+            out.push((buffer >> pending) as u8);
+            buffer &= (1 << pending) - 1;
+        }
+    }
+    Ok(out)
+}
+
+fn encode_bits(bytes: &[u8], alphabet: &[u8], bits: u32) -> String {
+    let mut out = String::with_capacity((bytes.len() * 8).div_ceil(bits as usize));
+    let mut buffer: u32 = 0;
+    let mut pending = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        pending += 8;
+        while pending >= bits {
+            pending -= bits;
+            out.push(alphabet[(buffer >> pending) as usize & ((1 << bits) - 1)] as char);
+        }
+        buffer &= (1 << pending) - 1;
+    }
+    if pending > 0 {
+        out.push(alphabet[(buffer << (bits - pending)) as usize & ((1 << bits) - 1)] as char);
+    }
+    out
+}
@@ -1,10 +1,368 @@
+//! Every error the program returns.
+//!
+//! Codes are part of the program's interface and never change once shipped.
+//! Each area owns a block of 100 codes, counted from Anchor's 6000 offset:
+//!
+//! | Codes     | Area                                   |
+//! |-----------|----------------------------------------|
+//! | 6000-6099 | Arithmetic and account validation      |
+//! | 6100-6199 | Proposal and milestone status          |
+//! | 6200-6299 | Proposal creation and closing          |
+//! | 6300-6399 | Contributions and refunds              |
+//! | 6400-6499 | Milestone submission, votes, releases  |
+//! | 6500-6599 | Penalties                              |
+//! | 6600-6699 | Team roster                            |
+//! | 6700-6799 | Account migration                      |
+//! | 6800-6899 | Funding rounds                         |
+//! | 6900-6999 | Content identifiers                    |
+//! | 7000-7099 | Proposal authority                     |
+//! | 7100-7199 | Pledges                                |
+//! | 7200-7299 | Contributor allowlists                 |
+//!
+//! New variants go at the end of their block. A retired variant keeps its
+//! slot so the codes after it don't shift.
+
 use anchor_lang::prelude::*;
 
 #[error_code]
+#[derive(PartialEq, Eq)]
 pub enum ErrorCode {
-    #[msg("Custom error message")]
-    CustomError,
+    // 6000 - Arithmetic and account validation
+    #[msg("Arithmetic overflow")]
+    MathOverflow = 0,
+    #[msg("Invalid escrow account")]
+    InvalidEscrow,
+    #[msg("Invalid mint")]
+    InvalidMint,
+
+    // 6100 - Proposal and milestone status
+    #[msg("Illegal proposal status transition")]
+    IllegalProposalTransition = 100,
+    #[msg("Illegal milestone status transition")]
+    IllegalMilestoneTransition,
+    #[msg("Proposal is not in Active state")]
+    ProposalNotActive,
+    #[msg("Proposal has neither failed nor completed")]
+    ProposalNotFailed,
+    #[msg("Proposal is not in a closable state")]
+    ProposalNotClosable,
+    #[msg("Milestone is not in Active state")]
+    MilestoneNotActive,
+    #[msg("Milestone not validated")]
+    MilestoneNotValidated,
+    #[msg("Milestone is not disputed")]
+    MilestoneNotDisputed,
+    #[msg("Milestone is disputed")]
+    MilestoneDisputed,
+
+    // 6200 - Proposal creation and closing
+    #[msg("Amount asked must be greater than zero")]
+    InvalidAmount = 200,
+    #[msg("Proposal needs between 1 and 252 milestones")]
+    InvalidMilestoneCount,
+    #[msg("Security deposit must be at least 10% of the amount asked")]
+    SecurityDepositTooLow,
+    #[msg("Escrow account still holds funds")]
+    EscrowNotEmpty,
+    #[msg("Contributor has not withdrawn their refund")]
+    RefundNotWithdrawn,
+    #[msg("Rent must go back to the wallet that paid it")]
+    InvalidRentRecipient,
+    #[msg("Account does not belong to this proposal")]
+    AccountNotInProposal,
+    #[msg("Expected each account to close followed by its rent recipient")]
+    InvalidCloseAccounts,
+    #[msg("Challenge period must be between 0 and 30 days")]
+    InvalidChallengePeriod,
+    #[msg("Maximum contribution must be at least the minimum")]
+    InvalidContributionLimits,
+    #[msg("Proposal can declare at most 3 stretch goals")]
+    TooManyStretchGoals,
+    #[msg("Stretch goal thresholds must rise and cover the amount asked and the budgets")]
+    InvalidStretchGoal,
+    #[msg("Close the proposal's milestones, evidence and contributors first")]
+    ProposalHasOpenAccounts,
+    #[msg("Close the milestone's votes first")]
+    MilestoneHasOpenVotes,
+
+    // 6300 - Contributions and refunds
+    #[msg("Enter a valid contribution amount")]
+    InvalidContribution = 300,
+    #[msg("Contributor already withdrawn")]
+    AlreadyWithdrawn,
+    #[msg("No refund available")]
+    NoRefundAvailable,
+    #[msg("Receipt mint address is already in use")]
+    InvalidReceiptMint,
+    #[msg("Sponsor memo is too long")]
+    MemoTooLong,
+    #[msg("Contribution is below the proposal's minimum")]
+    ContributionBelowMinimum,
+    #[msg("Contribution is above the proposal's maximum")]
+    ContributionAboveMaximum,
+    #[msg("Proposal has reached its funding goal")]
+    FundingGoalReached,
+
+    // 6400 - Milestone submission, votes and releases
+    #[msg("Milestone number must follow sequence")]
+    InvalidMilestoneOrder = 400,
+    #[msg("Milestone exceeds total allowed milestones")]
+    ExceedsTotalMilestones,
+    #[msg("Validation deadline has passed")]
+    ValidationDeadlinePassed,
+    #[msg("Invalid milestone for this proposal")]
+    InvalidMilestone,
+    #[msg("Funds already released")]
+    FundsAlreadyReleased,
+    #[msg("Insufficient funds in escrow")]
+    InsufficientFunds,
+    #[msg("Milestone votes must be committed and revealed")]
+    CommitRevealVoting,
+    #[msg("Milestone uses open voting")]
+    OpenVoting,
+    #[msg("Commit phase has ended")]
+    CommitPhaseClosed,
+    #[msg("Reveal phase has not started")]
+    RevealPhaseNotOpen,
+    #[msg("Reveal phase has not ended")]
+    RevealPhaseOpen,
+    #[msg("Vote already revealed")]
+    VoteAlreadyRevealed,
+    #[msg("Revealed vote does not match the commitment")]
+    CommitmentMismatch,
+    #[msg("Evidence is locked once the milestone is submitted")]
+    EvidenceLocked,
+    #[msg("Milestone evidence holds at most 8 attachments")]
+    TooManyAttachments,
+    #[msg("Attachment needs a non-zero size")]
+    InvalidAttachment,
+    #[msg("Unrevealed votes are closed by penalize_unrevealed_vote")]
+    UnrevealedVote,
+    #[msg("Funds are locked until the challenge period ends")]
+    ChallengePeriodActive,
+    #[msg("Challenge period has ended")]
+    ChallengePeriodEnded,
+    #[msg("Milestone was already challenged")]
+    AlreadyChallenged,
+    #[msg("Only contributors can challenge a milestone")]
+    NotAContributor,
+    #[msg("Dispute vote has not ended")]
+    DisputeOpen,
+    #[msg("Bond must go to the challenger or the proposal authority")]
+    InvalidBondRecipient,
+    #[msg("Stretch goal has not been unlocked")]
+    StretchGoalLocked,
+    #[msg("Review scores must be between 0 and 10")]
+    InvalidReviewScore,
+
+    // 6500 - Penalties
+    #[msg("Maximum penalties (4) already applied")]
+    MaxPenaltiesReached = 500,
+    #[msg("Insufficient security deposit")]
+    InsufficientSecurityDeposit,
+
+    // 6600 - Team roster
+    #[msg("Team must have between 1 and 5 members")]
+    InvalidTeamSize = 600,
+    #[msg("Team shares must be positive and sum to 10000 basis points")]
+    InvalidTeamShares,
+    #[msg("Team member listed more than once")]
+    DuplicateTeamMember,
+    #[msg("Every current team member must sign the change")]
+    MissingTeamApproval,
+    #[msg("Missing token account for a team member")]
+    MissingMemberTokenAccount,
+    #[msg("Team member token account has the wrong owner or mint")]
+    InvalidMemberTokenAccount,
+
+    // 6700 - Account migration
+    #[msg("Account is not owned by this program")]
+    InvalidAccountOwner = 700,
+    #[msg("Account type cannot be migrated")]
+    UnknownAccountType,
+    #[msg("Account is already at the current version")]
+    AccountAlreadyMigrated,
+    #[msg("Account has an unsupported layout version")]
+    UnsupportedAccountVersion,
+
+    // 6800 - Funding rounds
+    #[msg("Round must end after it starts, and in the future")]
+    InvalidRoundWindow = 800,
+    #[msg("Round needs between 1 and 10 distinct proposals")]
+    InvalidRoundProposals,
+    #[msg("Funding round is not open")]
+    RoundNotOpen,
+    #[msg("Proposal is not part of this funding round")]
+    ProposalNotInRound,
+    #[msg("Funding round and round contribution must be passed together")]
+    MissingRoundContribution,
+    #[msg("Funding round has not ended yet")]
+    RoundNotEnded,
+    #[msg("Matching pool already distributed")]
+    RoundAlreadyDistributed,
+    #[msg("Expected a proposal and its escrow for every proposal in the round")]
+    InvalidRoundAccounts,
+
+    // 6900 - Content identifiers
+    #[msg("Content identifier is longer than 200 characters")]
+    ContentIdTooLong = 900,
+    #[msg("Content identifier is empty")]
+    EmptyContentId,
+    #[msg("Not an IPFS CID or Arweave transaction id")]
+    UnknownContentIdFormat,
+    #[msg("URI scheme must be ipfs:// or ar://")]
+    UnsupportedUriScheme,
+    #[msg("Content identifier must not have a path")]
+    UnexpectedUriPath,
+    #[msg("Invalid base58btc character")]
+    InvalidBase58,
+    #[msg("Invalid base32 character")]
+    InvalidBase32,
+    #[msg("Invalid base64url character")]
+    InvalidBase64Url,
+    #[msg("CID version must be 0 or 1")]
+    InvalidCidVersion,
+    #[msg("Malformed varint in CID")]
+    InvalidVarint,
+    #[msg("Only multihashes with a 32-byte digest are supported")]
+    UnsupportedMultihash,
+    #[msg("CID has trailing or missing bytes")]
+    InvalidCidLength,
+    #[msg("Arweave id must be 43 base64url characters encoding 32 bytes")]
+    InvalidArweaveId,
+
+    // 7000 - Proposal authority
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority = 1000,
+    #[msg("Only the pending authority can accept the transfer")]
+    NotPendingAuthority,
+
+    // 7100 - Pledges
+    #[msg("A pledge needs at least two installments")]
+    InvalidPledge = 1100,
+    #[msg("Next pledge installment is not due yet")]
+    PledgeNotDue,
+    #[msg("Pledge has ended, cancel it to revoke the rest of its allowance")]
+    PledgeAlreadyEnded,
+
+    // 7200 - Contributor allowlists
+    #[msg("Wallet is not on the proposal's allowlist")]
+    NotAllowlisted = 1200,
+    #[msg("Contribution exceeds the wallet's allowlist cap")]
+    AllowlistCapExceeded,
+}
+
+impl ErrorCode {
+    /// Every variant, in code order.
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::MathOverflow,
+        ErrorCode::InvalidEscrow,
+        ErrorCode::InvalidMint,
+        ErrorCode::IllegalProposalTransition,
+        ErrorCode::IllegalMilestoneTransition,
+        ErrorCode::ProposalNotActive,
+        ErrorCode::ProposalNotFailed,
+        ErrorCode::ProposalNotClosable,
+        ErrorCode::MilestoneNotActive,
+        ErrorCode::MilestoneNotValidated,
+        ErrorCode::MilestoneNotDisputed,
+        ErrorCode::MilestoneDisputed,
+        ErrorCode::InvalidAmount,
+        ErrorCode::InvalidMilestoneCount,
+        ErrorCode::SecurityDepositTooLow,
+        ErrorCode::EscrowNotEmpty,
+        ErrorCode::RefundNotWithdrawn,
+        ErrorCode::InvalidRentRecipient,
+        ErrorCode::AccountNotInProposal,
+        ErrorCode::InvalidCloseAccounts,
+        ErrorCode::InvalidChallengePeriod,
+        ErrorCode::InvalidContributionLimits,
+        ErrorCode::TooManyStretchGoals,
+        ErrorCode::InvalidStretchGoal,
+        ErrorCode::ProposalHasOpenAccounts,
+        ErrorCode::MilestoneHasOpenVotes,
+        ErrorCode::InvalidContribution,
+        ErrorCode::AlreadyWithdrawn,
+        ErrorCode::NoRefundAvailable,
+        ErrorCode::InvalidReceiptMint,
+        ErrorCode::MemoTooLong,
+        ErrorCode::ContributionBelowMinimum,
+        ErrorCode::ContributionAboveMaximum,
+        ErrorCode::FundingGoalReached,
+        ErrorCode::InvalidMilestoneOrder,
+        ErrorCode::ExceedsTotalMilestones,
+        ErrorCode::ValidationDeadlinePassed,
+        ErrorCode::InvalidMilestone,
+        ErrorCode::FundsAlreadyReleased,
+        ErrorCode::InsufficientFunds,
+        ErrorCode::CommitRevealVoting,
+        ErrorCode::OpenVoting,
+        ErrorCode::CommitPhaseClosed,
+        ErrorCode::RevealPhaseNotOpen,
+        ErrorCode::RevealPhaseOpen,
+        ErrorCode::VoteAlreadyRevealed,
+        ErrorCode::CommitmentMismatch,
+        ErrorCode::EvidenceLocked,
+        ErrorCode::TooManyAttachments,
+        ErrorCode::InvalidAttachment,
+        ErrorCode::UnrevealedVote,
+        ErrorCode::ChallengePeriodActive,
+        ErrorCode::ChallengePeriodEnded,
+        ErrorCode::AlreadyChallenged,
+        ErrorCode::NotAContributor,
+        ErrorCode::DisputeOpen,
+        ErrorCode::InvalidBondRecipient,
+        ErrorCode::StretchGoalLocked,
+        ErrorCode::InvalidReviewScore,
+        ErrorCode::MaxPenaltiesReached,
+        ErrorCode::InsufficientSecurityDeposit,
+        ErrorCode::InvalidTeamSize,
+        ErrorCode::InvalidTeamShares,
+        ErrorCode::DuplicateTeamMember,
+        ErrorCode::MissingTeamApproval,
+        ErrorCode::MissingMemberTokenAccount,
+        ErrorCode::InvalidMemberTokenAccount,
+        ErrorCode::InvalidAccountOwner,
+        ErrorCode::UnknownAccountType,
+        ErrorCode::AccountAlreadyMigrated,
+        ErrorCode::UnsupportedAccountVersion,
+        ErrorCode::InvalidRoundWindow,
+        ErrorCode::InvalidRoundProposals,
+        ErrorCode::RoundNotOpen,
+        ErrorCode::ProposalNotInRound,
+        ErrorCode::MissingRoundContribution,
+        ErrorCode::RoundNotEnded,
+        ErrorCode::RoundAlreadyDistributed,
+        ErrorCode::InvalidRoundAccounts,
+        ErrorCode::ContentIdTooLong,
+        ErrorCode::EmptyContentId,
+        ErrorCode::UnknownContentIdFormat,
+        ErrorCode::UnsupportedUriScheme,
+        ErrorCode::UnexpectedUriPath,
+        ErrorCode::InvalidBase58,
+        ErrorCode::InvalidBase32,
+        ErrorCode::InvalidBase64Url,
+        ErrorCode::InvalidCidVersion,
+        ErrorCode::InvalidVarint,
+        ErrorCode::UnsupportedMultihash,
+        ErrorCode::InvalidCidLength,
+        ErrorCode::InvalidArweaveId,
+        ErrorCode::NoPendingAuthority,
+        ErrorCode::NotPendingAuthority,
+        ErrorCode::InvalidPledge,
+        ErrorCode::PledgeNotDue,
+        ErrorCode::PledgeAlreadyEnded,
+        ErrorCode::NotAllowlisted,
+        ErrorCode::AllowlistCapExceeded,
+    ];
+
+    /// The code the program returns for this error.
+    pub fn code(self) -> u32 {
+        self.into()
+    }
 
-    #[msg("Enter valid amount!")]
-    InvalidAmount,
+    /// Looks up the error behind a custom program error code.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error| error.code() == code)
+    }
 }
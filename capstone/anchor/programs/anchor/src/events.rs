@@ -0,0 +1,362 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    Attachment, ContentId, ContributionLimits, PledgeEnd, Review, StretchGoal, TeamMember,
+};
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub researcher: Pubkey,
+    pub escrow: Pubkey,
+    pub mint: Pubkey,
+    pub title: String,
+    pub ipfs_hash: ContentId,
+    pub amount_asked: u64,
+    pub total_milestones: u8,
+    pub security_deposit: u64,
+    pub challenge_period: i64,
+    pub allowlist_root: Option<[u8; 32]>,
+    pub contribution_limits: ContributionLimits,
+    pub stretch_goals: Vec<StretchGoal>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Funded {
+    pub proposal: Pubkey,
+    pub contributor: Pubkey,
+    pub contributor_account: Pubkey,
+    pub amount: u64,
+    pub contributor_total: u64, //Lifetime contribution of this contributor
+    pub amount_raised: u64,     //Proposal total after this contribution
+    pub contributors_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ContributionSponsored {
+    pub proposal: Pubkey,
+    pub sponsor: Pubkey,     //Paid for the contribution
+    pub beneficiary: Pubkey, //Holds its refund rights and receipt
+    pub contributor_account: Pubkey,
+    pub amount: u64,
+    pub memo: Option<String>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PledgeCreated {
+    pub proposal: Pubkey,
+    pub pledge: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64, //Per installment
+    pub installments: u16,
+    pub next_due: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PledgeExecuted {
+    pub proposal: Pubkey,
+    pub pledge: Pubkey,
+    pub contributor: Pubkey,
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub installments_paid: u16,
+    pub next_due: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PledgeEnded {
+    pub proposal: Pubkey,
+    pub pledge: Pubkey,
+    pub contributor: Pubkey,
+    pub installments_paid: u16,
+    pub reason: PledgeEnd,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneSubmitted {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub researcher: Pubkey,
+    pub milestone_number: u8,
+    pub evidence_hash: ContentId,
+    pub deadline: i64,
+    pub commit_deadline: i64,
+    pub evidence_version: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EvidenceAdded {
+    pub proposal: Pubkey,
+    pub evidence: Pubkey,
+    pub milestone_number: u8,
+    pub attachment: Attachment,
+    pub set_version: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub validator: Pubkey,
+    pub vote: Pubkey,
+    pub approved: bool,
+    pub validation_votes: u32,
+    pub total_validators: u32,
+    pub evidence_version: u16,
+    pub review: Option<Review>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCommitted {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub validator: Pubkey,
+    pub vote: Pubkey,
+    pub commitment: [u8; 32],
+    pub commits: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteRevealMissed {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub validator: Pubkey,
+    pub vote: Pubkey,
+    pub cranker: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneValidated {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub milestone_number: u8,
+    pub validation_votes: u32,
+    pub total_validators: u32,
+    pub challenge_ends_at: i64, //Funds can be released from then on
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneFailed {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub milestone_number: u8,
+    pub rejection_votes: u32,
+    pub total_validators: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneChallenged {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub challenger: Pubkey,
+    pub milestone_number: u8,
+    pub bond: u64,
+    pub dispute_deadline: i64, //Validators vote again until then
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ChallengeResolved {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub challenger: Pubkey,
+    pub milestone_number: u8,
+    pub upheld: bool, //The milestone failed and the bond went back to the challenger
+    pub bond: u64,
+    pub bond_recipient: Pubkey,
+    pub validation_votes: u32,
+    pub rejection_votes: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FundsReleased {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub escrow: Pubkey,
+    pub milestone_number: u8,
+    pub amount: u64, //Total paid out for the milestone
+    pub payouts: Vec<Payout>,
+    pub escrow_balance: u64, //Escrow balance left after the release
+    pub deposit_returned: u64, //Security deposit paid back to the authority on completion
+    pub proposal_completed: bool,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct Payout {
+    pub wallet: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PenaltyApplied {
+    pub proposal: Pubkey,
+    pub milestone: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub penalty_counter: u8,
+    pub remaining_security_deposit: u64,
+    pub proposal_failed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub proposal: Pubkey,
+    pub researcher: Pubkey,
+    pub milestones_submitted: u8,
+    pub penalty: u64,
+    pub deposit_returned: u64,
+    pub escrow_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundWithdrawn {
+    pub proposal: Pubkey,
+    pub contributor: Pubkey,
+    pub contributor_account: Pubkey,
+    pub amount: u64,
+    pub contributed: u64,
+    pub escrow_balance: u64, //Escrow balance left after the refund
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct IpNftMinted {
+    pub mint: Pubkey,
+    pub metadata: Pubkey,
+    pub master_edition: Pubkey,
+    pub payer: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub proposal: Pubkey,
+    pub researcher: Pubkey,
+    pub escrow: Pubkey,
+    pub escrow_balance: u64,
+    pub escrow_closed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountClosed {
+    pub proposal: Pubkey,
+    pub account: Pubkey,
+    pub rent_recipient: Pubkey,
+    pub lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TeamUpdated {
+    pub proposal: Pubkey,
+    pub members: Vec<TeamMember>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub proposal: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>, //`None` when a transfer was cancelled
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub proposal: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub payer: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub space: u32, //Allocated size after the migration
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FundingRoundCreated {
+    pub funding_round: Pubkey,
+    pub sponsor: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub round_id: u64,
+    pub matching_pool: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub proposals: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundContributionCounted {
+    pub funding_round: Pubkey,
+    pub proposal: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub contributor_total: u64, //Contributor's total towards the proposal in this round
+    pub round_contributors: u32,
+    pub round_contributed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MatchingDistributed {
+    pub funding_round: Pubkey,
+    pub matches: Vec<RoundMatch>,
+    pub returned: u64, //Sent back to the sponsor
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct RoundMatch {
+    pub proposal: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ContributionTrimmed {
+    pub proposal: Pubkey,
+    pub contributor: Pubkey,
+    pub requested: u64,
+    pub accepted: u64, //The rest never left the contributor's token account
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StretchGoalUnlocked {
+    pub proposal: Pubkey,
+    pub milestone_number: u8, //Milestone the goal is delivered as
+    pub threshold: u64,
+    pub budget: u64,
+    pub amount_raised: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::{AuthorityTransferred, Proposal};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    // Signing proves the new authority can act, e.g. that a multisig PDA
+    // is owned by a program that can sign for it
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Completes a transfer started with `propose_authority`. The team roster
+/// is left as is; changing it goes through `update_team` and its approvals.
+pub fn accept_authority_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    let pending = proposal
+        .pending_authority
+        .ok_or(ErrorCode::NoPendingAuthority)?;
+    require_keys_eq!(pending, new_authority, ErrorCode::NotPendingAuthority);
+
+    let previous_authority = proposal.authority;
+    proposal.authority = new_authority;
+    proposal.pending_authority = None;
+    proposal.updated_at = now;
+
+    emit!(AuthorityTransferred {
+        proposal: proposal.key(),
+        previous_authority,
+        authority: new_authority,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::state_machine::require_status;
+use crate::{
+    Attachment, EvidenceAdded, MilestoneEvidence, Proposal, ProposalStatus, Versioned,
+    MAX_EVIDENCE_ATTACHMENTS,
+};
+
+#[derive(Accounts)]
+#[instruction(milestone_number: u8)]
+pub struct AddEvidence<'info> {
+    pub authority: Signer<'info>,
+
+    // Separate from the authority, which may be a PDA that can't pay
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MilestoneEvidence::INIT_SPACE,
+        seeds = [
+            b"evidence",
+            proposal.key().as_ref(),
+            &milestone_number.to_le_bytes()
+        ],
+        bump
+    )]
+    pub evidence: Account<'info, MilestoneEvidence>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Appends an attachment to the next milestone's evidence. Voting opens with
+/// `submit_milestone`, after which the set can no longer change.
+pub fn add_evidence_handler(
+    ctx: Context<AddEvidence>,
+    milestone_number: u8,
+    attachment: Attachment,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+
+    // Only the milestone that comes next is still open for evidence
+    require_status(
+        &proposal.status,
+        &[ProposalStatus::Active],
+        ErrorCode::ProposalNotActive,
+    )?;
+    require!(
+        milestone_number > proposal.current_milestone,
+        ErrorCode::EvidenceLocked
+    );
+    require!(
+        milestone_number == proposal.current_milestone + 1,
+        ErrorCode::InvalidMilestoneOrder
+    );
+    require!(
+        (milestone_number as usize)
+            <= proposal.total_milestones as usize + proposal.stretch_goals.len(),
+        ErrorCode::ExceedsTotalMilestones
+    );
+    // Same as `submit_milestone`, stretch milestones follow once unlocked
+    require!(
+        milestone_number <= proposal.milestone_count(),
+        ErrorCode::StretchGoalLocked
+    );
+
+    // The cid is well-formed by construction, it is stored in binary
+    require!(attachment.size > 0, ErrorCode::InvalidAttachment);
+
+    let evidence = &mut ctx.accounts.evidence;
+    if evidence.proposal == Pubkey::default() {
+        evidence.version = MilestoneEvidence::VERSION;
+        evidence.proposal = proposal.key();
+        evidence.milestone_number = milestone_number;
+        evidence.bump = ctx.bumps.evidence;
+        evidence.payer = ctx.accounts.payer.key();
+        proposal.open_accounts = proposal
+            .open_accounts
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    require!(
+        evidence.attachments.len() < MAX_EVIDENCE_ATTACHMENTS as usize,
+        ErrorCode::TooManyAttachments
+    );
+    evidence.attachments.push(attachment.clone());
+    evidence.set_version = evidence
+        .set_version
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(EvidenceAdded {
+        proposal: proposal.key(),
+        evidence: evidence.key(),
+        milestone_number,
+        attachment,
+        set_version: evidence.set_version,
+        timestamp: now,
+    });
+
+    Ok(())
+}
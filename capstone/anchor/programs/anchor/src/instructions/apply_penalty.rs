@@ -1,4 +1,6 @@
-use crate::{Milestone, MilestoneStatus, Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::transition;
+use crate::{Milestone, MilestoneStatus, PenaltyApplied, Proposal, ProposalStatus};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
@@ -14,7 +16,8 @@ pub struct ApplyPenalty<'info> {
             proposal.researcher.as_ref(),
             proposal.title.as_bytes()
         ],
-        bump
+        bump,
+        constraint = proposal.status == ProposalStatus::Active @ ErrorCode::ProposalNotActive,
     )]
     pub proposal: Account<'info, Proposal>,
 
@@ -48,7 +51,7 @@ pub fn apply_penalty_handler(ctx: Context<ApplyPenalty>) -> Result<()> {
     
     // Get account_info before mutable borrow
     let proposal_info = ctx.accounts.proposal.to_account_info();
-    let researcher = ctx.accounts.proposal.researcher.clone();
+    let researcher = ctx.accounts.proposal.researcher;
     let title = ctx.accounts.proposal.title.clone();
     
     // Now get mutable references
@@ -86,13 +89,6 @@ pub fn apply_penalty_handler(ctx: Context<ApplyPenalty>) -> Result<()> {
         .checked_sub(penalty_amount)
         .ok_or(ErrorCode::MathOverflow)?;
 
-    // Log transfer details
-    msg!(
-        "Transferring {} tokens to treasury at {}",
-        penalty_amount,
-        ctx.accounts.treasury.key()
-    );
-
     let seeds = &[
         b"proposal".as_ref(),
         researcher.as_ref(),
@@ -117,28 +113,24 @@ pub fn apply_penalty_handler(ctx: Context<ApplyPenalty>) -> Result<()> {
     proposal.penalty_counter += 1;
 
     if proposal.penalty_counter >= 4 {
-        proposal.status = ProposalStatus::Failed;
+        transition(&mut proposal.status, ProposalStatus::Failed)?;
         proposal.current_security_deposit = 0;
     }
 
     proposal.updated_at = clock.unix_timestamp;
     milestone.updated_at = clock.unix_timestamp;
 
-    Ok(())
-}
+    emit!(PenaltyApplied {
+        proposal: proposal.key(),
+        milestone: milestone.key(),
+        authority: ctx.accounts.authority.key(),
+        treasury: ctx.accounts.treasury.key(),
+        amount: penalty_amount,
+        penalty_counter: proposal.penalty_counter,
+        remaining_security_deposit: proposal.current_security_deposit,
+        proposal_failed: proposal.status == ProposalStatus::Failed,
+        timestamp: clock.unix_timestamp,
+    });
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Proposal is not in Active state")]
-    ProposalNotActive,
-    #[msg("Maximum penalties (4) already applied")]
-    MaxPenaltiesReached,
-    #[msg("Arithmetic overflow")]
-    MathOverflow,
-    #[msg("Insufficient security deposit")]
-    InsufficientSecurityDeposit,
-    #[msg("Invalid escrow account")]
-    InvalidEscrow,
-    #[msg("Invalid mint")]
-    InvalidMint,
+    Ok(())
 }
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Revoke, Token, TokenAccount};
+
+use crate::{Pledge, PledgeEnd, PledgeEnded};
+
+#[derive(Accounts)]
+pub struct CancelPledge<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        close = contributor,
+        has_one = contributor,
+        seeds = [
+            b"pledge",
+            contributor.key().as_ref(),
+            pledge.proposal.as_ref()
+        ],
+        bump = pledge.bump
+    )]
+    pub pledge: Account<'info, Pledge>,
+
+    #[account(mut, address = pledge.token_account)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Stops a pledge and revokes its delegate approval. Installments already
+/// paid stay with the proposal. Also closes pledges `execute_pledge` ended
+/// with part of the allowance left, which only the owner can revoke.
+pub fn cancel_pledge_handler(ctx: Context<CancelPledge>) -> Result<()> {
+    let pledge = &ctx.accounts.pledge;
+
+    // Leave any delegate the contributor has approved since alone
+    if ctx.accounts.token_account.delegate == Some(pledge.key()).into() {
+        token::revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.contributor.to_account_info(),
+            },
+        ))?;
+    }
+
+    // A pledge `execute_pledge` already ended was reported then
+    if pledge.ended.is_none() {
+        emit!(PledgeEnded {
+            proposal: pledge.proposal,
+            pledge: pledge.key(),
+            contributor: pledge.contributor,
+            installments_paid: pledge.installments_paid,
+            reason: PledgeEnd::Cancelled,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    Ok(())
+}
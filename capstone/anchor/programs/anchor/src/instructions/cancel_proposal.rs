@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::state_machine::{require_status, transition};
+use crate::{
+    Proposal, ProposalCancelled, ProposalStatus, CANCEL_PENALTY_AFTER_SUBMISSION_BPS,
+    CANCEL_PENALTY_BPS,
+};
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", proposal.key().as_ref()],
+        bump,
+        constraint = escrow.key() == proposal.funds_escrow @ ErrorCode::InvalidEscrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.owner == authority.key(),
+        constraint = authority_token_account.mint == escrow.mint @ ErrorCode::InvalidMint,
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn cancel_proposal_handler(ctx: Context<CancelProposal>) -> Result<()> {
+    let clock = Clock::get()?;
+    let proposal = &ctx.accounts.proposal;
+
+    require_status(
+        &proposal.status,
+        &[ProposalStatus::Active],
+        ErrorCode::ProposalNotActive,
+    )?;
+
+    // 1. The penalty is a share of the initial deposit, steeper once a
+    //    milestone was submitted. It stays in escrow and is refunded to the
+    //    contributors, so it is waived when nobody contributed.
+    let penalty_bps = if proposal.current_milestone == 0 {
+        CANCEL_PENALTY_BPS
+    } else {
+        CANCEL_PENALTY_AFTER_SUBMISSION_BPS
+    };
+    let penalty = if proposal.amount_raised == 0 {
+        0
+    } else {
+        ((proposal.initial_security_deposit as u128)
+            .checked_mul(penalty_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / 10_000) as u64
+    }
+    .min(proposal.current_security_deposit);
+
+    // 2. The rest of the deposit goes back to the authority, as far as the
+    //    escrow still holds it
+    let returned = (proposal.current_security_deposit - penalty).min(ctx.accounts.escrow.amount);
+    if returned > 0 {
+        let seeds = &[
+            b"proposal".as_ref(),
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes(),
+            &[ctx.bumps.proposal],
+        ];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.proposal.to_account_info(),
+                },
+                &[seeds],
+            ),
+            returned,
+        )?;
+    }
+
+    // 3. Failed proposals refund the escrow through `withdraw_funds`
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.current_security_deposit = 0;
+    transition(&mut proposal.status, ProposalStatus::Failed)?;
+    proposal.updated_at = clock.unix_timestamp;
+
+    ctx.accounts.escrow.reload()?;
+    emit!(ProposalCancelled {
+        proposal: proposal.key(),
+        researcher: proposal.researcher,
+        milestones_submitted: proposal.current_milestone,
+        penalty,
+        deposit_returned: returned,
+        escrow_balance: ctx.accounts.escrow.amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::state_machine::{require_status, transition};
+use crate::{
+    Contributor, Milestone, MilestoneChallenged, MilestoneStatus, Proposal, ProposalStatus,
+    CHALLENGE_BOND_BPS, DISPUTE_PERIOD_SECONDS,
+};
+
+#[derive(Accounts)]
+pub struct ChallengeMilestone<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        seeds = [
+            b"contributor",
+            challenger.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump,
+        constraint = contributor_account.amount > 0 @ ErrorCode::NotAContributor,
+    )]
+    pub contributor_account: Account<'info, Contributor>,
+
+    #[account(address = proposal.funds_escrow @ ErrorCode::InvalidEscrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(address = escrow.mint @ ErrorCode::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    // Holds the bond until `resolve_challenge`
+    #[account(
+        init,
+        payer = challenger,
+        seeds = [b"challenge", milestone.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = proposal,
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = challenger_token_account.owner == challenger.key(),
+        constraint = challenger_token_account.mint == mint.key() @ ErrorCode::InvalidMint,
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Disputes a validated milestone before its funds are released. The
+/// validators vote on it again, openly, for `DISPUTE_PERIOD_SECONDS`.
+pub fn challenge_milestone_handler(ctx: Context<ChallengeMilestone>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &ctx.accounts.proposal;
+    let milestone = &ctx.accounts.milestone;
+
+    require_status(
+        &proposal.status,
+        &[ProposalStatus::Active],
+        ErrorCode::ProposalNotActive,
+    )?;
+    require_status(
+        &milestone.status,
+        &[MilestoneStatus::Validated],
+        ErrorCode::MilestoneNotValidated,
+    )?;
+    require!(!milestone.funds_released, ErrorCode::FundsAlreadyReleased);
+    require!(milestone.challenger.is_none(), ErrorCode::AlreadyChallenged);
+    let challenge_ends_at = milestone
+        .validated_at
+        .checked_add(proposal.challenge_period)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(now < challenge_ends_at, ErrorCode::ChallengePeriodEnded);
+
+    // 1. The bond is a share of the milestone's payout, stretch milestones
+    // pay their own budget
+    let payout = match proposal.stretch_goal(milestone.milestone_number) {
+        Some(goal) => goal.budget,
+        None => proposal
+            .amount_asked
+            .checked_div(proposal.total_milestones as u64)
+            .ok_or(ErrorCode::MathOverflow)?,
+    };
+    let bond = ((payout as u128)
+        .checked_mul(CHALLENGE_BOND_BPS as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / 10_000) as u64;
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.challenger_token_account.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+                authority: ctx.accounts.challenger.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    // 2. Voting reopens until the dispute deadline
+    let milestone = &mut ctx.accounts.milestone;
+    transition(&mut milestone.status, MilestoneStatus::Disputed)?;
+    milestone.challenger = Some(ctx.accounts.challenger.key());
+    milestone.challenge_bond = bond;
+    milestone.commit_deadline = 0;
+    milestone.deadline = now
+        .checked_add(DISPUTE_PERIOD_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+    milestone.updated_at = now;
+
+    emit!(MilestoneChallenged {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        challenger: ctx.accounts.challenger.key(),
+        milestone_number: milestone.milestone_number,
+        bond,
+        dispute_deadline: milestone.deadline,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::error::ErrorCode;
+use crate::instructions::close_contributor::require_refunded;
+use crate::instructions::close_milestone::{close_account, emit_closed};
+use crate::instructions::close_vote::require_revealed;
+use crate::state_machine::require_status;
+use crate::{
+    Contributor, Milestone, MilestoneEvidence, MilestoneStatus, Proposal, ProposalStatus, Vote,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CloseAccounts<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(address = proposal.funds_escrow @ ErrorCode::InvalidEscrow)]
+    pub escrow: Account<'info, TokenAccount>,
+    // remaining_accounts: each account to close followed by its rent
+    // recipient, votes take their writable milestone in between:
+    //   [milestone | evidence | contributor, recipient]
+    //   [vote, milestone, validator]
+}
+
+/// Batch of `close_milestone`, `close_vote` and `close_contributor`, with
+/// the same checks. Evidence accounts can be closed on their own, e.g. for
+/// milestones that were never submitted.
+pub fn close_accounts_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseAccounts<'info>>,
+) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require_status(
+        &proposal.status,
+        &[ProposalStatus::Completed, ProposalStatus::Failed],
+        ErrorCode::ProposalNotClosable,
+    )?;
+
+    // Milestones, evidence and contributors the proposal counts as open
+    let mut closed: u32 = 0;
+    let mut accounts = ctx.remaining_accounts.iter();
+    while let Some(account) = accounts.next() {
+        require_keys_eq!(*account.owner, crate::ID, ErrorCode::InvalidAccountOwner);
+
+        // 1. Check the account belongs to the proposal and find its payer
+        let payer = {
+            let data = account.try_borrow_data()?;
+            match data.get(..8).ok_or(ErrorCode::UnknownAccountType)? {
+                d if d == Milestone::DISCRIMINATOR => {
+                    let milestone = Milestone::try_deserialize(&mut &data[..])?;
+                    require_keys_eq!(
+                        milestone.proposal,
+                        proposal.key(),
+                        ErrorCode::AccountNotInProposal
+                    );
+                    require!(
+                        milestone.status != MilestoneStatus::Disputed,
+                        ErrorCode::MilestoneDisputed
+                    );
+                    require!(milestone.open_votes == 0, ErrorCode::MilestoneHasOpenVotes);
+                    closed += 1;
+                    milestone.rent_payer(&proposal.authority)
+                }
+                d if d == MilestoneEvidence::DISCRIMINATOR => {
+                    let evidence = MilestoneEvidence::try_deserialize(&mut &data[..])?;
+                    require_keys_eq!(
+                        evidence.proposal,
+                        proposal.key(),
+                        ErrorCode::AccountNotInProposal
+                    );
+                    closed += 1;
+                    evidence.rent_payer(&proposal.authority)
+                }
+                d if d == Contributor::DISCRIMINATOR => {
+                    let contributor = Contributor::try_deserialize(&mut &data[..])?;
+                    require_keys_eq!(
+                        contributor.proposal,
+                        proposal.key(),
+                        ErrorCode::AccountNotInProposal
+                    );
+                    require_refunded(
+                        proposal,
+                        ctx.accounts.escrow.amount,
+                        &contributor,
+                        &ctx.accounts.cranker.key(),
+                    )?;
+                    closed += 1;
+                    contributor.rent_payer()
+                }
+                d if d == Vote::DISCRIMINATOR => {
+                    let vote = Vote::try_deserialize(&mut &data[..])?;
+                    require_revealed(&vote)?;
+
+                    // Votes only know their milestone
+                    let milestone = accounts.next().ok_or(ErrorCode::InvalidCloseAccounts)?;
+                    require_keys_eq!(
+                        milestone.key(),
+                        vote.milestone,
+                        ErrorCode::AccountNotInProposal
+                    );
+                    let mut milestone = Account::<Milestone>::try_from(milestone)?;
+                    require_keys_eq!(
+                        milestone.proposal,
+                        proposal.key(),
+                        ErrorCode::AccountNotInProposal
+                    );
+                    milestone.open_votes = milestone.open_votes.saturating_sub(1);
+                    milestone.exit(&crate::ID)?;
+                    vote.validator
+                }
+                _ => return err!(ErrorCode::UnknownAccountType),
+            }
+        };
+
+        // 2. Rent goes back to the payer
+        let recipient = accounts.next().ok_or(ErrorCode::InvalidCloseAccounts)?;
+        require_keys_eq!(recipient.key(), payer, ErrorCode::InvalidRentRecipient);
+        emit_closed(proposal.key(), account, recipient)?;
+        close_account(account, recipient)?;
+    }
+
+    let open_accounts = &mut ctx.accounts.proposal.open_accounts;
+    *open_accounts = open_accounts.saturating_sub(closed);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use crate::error::ErrorCode;
+use crate::instructions::close_milestone::emit_closed;
+use crate::state_machine::require_status;
+use crate::{Contributor, Proposal, ProposalStatus};
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct CloseContributor<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = proposal @ ErrorCode::AccountNotInProposal,
+    )]
+    pub contributor_account: Account<'info, Contributor>,
+
+    #[account(address = proposal.funds_escrow @ ErrorCode::InvalidEscrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    /// Paid for the contributor account, a sponsor's contributors give it back
+    /// to the sponsor
+    #[account(
+        mut,
+        address = contributor_account.rent_payer() @ ErrorCode::InvalidRentRecipient,
+    )]
+    pub payer: SystemAccount<'info>,
+}
+
+pub fn close_contributor_handler(ctx: Context<CloseContributor>) -> Result<()> {
+    require_status(
+        &ctx.accounts.proposal.status,
+        &[ProposalStatus::Completed, ProposalStatus::Failed],
+        ErrorCode::ProposalNotClosable,
+    )?;
+    require_refunded(
+        &ctx.accounts.proposal,
+        ctx.accounts.escrow.amount,
+        &ctx.accounts.contributor_account,
+        &ctx.accounts.cranker.key(),
+    )?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.open_accounts = proposal.open_accounts.saturating_sub(1);
+
+    emit_closed(
+        ctx.accounts.proposal.key(),
+        &ctx.accounts.contributor_account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )
+}
+
+/// The contributor account is the claim on whatever the escrow still holds
+/// for contributors, see `Proposal::refundable`. Only the contributor can
+/// give it up before withdrawing their refund.
+pub(crate) fn require_refunded(
+    proposal: &Proposal,
+    escrow_amount: u64,
+    contributor: &Contributor,
+    cranker: &Pubkey,
+) -> Result<()> {
+    require!(
+        proposal.refundable(escrow_amount) == 0
+            || contributor.amount == 0
+            || contributor.wallet == *cranker,
+        ErrorCode::RefundNotWithdrawn
+    );
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use crate::error::ErrorCode;
+use crate::state_machine::require_status;
+use crate::{
+    AccountClosed, Milestone, MilestoneEvidence, MilestoneStatus, Proposal, ProposalStatus,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseMilestone<'info> {
+    // Anyone can close out a finished proposal, rent goes back to its payer
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = proposal @ ErrorCode::AccountNotInProposal,
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    /// CHECK: Attachments added with `add_evidence`, closed along with the
+    /// milestone when they exist
+    #[account(
+        mut,
+        seeds = [
+            b"evidence",
+            proposal.key().as_ref(),
+            milestone.milestone_number.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub evidence: UncheckedAccount<'info>,
+
+    /// CHECK: Paid for the milestone and gets the rent back, see
+    /// `Milestone::rent_payer`. Legacy milestones fall back to the authority,
+    /// which may be a multisig PDA
+    #[account(
+        mut,
+        address = milestone.rent_payer(&proposal.authority) @ ErrorCode::InvalidRentRecipient,
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    /// CHECK: Paid for the evidence, only needed when it exists. Checked by
+    /// the handler against `MilestoneEvidence::rent_payer`
+    #[account(mut)]
+    pub evidence_payer: Option<UncheckedAccount<'info>>,
+}
+
+/// Closes a milestone of a completed or failed proposal. Close its votes
+/// first, `close_vote` reads the milestone.
+pub fn close_milestone_handler(ctx: Context<CloseMilestone>) -> Result<()> {
+    require_status(
+        &ctx.accounts.proposal.status,
+        &[ProposalStatus::Completed, ProposalStatus::Failed],
+        ErrorCode::ProposalNotClosable,
+    )?;
+    // The challenge vault still holds the bond
+    require!(
+        ctx.accounts.milestone.status != MilestoneStatus::Disputed,
+        ErrorCode::MilestoneDisputed
+    );
+    require!(
+        ctx.accounts.milestone.open_votes == 0,
+        ErrorCode::MilestoneHasOpenVotes
+    );
+
+    let proposal = ctx.accounts.proposal.key();
+    let evidence = ctx.accounts.evidence.to_account_info();
+    let mut closed = 1;
+    if !evidence.data_is_empty() {
+        require_keys_eq!(*evidence.owner, crate::ID, ErrorCode::InvalidAccountOwner);
+        let payer = MilestoneEvidence::try_deserialize(&mut &evidence.try_borrow_data()?[..])?
+            .rent_payer(&ctx.accounts.proposal.authority);
+        let recipient = ctx
+            .accounts
+            .evidence_payer
+            .as_ref()
+            .ok_or(ErrorCode::InvalidRentRecipient)?
+            .to_account_info();
+        require_keys_eq!(recipient.key(), payer, ErrorCode::InvalidRentRecipient);
+        emit_closed(proposal, &evidence, &recipient)?;
+        close_account(&evidence, &recipient)?;
+        closed += 1;
+    }
+    let open_accounts = &mut ctx.accounts.proposal.open_accounts;
+    *open_accounts = open_accounts.saturating_sub(closed);
+
+    emit_closed(
+        proposal,
+        &ctx.accounts.milestone.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )
+}
+
+/// Moves all of `account`'s lamports to `recipient` and hands it back to the
+/// system program, what the `close` constraint does for typed accounts.
+pub(crate) fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    let lamports = recipient
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(ErrorCode::MathOverflow)?;
+    **recipient.try_borrow_mut_lamports()? = lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Emits `AccountClosed` for `account`, before its rent moves to `recipient`.
+pub(crate) fn emit_closed(
+    proposal: Pubkey,
+    account: &AccountInfo,
+    recipient: &AccountInfo,
+) -> Result<()> {
+    emit!(AccountClosed {
+        proposal,
+        account: account.key(),
+        rent_recipient: recipient.key(),
+        lamports: account.lamports(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
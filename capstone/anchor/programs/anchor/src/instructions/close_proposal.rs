@@ -1,14 +1,33 @@
-use crate::{Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::require_status;
+use crate::{Proposal, ProposalClosed, ProposalStatus};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct CloseProposal<'info> {
-    #[account(mut, close = researcher)]
+    #[account(
+        mut,
+        close = researcher,
+        has_one = authority,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
     pub proposal: Account<'info, Proposal>,
 
-    #[account(mut)]
-    pub researcher: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    /// Paid for the proposal and its escrow in `create_proposal`, so gets the
+    /// rent back even after a handover
+    #[account(
+        mut,
+        address = proposal.researcher @ ErrorCode::InvalidRentRecipient,
+    )]
+    pub researcher: SystemAccount<'info>,
 
     #[account(
         mut,
@@ -23,27 +42,54 @@ pub struct CloseProposal<'info> {
 }
 
 pub fn close_proposal_handler(ctx: Context<CloseProposal>) -> Result<()> {
-    // Commented out only for testing purposes
     // Proposal must be completed or failed
-    // require!(
-    //     ctx.accounts.proposal.status == ProposalStatus::Completed
-    //         || ctx.accounts.proposal.status == ProposalStatus::Failed,
-    //     ErrorCode::ProposalNotClosable
-    // );
+    require_status(
+        &ctx.accounts.proposal.status,
+        &[ProposalStatus::Completed, ProposalStatus::Failed],
+        ErrorCode::ProposalNotClosable,
+    )?;
 
-    // Escrow must be empty
-    // require!(ctx.accounts.escrow.amount == 0, ErrorCode::EscrowNotEmpty);
+    // Escrow must be empty, the proposal signs every refund out of it.
+    // Skipped along with the status checks, a funded escrow then stays open
+    require!(
+        cfg!(feature = "skip-status-checks") || ctx.accounts.escrow.amount == 0,
+        ErrorCode::EscrowNotEmpty
+    );
+    // Milestones, evidence and contributors close against the proposal
+    require!(
+        ctx.accounts.proposal.open_accounts == 0,
+        ErrorCode::ProposalHasOpenAccounts
+    );
 
-    // Proposal account will be closed automatically (rent sent to researcher)
-    Ok(())
-}
+    let escrow_closed = ctx.accounts.escrow.amount == 0;
+    if escrow_closed {
+        let proposal = &ctx.accounts.proposal;
+        let seeds = &[
+            b"proposal".as_ref(),
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes(),
+            &[ctx.bumps.proposal],
+        ];
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow.to_account_info(),
+                destination: ctx.accounts.researcher.to_account_info(),
+                authority: proposal.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+    }
+
+    emit!(ProposalClosed {
+        proposal: ctx.accounts.proposal.key(),
+        researcher: ctx.accounts.proposal.researcher,
+        escrow: ctx.accounts.escrow.key(),
+        escrow_balance: ctx.accounts.escrow.amount,
+        escrow_closed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Proposal is not in a closable state")]
-    ProposalNotClosable,
-    #[msg("Escrow account still holds funds")]
-    EscrowNotEmpty,
-    #[msg("Invalid escrow account")]
-    InvalidEscrow,
+    // Proposal account will be closed automatically (rent sent to the researcher)
+    Ok(())
 }
@@ -0,0 +1,64 @@
+use crate::error::ErrorCode;
+use crate::instructions::close_milestone::emit_closed;
+use crate::state_machine::require_status;
+use crate::{Milestone, Proposal, ProposalStatus, Vote};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseVote<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::AccountNotInProposal)]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        mut,
+        close = validator,
+        has_one = validator @ ErrorCode::InvalidRentRecipient,
+        has_one = milestone @ ErrorCode::AccountNotInProposal,
+    )]
+    pub vote: Account<'info, Vote>,
+
+    /// Paid for the vote
+    #[account(mut)]
+    pub validator: SystemAccount<'info>,
+}
+
+pub fn close_vote_handler(ctx: Context<CloseVote>) -> Result<()> {
+    require_status(
+        &ctx.accounts.proposal.status,
+        &[ProposalStatus::Completed, ProposalStatus::Failed],
+        ErrorCode::ProposalNotClosable,
+    )?;
+    require_revealed(&ctx.accounts.vote)?;
+
+    // Accounts migrated from before v7 weren't counted
+    let milestone = &mut ctx.accounts.milestone;
+    milestone.open_votes = milestone.open_votes.saturating_sub(1);
+
+    emit_closed(
+        ctx.accounts.proposal.key(),
+        &ctx.accounts.vote.to_account_info(),
+        &ctx.accounts.validator.to_account_info(),
+    )
+}
+
+/// Hidden votes that were never revealed forfeit their rent to whoever
+/// cranks `penalize_unrevealed_vote`, so they can't be closed here.
+pub(crate) fn require_revealed(vote: &Vote) -> Result<()> {
+    require!(
+        vote.commitment == [0; 32] || vote.revealed,
+        ErrorCode::UnrevealedVote
+    );
+    Ok(())
+}
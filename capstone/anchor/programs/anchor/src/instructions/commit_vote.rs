@@ -0,0 +1,79 @@
+use crate::error::ErrorCode;
+use crate::state_machine::require_status;
+use crate::{Milestone, MilestoneStatus, Proposal, Versioned, Vote, VoteCommitted};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CommitVote<'info> {
+    #[account(mut)]
+    pub validator: Signer<'info>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
+    pub milestone: Account<'info, Milestone>,
+
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = validator,
+        space = 8 + Vote::INIT_SPACE,
+        seeds = [
+            b"vote",
+            validator.key().as_ref(),
+            milestone.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// First phase of a commit-reveal vote, stores `Vote::commitment` of the
+/// hidden vote. A validator may replace their commitment until the phase ends.
+pub fn commit_vote_handler(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // Validate milestone state
+    let milestone = &mut ctx.accounts.milestone;
+    require_status(
+        &milestone.status,
+        &[MilestoneStatus::Active],
+        ErrorCode::MilestoneNotActive,
+    )?;
+    require!(milestone.commit_deadline != 0, ErrorCode::OpenVoting);
+    require!(
+        now < milestone.commit_deadline,
+        ErrorCode::CommitPhaseClosed
+    );
+
+    let vote = &mut ctx.accounts.vote;
+    if vote.validator == Pubkey::default() {
+        vote.version = Vote::VERSION;
+        vote.validator = ctx.accounts.validator.key();
+        vote.milestone = milestone.key();
+        milestone.commits = milestone
+            .commits
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        milestone.open_votes = milestone
+            .open_votes
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    vote.commitment = commitment;
+    vote.evidence_version = milestone.evidence_version;
+    vote.voted_at = now;
+
+    emit!(VoteCommitted {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        validator: vote.validator,
+        vote: vote.key(),
+        commitment,
+        commits: milestone.commits,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::{FundingRound, FundingRoundCreated, RoundEntry, Versioned, MAX_ROUND_PROPOSALS};
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct CreateFundingRound<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + FundingRound::INIT_SPACE,
+        seeds = [b"round", sponsor.key().as_ref(), &round_id.to_le_bytes()],
+        bump,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        seeds = [b"round_vault", funding_round.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = funding_round,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = sponsor_token_account.mint == mint.key() @ ErrorCode::InvalidMint,
+        token::authority = sponsor
+    )]
+    pub sponsor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_funding_round_handler(
+    ctx: Context<CreateFundingRound>,
+    round_id: u64,
+    matching_pool: u64,
+    starts_at: i64,
+    ends_at: i64,
+    proposals: Vec<Pubkey>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // Validate inputs
+    require!(matching_pool > 0, ErrorCode::InvalidAmount);
+    require!(
+        starts_at < ends_at && now < ends_at,
+        ErrorCode::InvalidRoundWindow
+    );
+    require!(
+        !proposals.is_empty() && proposals.len() <= MAX_ROUND_PROPOSALS as usize,
+        ErrorCode::InvalidRoundProposals
+    );
+    for (i, proposal) in proposals.iter().enumerate() {
+        require!(
+            !proposals[..i].contains(proposal),
+            ErrorCode::InvalidRoundProposals
+        );
+    }
+
+    // Move the matching pool into the round's vault
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.sponsor_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.sponsor.to_account_info(),
+            },
+        ),
+        matching_pool,
+    )?;
+
+    let round = &mut ctx.accounts.funding_round;
+    round.version = FundingRound::VERSION;
+    round.sponsor = ctx.accounts.sponsor.key();
+    round.round_id = round_id;
+    round.mint = ctx.accounts.mint.key();
+    round.vault = ctx.accounts.vault.key();
+    round.matching_pool = matching_pool;
+    round.starts_at = starts_at;
+    round.ends_at = ends_at;
+    round.entries = proposals
+        .iter()
+        .map(|proposal| RoundEntry {
+            proposal: *proposal,
+            contributors: 0,
+            contributed: 0,
+            sqrt_sum: 0,
+            matched: 0,
+        })
+        .collect();
+    round.distributed = false;
+    round.bump = ctx.bumps.funding_round;
+    round.vault_bump = ctx.bumps.vault;
+
+    emit!(FundingRoundCreated {
+        funding_round: round.key(),
+        sponsor: round.sponsor,
+        mint: round.mint,
+        vault: round.vault,
+        round_id,
+        matching_pool,
+        starts_at,
+        ends_at,
+        proposals,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::{self, Approve, Mint, Token, TokenAccount};
+use anchor_spl::token_2022::Token2022;
+
+use crate::allowlist::check_allowlist;
+use crate::error::ErrorCode;
+use crate::instructions::fund_proposal::{accept_contribution, credit_contribution, issue_receipt};
+use crate::receipt::ReceiptAccounts;
+use crate::{
+    AllowlistProof, Contributor, Funded, Pledge, PledgeCreated, Proposal, ProposalStatus, Versioned,
+    PLEDGE_INTERVAL_SECONDS,
+};
+
+#[derive(Accounts)]
+pub struct CreatePledge<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = proposal.status == ProposalStatus::Active @ ErrorCode::ProposalNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = contributor,
+        space = Pledge::SPACE,
+        seeds = [
+            b"pledge",
+            contributor.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump
+    )]
+    pub pledge: Account<'info, Pledge>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + Contributor::INIT_SPACE,
+        seeds = [
+            b"contributor",
+            contributor.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump
+    )]
+    pub contributor_account: Account<'info, Contributor>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", proposal.key().as_ref()],
+        bump,
+        constraint = escrow.key() == proposal.funds_escrow @ ErrorCode::InvalidEscrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    // A token account has a single delegate, each pledge needs its own
+    #[account(
+        mut,
+        constraint = contributor_token_account.mint == mint.key() @ ErrorCode::InvalidMint,
+        token::authority = contributor
+    )]
+    pub contributor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Soulbound receipt mint, created by the handler on the first
+    /// contribution
+    #[account(
+        mut,
+        seeds = [b"receipt", contributor_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Contributor's Token-2022 associated account for the receipt
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &contributor.key(),
+            &receipt_mint.key(),
+            &Token2022::id(),
+        )
+    )]
+    pub receipt_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Pays the first installment like `fund_proposal` and approves the pledge
+/// as delegate for the rest, which `execute_pledge` pulls every
+/// `PLEDGE_INTERVAL_SECONDS`. The whole pledge must fit the contributor's
+/// allowlist cap and maximum contribution, a hard cap trims the
+/// installments as they come.
+pub fn create_pledge_handler(
+    ctx: Context<CreatePledge>,
+    amount: u64,
+    installments: u16,
+    allowance: Option<AllowlistProof>,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidContribution);
+    require!(installments >= 2, ErrorCode::InvalidPledge);
+    let pledged = amount
+        .checked_mul(installments as u64)
+        .and_then(|total| total.checked_add(ctx.accounts.contributor_account.amount))
+        .ok_or(ErrorCode::MathOverflow)?;
+    check_allowlist(
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor.key(),
+        pledged,
+        allowance.as_ref(),
+    )?;
+    let max = ctx.accounts.proposal.contribution_limits.max;
+    require!(
+        max == 0 || pledged <= max,
+        ErrorCode::ContributionAboveMaximum
+    );
+    let first = accept_contribution(
+        &ctx.accounts.proposal,
+        ctx.accounts.contributor.key(),
+        ctx.accounts.contributor_account.amount,
+        amount,
+    )?;
+    let now = Clock::get()?.unix_timestamp;
+
+    // 1. First installment
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.contributor_token_account.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.contributor.to_account_info(),
+            },
+        ),
+        first,
+    )?;
+    credit_contribution(
+        &mut ctx.accounts.proposal,
+        &mut ctx.accounts.contributor_account,
+        ctx.accounts.contributor.key(),
+        ctx.accounts.contributor.key(),
+        ctx.bumps.contributor_account,
+        first,
+    )?;
+    let accounts = ReceiptAccounts {
+        payer: &ctx.accounts.contributor.to_account_info(),
+        owner: &ctx.accounts.contributor.to_account_info(),
+        mint: &ctx.accounts.receipt_mint.to_account_info(),
+        token_account: &ctx.accounts.receipt_token_account.to_account_info(),
+        token_2022_program: &ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+    };
+    issue_receipt(
+        &accounts,
+        ctx.bumps.receipt_mint,
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor_account,
+    )?;
+
+    // 2. Let the pledge pull the remaining installments
+    let remaining = amount
+        .checked_mul(installments as u64 - 1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    token::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Approve {
+                to: ctx.accounts.contributor_token_account.to_account_info(),
+                delegate: ctx.accounts.pledge.to_account_info(),
+                authority: ctx.accounts.contributor.to_account_info(),
+            },
+        ),
+        remaining,
+    )?;
+
+    let pledge = &mut ctx.accounts.pledge;
+    pledge.version = Pledge::VERSION;
+    pledge.contributor = ctx.accounts.contributor.key();
+    pledge.proposal = ctx.accounts.proposal.key();
+    pledge.token_account = ctx.accounts.contributor_token_account.key();
+    pledge.amount = amount;
+    pledge.installments = installments;
+    pledge.installments_paid = 1;
+    pledge.next_due = now
+        .checked_add(PLEDGE_INTERVAL_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pledge.created_at = now;
+    pledge.bump = ctx.bumps.pledge;
+    pledge.ended = None;
+
+    let proposal = &ctx.accounts.proposal;
+    let contributor = &ctx.accounts.contributor_account;
+    emit!(Funded {
+        proposal: proposal.key(),
+        contributor: pledge.contributor,
+        contributor_account: contributor.key(),
+        amount: first,
+        contributor_total: contributor.amount,
+        amount_raised: proposal.amount_raised,
+        contributors_count: proposal.contributors_count,
+        timestamp: now,
+    });
+    emit!(PledgeCreated {
+        proposal: proposal.key(),
+        pledge: pledge.key(),
+        contributor: pledge.contributor,
+        amount,
+        installments,
+        next_due: pledge.next_due,
+        timestamp: now,
+    });
+
+    Ok(())
+}
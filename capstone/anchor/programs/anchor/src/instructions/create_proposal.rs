@@ -1,7 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 
-use crate::{Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::transition;
+use crate::{
+    ContentId, ContributionLimits, Proposal, ProposalCreated, ProposalStatus, StretchGoal,
+    TeamMember, Versioned, MAX_CHALLENGE_PERIOD_SECONDS, MAX_STRETCH_GOALS, TEAM_SHARE_TOTAL_BPS,
+};
 
 #[derive(Accounts)]
 #[instruction(title: String)]
@@ -50,13 +55,31 @@ pub fn create_proposal_handler(
     amount_asked: u64,
     total_milestones: u8,
     security_deposit: u64,
+    challenge_period: i64,
+    allowlist_root: Option<[u8; 32]>,
+    contribution_limits: ContributionLimits,
+    stretch_goals: Vec<StretchGoal>,
 ) -> Result<()> {
     // Get current timestamp
     let clock = Clock::get()?;
 
     // Validate inputs
     require!(amount_asked > 0, ErrorCode::InvalidAmount);
-    require!(total_milestones > 0, ErrorCode::InvalidMilestone);
+    // Stretch milestones are numbered after the base ones, all within a u8
+    require!(
+        total_milestones > 0 && total_milestones <= u8::MAX - MAX_STRETCH_GOALS,
+        ErrorCode::InvalidMilestoneCount
+    );
+    require!(
+        (0..=MAX_CHALLENGE_PERIOD_SECONDS).contains(&challenge_period),
+        ErrorCode::InvalidChallengePeriod
+    );
+    require!(
+        contribution_limits.max == 0 || contribution_limits.max >= contribution_limits.min,
+        ErrorCode::InvalidContributionLimits
+    );
+    validate_stretch_goals(&stretch_goals, amount_asked, &contribution_limits)?;
+    let ipfs_hash = ContentId::parse(&ipfs_hash)?;
 
     // Calculate required deposit amount (10% of amount asked)
     let required_deposit = amount_asked
@@ -65,11 +88,12 @@ pub fn create_proposal_handler(
 
     require!(
         security_deposit >= required_deposit,
-        ErrorCode::MoreSecurityDeposit
+        ErrorCode::SecurityDepositTooLow
     );
 
     // Initialize proposal fields
     let proposal = &mut ctx.accounts.proposal;
+    proposal.version = Proposal::VERSION;
     proposal.title = title.clone();
     proposal.abstract_text = abstract_text;
     proposal.researcher = ctx.accounts.researcher.key();
@@ -79,13 +103,27 @@ pub fn create_proposal_handler(
     proposal.amount_raised = 0;
     proposal.total_milestones = total_milestones;
     proposal.current_milestone = 0;
-    proposal.ipfs_hash = ipfs_hash;
+    proposal.ipfs_hash = Some(ipfs_hash.clone());
     proposal.ip_nft_mint = None;
     proposal.created_at = clock.unix_timestamp;
     proposal.updated_at = clock.unix_timestamp;
-    proposal.status = ProposalStatus::Active;
+    transition(&mut proposal.status, ProposalStatus::Active)?;
     proposal.penalty_counter = 0;
     proposal.contributors_count = 0;
+    proposal.challenge_period = challenge_period;
+    proposal.authority = ctx.accounts.researcher.key();
+    proposal.pending_authority = None;
+    proposal.allowlist_root = allowlist_root;
+    proposal.contribution_limits = contribution_limits;
+    proposal.stretch_goals = stretch_goals.clone();
+    proposal.stretch_goals_unlocked = 0;
+    proposal.milestones_released = Some(0);
+
+    // The researcher starts as the only team member with the full split
+    proposal.team = vec![TeamMember {
+        wallet: ctx.accounts.researcher.key(),
+        share_bps: TEAM_SHARE_TOTAL_BPS,
+    }];
 
     // Store escrow account address
     proposal.funds_escrow = ctx.accounts.escrow.key();
@@ -103,27 +141,54 @@ pub fn create_proposal_handler(
         security_deposit,
     )?;
 
-    msg!("Proposal created with title: {}", title.clone());
-    msg!(
-        "Security deposit of {} tokens transferred to escrow",
-        security_deposit
-    );
-    msg!("Escrow address: {}", ctx.accounts.escrow.key());
+    emit!(ProposalCreated {
+        proposal: ctx.accounts.proposal.key(),
+        researcher: ctx.accounts.researcher.key(),
+        escrow: ctx.accounts.escrow.key(),
+        mint: ctx.accounts.mint.key(),
+        title,
+        ipfs_hash,
+        amount_asked,
+        total_milestones,
+        security_deposit,
+        challenge_period,
+        allowlist_root,
+        contribution_limits,
+        stretch_goals,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Enter valid amount!")]
-    InvalidAmount,
-
-    #[msg("Enter valid milestone!")]
-    InvalidMilestone,
-
-    #[msg("Overflow Error")]
-    MathOverflow,
+/// Goals unlock in order, so each threshold has to sit above the previous one
+/// and leave room for the base milestones and every budget up to its own.
+fn validate_stretch_goals(
+    stretch_goals: &[StretchGoal],
+    amount_asked: u64,
+    contribution_limits: &ContributionLimits,
+) -> Result<()> {
+    require!(
+        stretch_goals.len() <= MAX_STRETCH_GOALS as usize,
+        ErrorCode::TooManyStretchGoals
+    );
+    // A hard cap stops funding at `amount_asked`, no goal could ever unlock
+    require!(
+        stretch_goals.is_empty() || !contribution_limits.hard_cap,
+        ErrorCode::InvalidStretchGoal
+    );
 
-    #[msg("Security Deposit Amount is less than expected")]
-    MoreSecurityDeposit,
+    let mut committed = amount_asked;
+    let mut previous_threshold = 0;
+    for goal in stretch_goals {
+        committed = committed
+            .checked_add(goal.budget)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            goal.budget > 0 && goal.threshold >= committed && goal.threshold > previous_threshold,
+            ErrorCode::InvalidStretchGoal
+        );
+        previous_threshold = goal.threshold;
+    }
+    Ok(())
 }
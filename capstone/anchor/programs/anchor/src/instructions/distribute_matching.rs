@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::{FundingRound, MatchingDistributed, Proposal, ProposalStatus, RoundMatch};
+
+#[derive(Accounts)]
+pub struct DistributeMatching<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"round",
+            funding_round.sponsor.as_ref(),
+            &funding_round.round_id.to_le_bytes()
+        ],
+        bump = funding_round.bump,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+
+    #[account(
+        mut,
+        seeds = [b"round_vault", funding_round.key().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    // Receives whatever the formula leaves unallocated
+    #[account(
+        mut,
+        constraint = sponsor_token_account.owner == funding_round.sponsor,
+        constraint = sponsor_token_account.mint == funding_round.mint @ ErrorCode::InvalidMint,
+    )]
+    pub sponsor_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    // remaining_accounts: (proposal, escrow) pairs, writable, in round order
+}
+
+pub fn distribute_matching_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeMatching<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let round = &ctx.accounts.funding_round;
+
+    // 1. Anyone can crank the distribution once the round is over
+    require!(now >= round.ends_at, ErrorCode::RoundNotEnded);
+    require!(!round.distributed, ErrorCode::RoundAlreadyDistributed);
+    require!(
+        ctx.remaining_accounts.len() == round.entries.len() * 2,
+        ErrorCode::InvalidRoundAccounts
+    );
+
+    // 2. Load every proposal, only those still raising receive a match
+    let mut targets = Vec::with_capacity(round.entries.len());
+    for (entry, pair) in round.entries.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let proposal = Account::<Proposal>::try_from(&pair[0])?;
+        require_keys_eq!(proposal.key(), entry.proposal, ErrorCode::InvalidRoundAccounts);
+        require_keys_eq!(pair[1].key(), proposal.funds_escrow, ErrorCode::InvalidEscrow);
+        let escrow = Account::<TokenAccount>::try_from(&pair[1])?;
+        require_keys_eq!(escrow.mint, round.mint, ErrorCode::InvalidMint);
+        targets.push(proposal);
+    }
+    let eligible: Vec<bool> = targets
+        .iter()
+        .map(|proposal| proposal.status == ProposalStatus::Active)
+        .collect();
+    let pool = ctx.accounts.vault.amount;
+    let shares = FundingRound::matching_shares(pool, &round.entries, &eligible);
+
+    // 3. Pay each share into the proposal's escrow
+    let sponsor = round.sponsor;
+    let round_id = round.round_id.to_le_bytes();
+    let seeds = &[
+        b"round",
+        sponsor.as_ref(),
+        round_id.as_ref(),
+        &[round.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let mut matches = Vec::with_capacity(targets.len());
+    let mut paid: u64 = 0;
+    for ((proposal, pair), share) in targets
+        .iter_mut()
+        .zip(ctx.remaining_accounts.chunks(2))
+        .zip(shares.iter().copied())
+    {
+        if share > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: pair[1].clone(),
+                        authority: ctx.accounts.funding_round.to_account_info(),
+                    },
+                    signer,
+                ),
+                share,
+            )?;
+            proposal.matched_amount = proposal
+                .matched_amount
+                .checked_add(share)
+                .ok_or(ErrorCode::MathOverflow)?;
+            proposal.exit(&crate::ID)?;
+        }
+        paid = paid.checked_add(share).ok_or(ErrorCode::MathOverflow)?;
+        matches.push(RoundMatch {
+            proposal: proposal.key(),
+            amount: share,
+        });
+    }
+
+    // 4. Rounding dust and unmatched pools go back to the sponsor
+    let returned = pool.checked_sub(paid).ok_or(ErrorCode::MathOverflow)?;
+    if returned > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.sponsor_token_account.to_account_info(),
+                    authority: ctx.accounts.funding_round.to_account_info(),
+                },
+                signer,
+            ),
+            returned,
+        )?;
+    }
+
+    let round = &mut ctx.accounts.funding_round;
+    for (entry, share) in round.entries.iter_mut().zip(shares) {
+        entry.matched = share;
+    }
+    round.distributed = true;
+
+    emit!(MatchingDistributed {
+        funding_round: round.key(),
+        matches,
+        returned,
+        timestamp: now,
+    });
+
+    Ok(())
+}
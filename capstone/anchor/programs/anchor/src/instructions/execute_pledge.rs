@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::{self, Token, TokenAccount};
+use anchor_spl::token_2022::Token2022;
+
+use crate::error::ErrorCode;
+use crate::instructions::fund_proposal::{accept_contribution, credit_contribution, issue_receipt};
+use crate::receipt::ReceiptAccounts;
+use crate::{
+    Contributor, Funded, Pledge, PledgeEnd, PledgeEnded, PledgeExecuted, Proposal, ProposalStatus,
+    PLEDGE_INTERVAL_SECONDS,
+};
+
+#[derive(Accounts)]
+pub struct ExecutePledge<'info> {
+    // Anyone can crank a due installment
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        has_one = proposal @ ErrorCode::AccountNotInProposal,
+        has_one = contributor @ ErrorCode::InvalidRentRecipient,
+        seeds = [
+            b"pledge",
+            pledge.contributor.as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump = pledge.bump
+    )]
+    pub pledge: Account<'info, Pledge>,
+
+    /// Gets the pledge's rent back once it ends
+    #[account(mut)]
+    pub contributor: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"contributor",
+            contributor.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump = contributor_account.bump
+    )]
+    pub contributor_account: Account<'info, Contributor>,
+
+    #[account(mut, address = proposal.funds_escrow @ ErrorCode::InvalidEscrow)]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(mut, address = pledge.token_account)]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: The contributor's receipt mint, minted with the first installment
+    #[account(
+        mut,
+        seeds = [b"receipt", contributor_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Contributor's Token-2022 associated account for the receipt
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &contributor.key(),
+            &receipt_mint.key(),
+            &Token2022::id(),
+        )
+    )]
+    pub receipt_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pulls the next due installment into escrow. Once the proposal has left
+/// `Active` or reached its hard cap the pledge ends instead, as it does
+/// after the last installment.
+pub fn execute_pledge_handler(ctx: Context<ExecutePledge>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.pledge.ended.is_none(),
+        ErrorCode::PledgeAlreadyEnded
+    );
+    if ctx.accounts.proposal.status != ProposalStatus::Active {
+        return end_pledge(ctx, PledgeEnd::ProposalInactive, now);
+    }
+    if ctx.accounts.proposal.remaining_to_cap() == Some(0) {
+        return end_pledge(ctx, PledgeEnd::GoalReached, now);
+    }
+    require!(now >= ctx.accounts.pledge.next_due, ErrorCode::PledgeNotDue);
+
+    // 1. Pull the installment as the pledge, the token account's delegate
+    let pledge = &ctx.accounts.pledge;
+    let amount = accept_contribution(
+        &ctx.accounts.proposal,
+        pledge.contributor,
+        ctx.accounts.contributor_account.amount,
+        pledge.amount,
+    )?;
+    let proposal_key = ctx.accounts.proposal.key();
+    let seeds = &[
+        b"pledge".as_ref(),
+        pledge.contributor.as_ref(),
+        proposal_key.as_ref(),
+        &[pledge.bump],
+    ];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.token_account.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: pledge.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    // 2. Same accounting as any other contribution
+    let contributor = ctx.accounts.contributor.key();
+    let bump = ctx.accounts.contributor_account.bump;
+    credit_contribution(
+        &mut ctx.accounts.proposal,
+        &mut ctx.accounts.contributor_account,
+        contributor,
+        contributor,
+        bump,
+        amount,
+    )?;
+    let accounts = ReceiptAccounts {
+        payer: &ctx.accounts.cranker.to_account_info(),
+        owner: &ctx.accounts.contributor.to_account_info(),
+        mint: &ctx.accounts.receipt_mint.to_account_info(),
+        token_account: &ctx.accounts.receipt_token_account.to_account_info(),
+        token_2022_program: &ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+    };
+    issue_receipt(
+        &accounts,
+        ctx.bumps.receipt_mint,
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor_account,
+    )?;
+
+    // 3. Schedule the next one, a late crank doesn't shift the schedule
+    let pledge = &mut ctx.accounts.pledge;
+    pledge.installments_paid += 1;
+    pledge.next_due = pledge
+        .next_due
+        .checked_add(PLEDGE_INTERVAL_SECONDS)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let proposal = &ctx.accounts.proposal;
+    let contributor_account = &ctx.accounts.contributor_account;
+    emit!(Funded {
+        proposal: proposal.key(),
+        contributor,
+        contributor_account: contributor_account.key(),
+        amount,
+        contributor_total: contributor_account.amount,
+        amount_raised: proposal.amount_raised,
+        contributors_count: proposal.contributors_count,
+        timestamp: now,
+    });
+    emit!(PledgeExecuted {
+        proposal: proposal.key(),
+        pledge: pledge.key(),
+        contributor,
+        cranker: ctx.accounts.cranker.key(),
+        amount,
+        installments_paid: pledge.installments_paid,
+        next_due: pledge.next_due,
+        timestamp: now,
+    });
+
+    if pledge.installments_paid == pledge.installments {
+        return end_pledge(ctx, PledgeEnd::Completed, now);
+    }
+    Ok(())
+}
+
+/// Closes the pledge once its allowance is gone. Only the owner can revoke
+/// an SPL approval, so while the pledge is still the token account's
+/// delegate it stays open, marked `ended`, until the contributor calls
+/// `cancel_pledge`.
+fn end_pledge(ctx: Context<ExecutePledge>, reason: PledgeEnd, now: i64) -> Result<()> {
+    let pledge = &ctx.accounts.pledge;
+    emit!(PledgeEnded {
+        proposal: pledge.proposal,
+        pledge: pledge.key(),
+        contributor: pledge.contributor,
+        installments_paid: pledge.installments_paid,
+        reason,
+        timestamp: now,
+    });
+
+    // The last pull clears a fully used approval, trimmed installments leave the rest
+    if ctx.accounts.token_account.delegate == Some(pledge.key()).into() {
+        ctx.accounts.pledge.ended = Some(reason);
+        return Ok(());
+    }
+    ctx.accounts
+        .pledge
+        .close(ctx.accounts.contributor.to_account_info())
+}
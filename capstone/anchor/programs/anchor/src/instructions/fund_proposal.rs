@@ -1,7 +1,16 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
 use anchor_spl::token::{self, Token, TokenAccount, Mint};
+use anchor_spl::token_2022::Token2022;
 
-use crate::{Contributor, Proposal, ProposalStatus};
+use crate::allowlist::check_allowlist;
+use crate::error::ErrorCode;
+use crate::receipt::{self, ReceiptAccounts};
+use crate::{
+    isqrt, AllowlistProof, ContentId, ContributionTrimmed, Contributor, Funded, FundingRound,
+    Proposal, ProposalStatus, RoundContribution, RoundContributionCounted, StretchGoalUnlocked,
+    Versioned,
+};
 
 #[derive(Accounts)]
 pub struct FundProposal<'info> {
@@ -44,14 +53,74 @@ pub struct FundProposal<'info> {
     )]
     pub contributor_token_account: Account<'info, TokenAccount>,
 
+    /// CHECK: Soulbound receipt mint, created by the handler on the first
+    /// contribution
+    #[account(
+        mut,
+        seeds = [b"receipt", contributor_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Contributor's Token-2022 associated account for the receipt
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &contributor.key(),
+            &receipt_mint.key(),
+            &Token2022::id(),
+        )
+    )]
+    pub receipt_token_account: UncheckedAccount<'info>,
+
+    // Round the contribution counts towards, if any
+    #[account(mut)]
+    pub funding_round: Option<Account<'info, FundingRound>>,
+
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + RoundContribution::INIT_SPACE,
+        seeds = [
+            b"round_contribution",
+            funding_round.as_ref().map(|round| round.key()).unwrap_or_default().as_ref(),
+            contributor_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub round_contribution: Option<Account<'info, RoundContribution>>,
+
     pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn fund_proposal_handler(ctx: Context<FundProposal>, amount: u64) -> Result<()> {
+/// `allowance` is only needed when the proposal has an allowlist.
+pub fn fund_proposal_handler(
+    ctx: Context<FundProposal>,
+    amount: u64,
+    allowance: Option<AllowlistProof>,
+) -> Result<()> {
     // Basic checks
     require!(amount > 0, ErrorCode::InvalidContribution);
+    let amount = accept_contribution(
+        &ctx.accounts.proposal,
+        ctx.accounts.contributor.key(),
+        ctx.accounts.contributor_account.amount,
+        amount,
+    )?;
+    check_allowlist(
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor.key(),
+        ctx.accounts
+            .contributor_account
+            .amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?,
+        allowance.as_ref(),
+    )?;
 
     // Transfer tokens from contributor to escrow
     token::transfer(
@@ -66,17 +135,116 @@ pub fn fund_proposal_handler(ctx: Context<FundProposal>, amount: u64) -> Result<
         amount,
     )?;
 
-    // Update contributor's account
-    let contributor = &mut ctx.accounts.contributor_account;
+    count_round_contribution(ctx.accounts, ctx.bumps.round_contribution, amount)?;
+
+    credit_contribution(
+        &mut ctx.accounts.proposal,
+        &mut ctx.accounts.contributor_account,
+        ctx.accounts.contributor.key(),
+        ctx.accounts.contributor.key(),
+        ctx.bumps.contributor_account,
+        amount,
+    )?;
+
+    let accounts = ReceiptAccounts {
+        payer: &ctx.accounts.contributor.to_account_info(),
+        owner: &ctx.accounts.contributor.to_account_info(),
+        mint: &ctx.accounts.receipt_mint.to_account_info(),
+        token_account: &ctx.accounts.receipt_token_account.to_account_info(),
+        token_2022_program: &ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+    };
+    issue_receipt(
+        &accounts,
+        ctx.bumps.receipt_mint,
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor_account,
+    )?;
+
+    let proposal = &ctx.accounts.proposal;
+    let contributor = &ctx.accounts.contributor_account;
+    emit!(Funded {
+        proposal: proposal.key(),
+        contributor: ctx.accounts.contributor.key(),
+        contributor_account: contributor.key(),
+        amount,
+        contributor_total: contributor.amount,
+        amount_raised: proposal.amount_raised,
+        contributors_count: proposal.contributors_count,
+        timestamp: proposal.updated_at,
+    });
+
+    Ok(())
+}
+
+/// Applies the proposal's contribution limits to `requested` more from
+/// `wallet`, who holds `total` so far, and returns how much to take. Only a
+/// hard cap takes less, the last contribution before the goal may then end
+/// up below the minimum.
+pub(crate) fn accept_contribution(
+    proposal: &Account<Proposal>,
+    wallet: Pubkey,
+    total: u64,
+    requested: u64,
+) -> Result<u64> {
+    let limits = proposal.contribution_limits;
+    let new_total = total
+        .checked_add(requested)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        new_total >= limits.min,
+        ErrorCode::ContributionBelowMinimum
+    );
+    require!(
+        limits.max == 0 || new_total <= limits.max,
+        ErrorCode::ContributionAboveMaximum
+    );
+
+    let Some(remaining) = proposal.remaining_to_cap() else {
+        return Ok(requested);
+    };
+    require!(remaining > 0, ErrorCode::FundingGoalReached);
+    let accepted = requested.min(remaining);
+    if accepted < requested {
+        emit!(ContributionTrimmed {
+            proposal: proposal.key(),
+            contributor: wallet,
+            requested,
+            accepted,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+    Ok(accepted)
+}
+
+/// Credits `amount` to `wallet`'s contributor account, opening it on the
+/// first contribution with the rent `payer` recorded, and to the proposal's
+/// total.
+pub(crate) fn credit_contribution(
+    proposal: &mut Account<Proposal>,
+    contributor: &mut Account<Contributor>,
+    wallet: Pubkey,
+    payer: Pubkey,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
 
     if contributor.amount == 0 {
-        contributor.wallet = ctx.accounts.contributor.key();
-        contributor.proposal = ctx.accounts.proposal.key();
-        contributor.timestamp = Clock::get()?.unix_timestamp;
-        contributor.bump = ctx.bumps.contributor_account;
-        
+        contributor.version = Contributor::VERSION;
+        contributor.wallet = wallet;
+        contributor.proposal = proposal.key();
+        contributor.timestamp = now;
+        contributor.bump = bump;
+        contributor.payer = payer;
+
         // Increment contributors count on first contribution
-        ctx.accounts.proposal.contributors_count += 1;
+        proposal.contributors_count += 1;
+        proposal.open_accounts = proposal
+            .open_accounts
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
     }
 
     contributor.amount = contributor
@@ -85,34 +253,123 @@ pub fn fund_proposal_handler(ctx: Context<FundProposal>, amount: u64) -> Result<
         .ok_or(ErrorCode::MathOverflow)?;
     contributor.shares = contributor.amount;
 
-    // Update proposal state
-    let proposal = &mut ctx.accounts.proposal;
     proposal.amount_raised = proposal
         .amount_raised
         .checked_add(amount)
         .ok_or(ErrorCode::MathOverflow)?;
-    proposal.updated_at = Clock::get()?.unix_timestamp;
-
-    msg!("Contributed {} tokens to proposal: {}", amount, proposal.title);
-    msg!("New total raised: {}", proposal.amount_raised);
+    proposal.updated_at = now;
 
+    // Overfunding unlocks stretch goals in order
+    while let Some(goal) = proposal
+        .stretch_goals
+        .get(proposal.stretch_goals_unlocked as usize)
+        .copied()
+    {
+        if proposal.amount_raised < goal.threshold {
+            break;
+        }
+        proposal.stretch_goals_unlocked += 1;
+        emit!(StretchGoalUnlocked {
+            proposal: proposal.key(),
+            milestone_number: proposal.milestone_count(),
+            threshold: goal.threshold,
+            budget: goal.budget,
+            amount_raised: proposal.amount_raised,
+            timestamp: now,
+        });
+    }
     Ok(())
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("The research you are trying to fund is not active")]
-    ProposalNotActive,
+/// Mints the receipt on the first contribution, then keeps its amount current.
+pub(crate) fn issue_receipt(
+    accounts: &ReceiptAccounts,
+    bump: u8,
+    proposal: &Account<Proposal>,
+    contributor: &Account<Contributor>,
+) -> Result<()> {
+    let contributor_key = contributor.key();
+    let seeds = &[b"receipt", contributor_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+    if accounts.mint.data_is_empty() {
+        receipt::mint_receipt(
+            accounts,
+            signer,
+            &proposal.key(),
+            &proposal.title,
+            &proposal
+                .ipfs_hash
+                .as_ref()
+                .map(ContentId::uri)
+                .unwrap_or_default(),
+            contributor.amount,
+        )
+    } else {
+        receipt::update_amount(accounts, signer, contributor.amount)
+    }
+}
+
+/// Adds the contribution to the funding round's quadratic tally, counting
+/// each contributor once with the square root of their round total.
+fn count_round_contribution(
+    accounts: &mut FundProposal,
+    bump: Option<u8>,
+    amount: u64,
+) -> Result<()> {
+    let (round, record) = match (
+        accounts.funding_round.as_mut(),
+        accounts.round_contribution.as_mut(),
+    ) {
+        (None, None) => return Ok(()),
+        (Some(round), Some(record)) => (round, record),
+        _ => return err!(ErrorCode::MissingRoundContribution),
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let round_key = round.key();
+    let proposal = accounts.proposal.key();
+    require!(round.is_open(now), ErrorCode::RoundNotOpen);
+    require_keys_eq!(round.mint, accounts.escrow.mint, ErrorCode::InvalidMint);
+
+    if record.round == Pubkey::default() {
+        record.version = RoundContribution::VERSION;
+        record.round = round_key;
+        record.proposal = proposal;
+        record.wallet = accounts.contributor.key();
+        record.bump = bump.ok_or(ErrorCode::MissingRoundContribution)?;
+    }
+
+    let previous = record.amount;
+    record.amount = previous
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    #[msg("Enter a valid contribution amount")]
-    InvalidContribution,
+    let entry = round
+        .entry_mut(&proposal)
+        .ok_or(ErrorCode::ProposalNotInRound)?;
+    if previous == 0 {
+        entry.contributors += 1;
+    }
+    entry.contributed = entry
+        .contributed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    entry.sqrt_sum = entry
+        .sqrt_sum
+        .checked_add(isqrt(record.amount as u128))
+        .and_then(|v| v.checked_sub(isqrt(previous as u128)))
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    #[msg("Buffer overflow error")]
-    MathOverflow,
+    emit!(RoundContributionCounted {
+        funding_round: round_key,
+        proposal,
+        contributor: record.wallet,
+        amount,
+        contributor_total: record.amount,
+        round_contributors: entry.contributors,
+        round_contributed: entry.contributed,
+        timestamp: now,
+    });
 
-    #[msg("Invalid mint")]
-    InvalidMint,
-    
-    #[msg("Invalid escrow account")]
-    InvalidEscrow,
+    Ok(())
 }
@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token_2022::Token2022;
+
+use crate::allowlist::check_allowlist;
+use crate::error::ErrorCode;
+use crate::instructions::fund_proposal::{accept_contribution, credit_contribution, issue_receipt};
+use crate::receipt::ReceiptAccounts;
+use crate::{
+    AllowlistProof, ContributionSponsored, Contributor, Funded, Proposal, ProposalStatus, MAX_SPONSOR_MEMO_LEN,
+};
+
+#[derive(Accounts)]
+pub struct FundProposalFor<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    /// CHECK: Wallet credited with the contribution, it may be a DAO
+    /// treasury PDA that never signs
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = proposal.status == ProposalStatus::Active @ ErrorCode::ProposalNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + Contributor::INIT_SPACE,
+        seeds = [
+            b"contributor",
+            beneficiary.key().as_ref(),
+            proposal.key().as_ref()
+        ],
+        bump
+    )]
+    pub contributor_account: Account<'info, Contributor>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", proposal.key().as_ref()],
+        bump,
+        constraint = escrow.key() == proposal.funds_escrow @ ErrorCode::InvalidEscrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = sponsor_token_account.mint == mint.key() @ ErrorCode::InvalidMint,
+        token::authority = sponsor
+    )]
+    pub sponsor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Soulbound receipt mint, created by the handler on the first
+    /// contribution
+    #[account(
+        mut,
+        seeds = [b"receipt", contributor_account.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Beneficiary's Token-2022 associated account for the receipt
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &beneficiary.key(),
+            &receipt_mint.key(),
+            &Token2022::id(),
+        )
+    )]
+    pub receipt_token_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// `fund_proposal` paid by a sponsor and credited to `beneficiary`, who gets
+/// the receipt and any refund. Sponsored contributions don't count towards
+/// funding rounds, they aren't the beneficiary's own support. An allowlist
+/// applies to the beneficiary, whoever pays. The first sponsor is stored on
+/// the contributor account, the memo is only emitted in `ContributionSponsored`.
+pub fn fund_proposal_for_handler(
+    ctx: Context<FundProposalFor>,
+    amount: u64,
+    memo: Option<String>,
+    allowance: Option<AllowlistProof>,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidContribution);
+    require!(
+        memo.as_ref().map_or(0, String::len) <= MAX_SPONSOR_MEMO_LEN,
+        ErrorCode::MemoTooLong
+    );
+    let amount = accept_contribution(
+        &ctx.accounts.proposal,
+        ctx.accounts.beneficiary.key(),
+        ctx.accounts.contributor_account.amount,
+        amount,
+    )?;
+    check_allowlist(
+        &ctx.accounts.proposal,
+        &ctx.accounts.beneficiary.key(),
+        ctx.accounts
+            .contributor_account
+            .amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?,
+        allowance.as_ref(),
+    )?;
+
+    // 1. Move the sponsor's tokens into escrow
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.sponsor_token_account.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.sponsor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // 2. Credit the beneficiary
+    let beneficiary = ctx.accounts.beneficiary.key();
+    credit_contribution(
+        &mut ctx.accounts.proposal,
+        &mut ctx.accounts.contributor_account,
+        beneficiary,
+        ctx.accounts.sponsor.key(),
+        ctx.bumps.contributor_account,
+        amount,
+    )?;
+    // Later sponsors are only in the events
+    let contributor = &mut ctx.accounts.contributor_account;
+    if contributor.sponsor.is_none() {
+        contributor.sponsor = Some(ctx.accounts.sponsor.key());
+    }
+
+    let accounts = ReceiptAccounts {
+        payer: &ctx.accounts.sponsor.to_account_info(),
+        owner: &ctx.accounts.beneficiary.to_account_info(),
+        mint: &ctx.accounts.receipt_mint.to_account_info(),
+        token_account: &ctx.accounts.receipt_token_account.to_account_info(),
+        token_2022_program: &ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+        system_program: &ctx.accounts.system_program.to_account_info(),
+    };
+    issue_receipt(
+        &accounts,
+        ctx.bumps.receipt_mint,
+        &ctx.accounts.proposal,
+        &ctx.accounts.contributor_account,
+    )?;
+
+    let proposal = &ctx.accounts.proposal;
+    let contributor = &ctx.accounts.contributor_account;
+    emit!(Funded {
+        proposal: proposal.key(),
+        contributor: beneficiary,
+        contributor_account: contributor.key(),
+        amount,
+        contributor_total: contributor.amount,
+        amount_raised: proposal.amount_raised,
+        contributors_count: proposal.contributors_count,
+        timestamp: proposal.updated_at,
+    });
+    emit!(ContributionSponsored {
+        proposal: proposal.key(),
+        sponsor: ctx.accounts.sponsor.key(),
+        beneficiary,
+        contributor_account: contributor.key(),
+        amount,
+        memo,
+        timestamp: proposal.updated_at,
+    });
+
+    Ok(())
+}
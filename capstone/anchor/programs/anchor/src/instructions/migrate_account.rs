@@ -0,0 +1,110 @@
+use crate::error::ErrorCode;
+use crate::{
+    AccountMigrated, Contributor, FundingRound, Milestone, MilestoneEvidence, Pledge, Proposal,
+    RoundContribution, Versioned, Vote,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any of the program's accounts, its type is read from the
+    /// discriminator by the handler
+    #[account(mut, owner = crate::ID @ ErrorCode::InvalidAccountOwner)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_account_handler(ctx: Context<MigrateAccount>) -> Result<()> {
+    let discriminator = {
+        let data = ctx.accounts.account.try_borrow_data()?;
+        data.get(..8)
+            .ok_or(ErrorCode::UnknownAccountType)?
+            .to_vec()
+    };
+
+    let from_version = match discriminator.as_slice() {
+        d if d == Proposal::DISCRIMINATOR => migrate::<Proposal>(&ctx)?,
+        d if d == Milestone::DISCRIMINATOR => migrate::<Milestone>(&ctx)?,
+        d if d == Contributor::DISCRIMINATOR => migrate::<Contributor>(&ctx)?,
+        d if d == Vote::DISCRIMINATOR => migrate::<Vote>(&ctx)?,
+        d if d == FundingRound::DISCRIMINATOR => migrate::<FundingRound>(&ctx)?,
+        d if d == RoundContribution::DISCRIMINATOR => migrate::<RoundContribution>(&ctx)?,
+        d if d == MilestoneEvidence::DISCRIMINATOR => migrate::<MilestoneEvidence>(&ctx)?,
+        d if d == Pledge::DISCRIMINATOR => migrate::<Pledge>(&ctx)?,
+        _ => return err!(ErrorCode::UnknownAccountType),
+    };
+
+    let account = ctx.accounts.account.to_account_info();
+    emit!(AccountMigrated {
+        account: account.key(),
+        payer: ctx.accounts.payer.key(),
+        from_version,
+        to_version: account.try_borrow_data()?[8],
+        space: account.data_len() as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Brings an account of type `T` up to `T::VERSION`, returning the version it
+/// had before.
+fn migrate<T: Versioned>(ctx: &Context<MigrateAccount>) -> Result<u8> {
+    let account = ctx.accounts.account.to_account_info();
+    let old_len = account.data_len();
+
+    // 1. Accounts from before the header are told apart by their size
+    let from_version = if old_len == T::UNVERSIONED_SPACE {
+        0
+    } else {
+        let version = *account
+            .try_borrow_data()?
+            .get(8)
+            .ok_or(ErrorCode::UnsupportedAccountVersion)?;
+        require!(version < T::VERSION, ErrorCode::AccountAlreadyMigrated);
+        version
+    };
+
+    // 2. Decode the old layout from a copy with the header in place
+    let state = {
+        let mut data = account.try_borrow_data()?.to_vec();
+        if from_version == 0 {
+            data.insert(8, T::VERSION);
+        }
+        data[8] = T::VERSION;
+        data.resize(data.len().max(T::SPACE), 0);
+        T::upgrade(&data, from_version)?
+    };
+
+    // 3. Caller tops the account up to rent exemption at the new size
+    let rent = Rent::get()?
+        .minimum_balance(T::SPACE)
+        .saturating_sub(account.lamports());
+    if rent > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            rent,
+        )?;
+    }
+
+    // 4. Resize the account, layouts that replaced a field can also shrink,
+    // and rewrite it in the current layout
+    account.realloc(T::SPACE, true)?;
+    let mut data = account.try_borrow_mut_data()?;
+    data.fill(0);
+    state.try_serialize(&mut &mut data[..])?;
+
+    Ok(from_version)
+}
@@ -4,6 +4,8 @@ use anchor_spl::token::{spl_token, Mint};
 use mpl_token_metadata::instructions::CreateV1Builder;
 use mpl_token_metadata::types::{Creator, PrintSupply};
 
+use crate::{ContentId, IpNftMinted};
+
 #[derive(Accounts)]
 pub struct MintNft<'info> {
     #[account(mut)]
@@ -48,6 +50,9 @@ pub fn mint_ip_nft_handler(
     symbol: String,
     uri: String,
 ) -> Result<()> {
+    // Metadata must live on IPFS or Arweave, a path like /metadata.json is fine
+    ContentId::parse_uri(&uri)?;
+
     let creators = vec![Creator {
         address: ctx.accounts.payer.key(),
         verified: true,
@@ -66,9 +71,9 @@ pub fn mint_ip_nft_handler(
         .creators(creators)
         .seller_fee_basis_points(500)
         .is_mutable(true)
-        .name(name)
-        .symbol(symbol)
-        .uri(uri)
+        .name(name.clone())
+        .symbol(symbol.clone())
+        .uri(uri.clone())
         .decimals(0)
         .print_supply(PrintSupply::Zero)
         .token_standard(mpl_token_metadata::types::TokenStandard::NonFungible)
@@ -91,5 +96,16 @@ pub fn mint_ip_nft_handler(
     msg!("Invoking Metaplex CreateV1...");
     invoke(&create_ix, &create_infos)?;
 
+    emit!(IpNftMinted {
+        mint: ctx.accounts.mint.key(),
+        metadata: ctx.accounts.metadata.key(),
+        master_edition: ctx.accounts.master_edition.key(),
+        payer: ctx.accounts.payer.key(),
+        name,
+        symbol,
+        uri,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
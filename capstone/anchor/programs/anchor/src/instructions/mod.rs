@@ -24,3 +24,58 @@ pub use mint_ip_nft::*;
 
 pub mod close_proposal;
 pub use close_proposal::*;
+
+pub mod update_team;
+pub use update_team::*;
+
+pub mod migrate_account;
+pub use migrate_account::*;
+
+pub mod create_funding_round;
+pub use create_funding_round::*;
+
+pub mod distribute_matching;
+pub use distribute_matching::*;
+
+pub mod commit_vote;
+pub use commit_vote::*;
+
+pub mod reveal_vote;
+pub use reveal_vote::*;
+
+pub mod penalize_unrevealed_vote;
+pub use penalize_unrevealed_vote::*;
+
+pub mod add_evidence;
+pub use add_evidence::*;
+
+pub mod cancel_proposal;
+pub use cancel_proposal::*;
+
+pub mod close_milestone;
+pub use close_milestone::*;
+
+pub mod close_vote;
+pub use close_vote::*;
+
+pub mod close_contributor;
+pub use close_contributor::*;
+
+pub mod close_accounts;
+pub use close_accounts::*;
+pub mod challenge_milestone;
+pub use challenge_milestone::*;
+pub mod resolve_challenge;
+pub use resolve_challenge::*;
+pub mod propose_authority;
+pub use propose_authority::*;
+pub mod accept_authority;
+pub use accept_authority::*;
+pub mod fund_proposal_for;
+pub use fund_proposal_for::*;
+pub mod create_pledge;
+pub use create_pledge::*;
+pub mod execute_pledge;
+pub use execute_pledge::*;
+pub mod cancel_pledge;
+pub use cancel_pledge::*;
\ No newline at end of file
@@ -0,0 +1,53 @@
+use crate::error::ErrorCode;
+use crate::{Milestone, Proposal, Vote, VoteRevealMissed};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct PenalizeUnrevealedVote<'info> {
+    // Anyone can crank it, the forfeited vote rent is their reward
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
+    pub milestone: Account<'info, Milestone>,
+
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [
+            b"vote",
+            vote.validator.as_ref(),
+            milestone.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+}
+
+/// Penalty hook for validators who committed a vote but never revealed it.
+///
+/// The vote account is closed and its rent goes to the cranker.
+/// `VoteRevealMissed` lets staking or reputation programs apply their own
+/// penalty on top.
+pub fn penalize_unrevealed_vote_handler(ctx: Context<PenalizeUnrevealedVote>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let milestone = &mut ctx.accounts.milestone;
+    require!(milestone.commit_deadline != 0, ErrorCode::OpenVoting);
+    require!(now >= milestone.deadline, ErrorCode::RevealPhaseOpen);
+    require!(!ctx.accounts.vote.revealed, ErrorCode::VoteAlreadyRevealed);
+    milestone.open_votes = milestone.open_votes.saturating_sub(1);
+
+    emit!(VoteRevealMissed {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        validator: ctx.accounts.vote.validator,
+        vote: ctx.accounts.vote.key(),
+        cranker: ctx.accounts.cranker.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::{AuthorityProposed, Proposal};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// First half of an authority transfer, `accept_authority` completes it.
+/// `None` cancels a pending transfer.
+pub fn propose_authority_handler(
+    ctx: Context<ProposeAuthority>,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.pending_authority = new_authority;
+    proposal.updated_at = now;
+
+    emit!(AuthorityProposed {
+        proposal: proposal.key(),
+        authority: proposal.authority,
+        pending_authority: new_authority,
+        timestamp: now,
+    });
+
+    Ok(())
+}
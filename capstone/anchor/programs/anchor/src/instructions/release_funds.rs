@@ -1,21 +1,26 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
-use crate::{Milestone, MilestoneStatus, Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::transition;
+use crate::{
+    FundsReleased, Milestone, MilestoneStatus, Payout, Proposal, ProposalStatus,
+    TEAM_SHARE_TOTAL_BPS,
+};
 
 #[derive(Accounts)]
 pub struct ReleaseFunds<'info> {
-    #[account(mut)]
-    pub researcher: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        has_one = researcher,
+        has_one = authority,
         seeds = [
             b"proposal", 
-            researcher.key().as_ref(), 
+            proposal.researcher.as_ref(), 
             proposal.title.as_bytes()
         ],
         bump,
+        constraint = proposal.status == ProposalStatus::Active @ ErrorCode::ProposalNotActive,
     )]
     pub proposal: Account<'info, Proposal>,
 
@@ -30,27 +35,64 @@ pub struct ReleaseFunds<'info> {
     #[account(
         mut,
         constraint = milestone.status == MilestoneStatus::Validated @ ErrorCode::MilestoneNotValidated,
-        constraint = milestone.funds_released == false @ ErrorCode::FundsAlreadyReleased,
+        constraint = !milestone.funds_released @ ErrorCode::FundsAlreadyReleased,
         constraint = milestone.proposal == proposal.key() @ ErrorCode::InvalidMilestone,
     )]
     pub milestone: Account<'info, Milestone>,
 
     #[account(
         mut,
-        constraint = researcher_token_account.owner == researcher.key(),
+        constraint = authority_token_account.owner == authority.key(),
     )]
-    pub researcher_token_account: Account<'info, TokenAccount>,
+    pub authority_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    // remaining_accounts: token accounts of the team members other than the authority, in roster order
 }
 
-pub fn release_funds_handler(ctx: Context<ReleaseFunds>) -> Result<()> {
+pub fn release_funds_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReleaseFunds<'info>>,
+) -> Result<()> {
     let clock = Clock::get()?;
+
+    // Contributors can challenge the verdict until the window has passed. A
+    // milestone is only challenged once, a dismissed challenge settles it
+    if ctx.accounts.milestone.challenger.is_none() {
+        let challenge_ends_at = ctx.accounts.milestone.validated_at
+            .checked_add(ctx.accounts.proposal.challenge_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            clock.unix_timestamp >= challenge_ends_at,
+            ErrorCode::ChallengePeriodActive
+        );
+    }
     
-    // Calculate payment amount per milestone
-    let amount_per_milestone = ctx.accounts.proposal.amount_asked
-        .checked_div(ctx.accounts.proposal.total_milestones as u64)
+    // Stretch milestones pay their own budget, matching funds go to the base ones
+    let proposal = &ctx.accounts.proposal;
+    let (base_amount, matched_payout) =
+        if let Some(goal) = proposal.stretch_goal(ctx.accounts.milestone.milestone_number) {
+            (goal.budget, 0)
+        } else {
+            // Calculate payment amount per milestone
+            let base_amount = proposal.amount_asked
+                .checked_div(proposal.total_milestones as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            // Matching funds still held are spread over the milestones left, this one included
+            let milestones_left = proposal
+                .total_milestones
+                .saturating_sub(ctx.accounts.milestone.milestone_number)
+                .saturating_add(1);
+            let matched_payout = proposal
+                .matched_amount
+                .checked_sub(proposal.matched_released)
+                .and_then(|v| v.checked_div(milestones_left as u64))
+                .ok_or(ErrorCode::MathOverflow)?;
+            (base_amount, matched_payout)
+        };
+    let amount_per_milestone = base_amount
+        .checked_add(matched_payout)
         .ok_or(ErrorCode::MathOverflow)?;
 
     // Check if escrow has enough tokens
@@ -59,9 +101,9 @@ pub fn release_funds_handler(ctx: Context<ReleaseFunds>) -> Result<()> {
         ErrorCode::InsufficientFunds
     );
 
-    // Transfer tokens from escrow to researcher
+    // Transfer tokens from escrow to the research team
     // Fixed PDA signer derivation
-    let binding = ctx.accounts.researcher.key();
+    let binding = ctx.accounts.proposal.researcher;
     let seeds = &[
         b"proposal",
         binding.as_ref(),
@@ -70,18 +112,94 @@ pub fn release_funds_handler(ctx: Context<ReleaseFunds>) -> Result<()> {
     ];
     let signer = &[&seeds[..]];
 
+    // Pay every team member their split, the authority also receives any rounding dust
+    let authority = ctx.accounts.authority.key();
+    let mut member_accounts = ctx.remaining_accounts.iter();
+    let mut authority_payout = amount_per_milestone;
+    let mut payouts = Vec::with_capacity(ctx.accounts.proposal.team.len());
+
+    for member in ctx.accounts.proposal.team.iter() {
+        if member.wallet == authority {
+            continue;
+        }
+
+        let share = (amount_per_milestone as u128)
+            .checked_mul(member.share_bps as u128)
+            .and_then(|v| v.checked_div(TEAM_SHARE_TOTAL_BPS as u128))
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+
+        let member_info = member_accounts
+            .next()
+            .ok_or(ErrorCode::MissingMemberTokenAccount)?;
+        let member_token_account = Account::<TokenAccount>::try_from(member_info)?;
+        require!(
+            member_token_account.owner == member.wallet
+                && member_token_account.mint == ctx.accounts.escrow.mint,
+            ErrorCode::InvalidMemberTokenAccount
+        );
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: member_info.clone(),
+                    authority: ctx.accounts.proposal.to_account_info(),
+                },
+                signer,
+            ),
+            share,
+        )?;
+
+        authority_payout = authority_payout
+            .checked_sub(share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        payouts.push(Payout {
+            wallet: member.wallet,
+            token_account: member_info.key(),
+            amount: share,
+        });
+    }
+
     token::transfer(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             token::Transfer {
                 from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.researcher_token_account.to_account_info(),
+                to: ctx.accounts.authority_token_account.to_account_info(),
                 authority: ctx.accounts.proposal.to_account_info(),
             },
             signer,
         ),
-        amount_per_milestone,
+        authority_payout,
     )?;
+    payouts.push(Payout {
+        wallet: authority,
+        token_account: ctx.accounts.authority_token_account.key(),
+        amount: authority_payout,
+    });
+
+    // The last release hands the researcher their deposit back, leaving the escrow empty
+    let completes = ctx.accounts.proposal.is_last_release();
+    let deposit_returned = if completes {
+        ctx.accounts.proposal.current_security_deposit
+    } else {
+        0
+    };
+    if deposit_returned > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.authority_token_account.to_account_info(),
+                    authority: ctx.accounts.proposal.to_account_info(),
+                },
+                signer,
+            ),
+            deposit_returned,
+        )?;
+    }
 
     // Update milestone state
     let milestone = &mut ctx.accounts.milestone;
@@ -91,34 +209,35 @@ pub fn release_funds_handler(ctx: Context<ReleaseFunds>) -> Result<()> {
     // Update proposal state
     let proposal = &mut ctx.accounts.proposal;
     proposal.updated_at = clock.unix_timestamp;
+    proposal.matched_released = proposal
+        .matched_released
+        .checked_add(matched_payout)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if let Some(released) = proposal.milestones_released {
+        proposal.milestones_released =
+            Some(released.checked_add(1).ok_or(ErrorCode::MathOverflow)?);
+    }
 
-    // Check if all milestones completed
-    if proposal.current_milestone == proposal.total_milestones {
-        proposal.status = ProposalStatus::Completed;
+    // Check if all milestones completed, unlocked stretch goals included
+    if completes {
+        transition(&mut proposal.status, ProposalStatus::Completed)?;
+        proposal.current_security_deposit = 0;
     }
 
-    msg!("Released {} tokens for milestone {}", amount_per_milestone, ctx.accounts.milestone.milestone_number);
+    ctx.accounts.escrow.reload()?;
+
+    emit!(FundsReleased {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: ctx.accounts.milestone.key(),
+        escrow: ctx.accounts.escrow.key(),
+        milestone_number: ctx.accounts.milestone.milestone_number,
+        amount: amount_per_milestone,
+        payouts,
+        escrow_balance: ctx.accounts.escrow.amount,
+        deposit_returned,
+        proposal_completed: ctx.accounts.proposal.status == ProposalStatus::Completed,
+        timestamp: clock.unix_timestamp,
+    });
 
     Ok(())
 }
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Insufficient funds in escrow")]
-    InsufficientFunds,
-
-    #[msg("Milestone not validated")]
-    MilestoneNotValidated,
-    
-    #[msg("Funds already released")]
-    FundsAlreadyReleased,
-    
-    #[msg("Arithmetic overflow")]
-    MathOverflow,
-    
-    #[msg("Invalid escrow account")]
-    InvalidEscrow,
-    
-    #[msg("Invalid milestone for this proposal")]
-    InvalidMilestone,
-}
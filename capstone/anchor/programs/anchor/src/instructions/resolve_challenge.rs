@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount};
+
+use crate::error::ErrorCode;
+use crate::state_machine::{require_status, transition};
+use crate::{ChallengeResolved, Milestone, MilestoneStatus, Proposal};
+
+#[derive(Accounts)]
+pub struct ResolveChallenge<'info> {
+    // Anyone can settle a dispute once its vote is over
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        mut,
+        seeds = [b"challenge", milestone.key().as_ref()],
+        bump,
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = Some(challenger_token_account.owner) == milestone.challenger
+            @ ErrorCode::InvalidBondRecipient,
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.owner == proposal.authority
+            @ ErrorCode::InvalidBondRecipient,
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    /// Paid for the bond vault
+    #[account(
+        mut,
+        constraint = Some(challenger.key()) == milestone.challenger @ ErrorCode::InvalidRentRecipient,
+    )]
+    pub challenger: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Settles a disputed milestone once the dispute period is over. A majority
+/// of rejections upholds the challenge: the milestone fails and the bond goes
+/// back to the challenger. Otherwise the milestone is validated again, its
+/// funds can be released right away and the proposal's authority keeps the
+/// bond.
+pub fn resolve_challenge_handler(ctx: Context<ResolveChallenge>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let milestone = &ctx.accounts.milestone;
+
+    require_status(
+        &milestone.status,
+        &[MilestoneStatus::Disputed],
+        ErrorCode::MilestoneNotDisputed,
+    )?;
+    require!(now >= milestone.deadline, ErrorCode::DisputeOpen);
+    let upheld = milestone.rejection_votes > milestone.total_validators / 2;
+
+    // 1. Pay out the bond and close its vault
+    let proposal = &ctx.accounts.proposal;
+    let seeds = &[
+        b"proposal".as_ref(),
+        proposal.researcher.as_ref(),
+        proposal.title.as_bytes(),
+        &[ctx.bumps.proposal],
+    ];
+    let recipient = if upheld {
+        &ctx.accounts.challenger_token_account
+    } else {
+        &ctx.accounts.authority_token_account
+    };
+    let bond = ctx.accounts.bond_vault.amount;
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                to: recipient.to_account_info(),
+                authority: proposal.to_account_info(),
+            },
+            &[seeds],
+        ),
+        bond,
+    )?;
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.bond_vault.to_account_info(),
+            destination: ctx.accounts.challenger.to_account_info(),
+            authority: proposal.to_account_info(),
+        },
+        &[seeds],
+    ))?;
+    let bond_recipient = recipient.key();
+
+    // 2. Settle the milestone
+    let milestone = &mut ctx.accounts.milestone;
+    let next = if upheld {
+        MilestoneStatus::Failed
+    } else {
+        MilestoneStatus::Validated
+    };
+    transition(&mut milestone.status, next)?;
+    milestone.updated_at = now;
+
+    emit!(ChallengeResolved {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        challenger: ctx.accounts.challenger.key(),
+        milestone_number: milestone.milestone_number,
+        upheld,
+        bond,
+        bond_recipient,
+        validation_votes: milestone.validation_votes,
+        rejection_votes: milestone.rejection_votes,
+        timestamp: now,
+    });
+
+    Ok(())
+}
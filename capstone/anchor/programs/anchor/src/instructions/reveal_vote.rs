@@ -0,0 +1,86 @@
+use crate::error::ErrorCode;
+use crate::instructions::validate_milestone::{record_review, settle_votes};
+use crate::{Milestone, Proposal, Review, Vote, VoteCast};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RevealVote<'info> {
+    pub validator: Signer<'info>,
+
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
+    pub milestone: Account<'info, Milestone>,
+
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vote",
+            validator.key().as_ref(),
+            milestone.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote: Account<'info, Vote>,
+}
+
+/// Second phase of a commit-reveal vote. Only votes matching their
+/// commitment are tallied, reveals still count after the milestone is
+/// decided so nobody is penalized for an early majority. The review isn't
+/// part of the commitment, it's published with the vote.
+pub fn reveal_vote_handler(
+    ctx: Context<RevealVote>,
+    approved: bool,
+    salt: [u8; 32],
+    review: Option<Review>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let milestone = &mut ctx.accounts.milestone;
+    require!(milestone.commit_deadline != 0, ErrorCode::OpenVoting);
+    require!(
+        now >= milestone.commit_deadline,
+        ErrorCode::RevealPhaseNotOpen
+    );
+    require!(
+        now < milestone.deadline,
+        ErrorCode::ValidationDeadlinePassed
+    );
+
+    let vote = &mut ctx.accounts.vote;
+    require!(!vote.revealed, ErrorCode::VoteAlreadyRevealed);
+    require!(
+        Vote::commitment(&milestone.key(), &vote.validator, approved, &salt) == vote.commitment,
+        ErrorCode::CommitmentMismatch
+    );
+
+    vote.approved = approved;
+    vote.revealed = true;
+    vote.voted_at = now;
+
+    milestone.reveals = milestone
+        .reveals
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if approved {
+        milestone.validation_votes += 1;
+    } else {
+        milestone.rejection_votes += 1;
+    }
+    record_review(milestone, vote, review)?;
+
+    emit!(VoteCast {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        validator: vote.validator,
+        vote: vote.key(),
+        approved,
+        validation_votes: milestone.validation_votes,
+        total_validators: milestone.total_validators,
+        evidence_version: vote.evidence_version,
+        review: vote.review.clone(),
+        timestamp: now,
+    });
+
+    settle_votes(&ctx.accounts.proposal, milestone, now)
+}
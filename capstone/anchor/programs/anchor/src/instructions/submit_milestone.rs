@@ -1,19 +1,27 @@
 use anchor_lang::prelude::*;
-use crate::{Milestone, MilestoneStatus, Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::{require_status, transition};
+use crate::{
+    ContentId, Milestone, MilestoneEvidence, MilestoneStatus, MilestoneSubmitted, Proposal, ProposalStatus,
+    Versioned, COMMIT_PHASE_SECONDS,
+};
 
 #[derive(Accounts)]
-#[instruction(milestone_number: u8, evidence_hash: String)]
+#[instruction(milestone_number: u8, evidence_hash: String, commit_reveal: bool)]
 pub struct SubmitMilestone<'info>{
 
+    pub authority: Signer<'info>,
+
+    // Separate from the authority, which may be a PDA that can't pay
     #[account(mut)]
-    pub researcher: Signer<'info>,
+    pub payer: Signer<'info>,
 
      #[account(
         mut,
-        has_one = researcher,
+        has_one = authority,
         seeds = [
             b"proposal", 
-            researcher.key().as_ref(), 
+            proposal.researcher.as_ref(), 
             proposal.title.as_bytes()
         ],
         bump,
@@ -22,7 +30,7 @@ pub struct SubmitMilestone<'info>{
 
     #[account(
         init,
-        payer = researcher,
+        payer = payer,
         space = 8 + Milestone::INIT_SPACE,
         seeds = [
             b"milestone",
@@ -33,6 +41,18 @@ pub struct SubmitMilestone<'info>{
     )]
     pub milestone: Account<'info, Milestone>,
 
+    /// CHECK: Attachments added with `add_evidence`, left uninitialized when
+    /// there are none
+    #[account(
+        seeds = [
+            b"evidence",
+            proposal.key().as_ref(),
+            &milestone_number.to_le_bytes()
+        ],
+        bump
+    )]
+    pub evidence: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 
 }
@@ -42,35 +62,68 @@ pub fn submit_milestone_handler(
     ctx: Context<SubmitMilestone>,
     milestone_number: u8,
     evidence_hash: String,
+    commit_reveal: bool,
 ) -> Result<()> {
     let clock = Clock::get()?;
     
     // Validate proposal state
-    // require!(
-    //     ctx.accounts.proposal.status == ProposalStatus::Active,
-    //     ErrorCode::ProposalNotActive
-    // );
+    require_status(
+        &ctx.accounts.proposal.status,
+        &[ProposalStatus::Active],
+        ErrorCode::ProposalNotActive,
+    )?;
 
     // Validate milestone sequence
     require!(
         milestone_number == ctx.accounts.proposal.current_milestone + 1,
         ErrorCode::InvalidMilestoneOrder
     );
+    let proposal = &ctx.accounts.proposal;
     require!(
-        milestone_number <= ctx.accounts.proposal.total_milestones,
+        (milestone_number as usize)
+            <= proposal.total_milestones as usize + proposal.stretch_goals.len(),
         ErrorCode::ExceedsTotalMilestones
     );
+    // Stretch milestones only follow once the funding unlocked them
+    require!(
+        milestone_number <= proposal.milestone_count(),
+        ErrorCode::StretchGoalLocked
+    );
+
+    let evidence_hash = ContentId::parse(&evidence_hash)?;
+
+    // Voting opens on the evidence set as it stands now
+    let evidence = &ctx.accounts.evidence;
+    let evidence_version = if evidence.data_is_empty() {
+        0
+    } else {
+        require_keys_eq!(*evidence.owner, crate::ID, ErrorCode::InvalidAccountOwner);
+        MilestoneEvidence::try_deserialize(&mut &evidence.try_borrow_data()?[..])?.set_version
+    };
 
     // Initialize milestone
     let milestone = &mut ctx.accounts.milestone;
+    milestone.version = Milestone::VERSION;
     milestone.proposal = ctx.accounts.proposal.key();
     milestone.milestone_number = milestone_number;
-    milestone.evidence_hash = evidence_hash;
+    milestone.payer = ctx.accounts.payer.key();
+    milestone.evidence_hash = Some(evidence_hash.clone());
     milestone.validation_votes = 0;
+    milestone.rejection_votes = 0;
     milestone.total_validators = 3;     //TEST: This is only for testing purposes 
     milestone.funds_released = false;
     milestone.deadline = clock.unix_timestamp + 14 * 86400; 
-    milestone.status = MilestoneStatus::Pending;
+    // Hidden votes are committed first and revealed before the deadline
+    milestone.commit_deadline = if commit_reveal {
+        clock.unix_timestamp + COMMIT_PHASE_SECONDS
+    } else {
+        0
+    };
+    milestone.commits = 0;
+    milestone.reveals = 0;
+    milestone.evidence_version = evidence_version;
+    // Evidence is in, voting opens
+    transition(&mut milestone.status, MilestoneStatus::Active)?;
     milestone.created_at = clock.unix_timestamp;
     milestone.updated_at = clock.unix_timestamp;
 
@@ -78,20 +131,22 @@ pub fn submit_milestone_handler(
     let proposal = &mut ctx.accounts.proposal;
     proposal.current_milestone = milestone_number;
     proposal.updated_at = clock.unix_timestamp;
+    proposal.open_accounts = proposal
+        .open_accounts
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    Ok(())
-}
-
+    emit!(MilestoneSubmitted {
+        proposal: proposal.key(),
+        milestone: milestone.key(),
+        researcher: proposal.researcher,
+        milestone_number,
+        evidence_hash,
+        deadline: milestone.deadline,
+        commit_deadline: milestone.commit_deadline,
+        evidence_version,
+        timestamp: clock.unix_timestamp,
+    });
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Proposal is not in Active state")]
-    ProposalNotActive,
-    
-    #[msg("Milestone number must follow sequence")]
-    InvalidMilestoneOrder,
-    
-    #[msg("Milestone exceeds total allowed milestones")]
-    ExceedsTotalMilestones,
-    
+    Ok(())
 }
@@ -0,0 +1,76 @@
+use crate::error::ErrorCode;
+use crate::{
+    Proposal, ProposalStatus, TeamMember, TeamUpdated, MAX_TEAM_MEMBERS, TEAM_SHARE_TOTAL_BPS,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateTeam<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [
+            b"proposal",
+            proposal.researcher.as_ref(),
+            proposal.title.as_bytes()
+        ],
+        bump,
+        constraint = proposal.status == ProposalStatus::Active @ ErrorCode::ProposalNotActive,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    // remaining_accounts: every current team member other than the authority, as signers
+}
+
+pub fn update_team_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateTeam<'info>>,
+    members: Vec<TeamMember>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // Validate the new roster
+    require!(
+        !members.is_empty() && members.len() <= MAX_TEAM_MEMBERS as usize,
+        ErrorCode::InvalidTeamSize
+    );
+
+    let mut total_bps: u16 = 0;
+    for (i, member) in members.iter().enumerate() {
+        require!(member.share_bps > 0, ErrorCode::InvalidTeamShares);
+        require!(
+            !members[..i].iter().any(|m| m.wallet == member.wallet),
+            ErrorCode::DuplicateTeamMember
+        );
+        total_bps = total_bps
+            .checked_add(member.share_bps)
+            .ok_or(ErrorCode::InvalidTeamShares)?;
+    }
+    require!(total_bps == TEAM_SHARE_TOTAL_BPS, ErrorCode::InvalidTeamShares);
+
+    // Every existing member has to approve the change by signing
+    let authority = ctx.accounts.authority.key();
+    for member in ctx.accounts.proposal.team.iter() {
+        if member.wallet == authority {
+            continue;
+        }
+        require!(
+            ctx.remaining_accounts
+                .iter()
+                .any(|account| account.key() == member.wallet && account.is_signer),
+            ErrorCode::MissingTeamApproval
+        );
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.team = members;
+    proposal.updated_at = clock.unix_timestamp;
+
+    emit!(TeamUpdated {
+        proposal: proposal.key(),
+        members: proposal.team.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,4 +1,10 @@
-use crate::{Milestone, MilestoneStatus, Proposal, Vote};
+use crate::error::ErrorCode;
+use crate::instructions::close_vote::require_revealed;
+use crate::state_machine::{require_status, transition};
+use crate::{
+    Milestone, MilestoneFailed, MilestoneStatus, MilestoneValidated, Proposal, Review, Versioned,
+    Vote, VoteCast, MAX_REVIEW_SCORE,
+};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -7,7 +13,7 @@ pub struct ValidateMilestone<'info> {
     #[account(mut)]
     pub validator: Signer<'info>,
 
-    #[account(mut)]
+    #[account(mut, has_one = proposal @ ErrorCode::InvalidMilestone)]
     pub milestone: Account<'info, Milestone>,
 
     #[account(mut)]
@@ -29,58 +35,159 @@ pub struct ValidateMilestone<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn validate_milestone_handler(ctx: Context<ValidateMilestone>, approved: bool) -> Result<()> {
+pub fn validate_milestone_handler(
+    ctx: Context<ValidateMilestone>,
+    approved: bool,
+    review: Option<Review>,
+) -> Result<()> {
     let clock = Clock::get()?;
 
-    // Commented out for testing purpose
-    // Validate milestone state
-    // require!(
-    //     ctx.accounts.milestone.status == MilestoneStatus::Active,
-    //     ErrorCode::MilestoneNotActive
-    // );
+    // Validate milestone state, challenged milestones are voted on again
+    require_status(
+        &ctx.accounts.milestone.status,
+        &[MilestoneStatus::Active, MilestoneStatus::Disputed],
+        ErrorCode::MilestoneNotActive,
+    )?;
     require!(
         clock.unix_timestamp < ctx.accounts.milestone.deadline,
         ErrorCode::ValidationDeadlinePassed
     );
+    require!(
+        ctx.accounts.milestone.commit_deadline == 0,
+        ErrorCode::CommitRevealVoting
+    );
 
     // Check if validator already voted
     let vote = &mut ctx.accounts.vote;
-    if vote.voted_at == 0 {
+    if vote.validator == Pubkey::default() {
         // First vote
+        vote.version = Vote::VERSION;
         vote.validator = ctx.accounts.validator.key();
         vote.milestone = ctx.accounts.milestone.key();
         vote.approved = approved;
         vote.voted_at = clock.unix_timestamp;
+        ctx.accounts.milestone.open_votes = ctx.accounts.milestone
+            .open_votes
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
 
         // Update vote count
         if approved {
             ctx.accounts.milestone.validation_votes += 1;
+        } else {
+            ctx.accounts.milestone.rejection_votes += 1;
         }
     } else {
-        // Update existing vote
+        // Update existing vote, hidden ones only count once revealed
+        require_revealed(vote)?;
         if vote.approved && !approved {
             ctx.accounts.milestone.validation_votes -= 1;
+            ctx.accounts.milestone.rejection_votes += 1;
         } else if !vote.approved && approved {
+            ctx.accounts.milestone.rejection_votes -= 1;
             ctx.accounts.milestone.validation_votes += 1;
         }
         vote.approved = approved;
     }
+    vote.evidence_version = ctx.accounts.milestone.evidence_version;
+    record_review(&mut ctx.accounts.milestone, vote, review)?;
 
-    // Check validation threshold
     let milestone = &mut ctx.accounts.milestone;
-    if milestone.validation_votes > milestone.total_validators / 2 {
-        milestone.status = MilestoneStatus::Validated;
-        milestone.funds_released = true;
-        milestone.updated_at = clock.unix_timestamp;
+    emit!(VoteCast {
+        proposal: ctx.accounts.proposal.key(),
+        milestone: milestone.key(),
+        validator: ctx.accounts.validator.key(),
+        vote: ctx.accounts.vote.key(),
+        approved,
+        validation_votes: milestone.validation_votes,
+        total_validators: milestone.total_validators,
+        evidence_version: milestone.evidence_version,
+        review: ctx.accounts.vote.review.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    settle_votes(&ctx.accounts.proposal, milestone, clock.unix_timestamp)
+}
+
+/// Replaces the review behind a validator's vote in the milestone's totals,
+/// voting again without one withdraws the earlier review.
+pub(crate) fn record_review(
+    milestone: &mut Milestone,
+    vote: &mut Vote,
+    review: Option<Review>,
+) -> Result<()> {
+    if let Some(review) = &review {
+        let scores = &review.scores;
+        require!(
+            scores.reproducibility <= MAX_REVIEW_SCORE
+                && scores.completeness <= MAX_REVIEW_SCORE
+                && scores.quality <= MAX_REVIEW_SCORE,
+            ErrorCode::InvalidReviewScore
+        );
     }
 
+    if let Some(previous) = vote.review.take() {
+        milestone.review_totals.remove(&previous.scores)?;
+        milestone.reviews = milestone
+            .reviews
+            .checked_sub(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    if let Some(review) = &review {
+        milestone.review_totals.add(&review.scores)?;
+        milestone.reviews = milestone
+            .reviews
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    vote.review = review;
     Ok(())
 }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Milestone is not in Active state")]
-    MilestoneNotActive,
-    #[msg("Validation deadline has passed")]
-    ValidationDeadlinePassed,
+/// Validates or fails an Active milestone once either side holds a majority
+/// of `total_validators`. Disputed milestones are settled by
+/// `resolve_challenge` once the dispute period is over.
+pub(crate) fn settle_votes(
+    proposal: &Account<Proposal>,
+    milestone: &mut Account<Milestone>,
+    now: i64,
+) -> Result<()> {
+    if milestone.status != MilestoneStatus::Active {
+        return Ok(());
+    }
+
+    // Check validation and rejection thresholds
+    if milestone.validation_votes > milestone.total_validators / 2 {
+        transition(&mut milestone.status, MilestoneStatus::Validated)?;
+        milestone.validated_at = now;
+        milestone.updated_at = now;
+    } else if milestone.rejection_votes > milestone.total_validators / 2 {
+        transition(&mut milestone.status, MilestoneStatus::Failed)?;
+        milestone.updated_at = now;
+    }
+
+    if milestone.status == MilestoneStatus::Validated {
+        emit!(MilestoneValidated {
+            proposal: proposal.key(),
+            milestone: milestone.key(),
+            milestone_number: milestone.milestone_number,
+            validation_votes: milestone.validation_votes,
+            total_validators: milestone.total_validators,
+            challenge_ends_at: now
+                .checked_add(proposal.challenge_period)
+                .ok_or(ErrorCode::MathOverflow)?,
+            timestamp: now,
+        });
+    } else if milestone.status == MilestoneStatus::Failed {
+        emit!(MilestoneFailed {
+            proposal: proposal.key(),
+            milestone: milestone.key(),
+            milestone_number: milestone.milestone_number,
+            rejection_votes: milestone.rejection_votes,
+            total_validators: milestone.total_validators,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
 }
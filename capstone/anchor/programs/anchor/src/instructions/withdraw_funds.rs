@@ -1,11 +1,15 @@
-use crate::{Contributor, Proposal, ProposalStatus};
+use crate::error::ErrorCode;
+use crate::state_machine::require_status;
+use crate::{Contributor, Proposal, ProposalStatus, RefundWithdrawn};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct WithdrawFunds<'info> {
-    #[account(mut)]
-    pub contributor: Signer<'info>,
+    /// CHECK: Wallet credited with the contribution. It doesn't sign, the
+    /// refund only goes to a token account it owns, so beneficiaries that
+    /// never sign (e.g. a DAO treasury PDA) can still be refunded
+    pub contributor: UncheckedAccount<'info>,
 
     #[account(
         mut,
@@ -53,16 +57,22 @@ pub fn withdraw_funds_handler(ctx: Context<WithdrawFunds>) -> Result<()> {
     let proposal = &ctx.accounts.proposal;
     let escrow = &ctx.accounts.escrow;
 
-    // 1. Proposal must have failed. Commendted out only for testing purpose
-    // require!(
-    //     proposal.status == ProposalStatus::Failed,
-    //     ErrorCode::ProposalNotFailed
-    // );
+    // 1. Proposal must have failed, or completed with funds left over
+    require_status(
+        &proposal.status,
+        &[ProposalStatus::Failed, ProposalStatus::Completed],
+        ErrorCode::ProposalNotFailed,
+    )?;
+
+    require!(contributor.amount > 0, ErrorCode::AlreadyWithdrawn);
 
-    // 2. Calculate refund (proportional to contribution)
+    // 2. Calculate refund (proportional to contribution). A completed
+    // proposal only gives back what its milestones didn't pay out, e.g. the
+    // overfunding set aside for stretch goals that never unlocked.
     let escrow_balance = escrow.amount;
+    let refundable = proposal.refundable(escrow_balance);
     let refund = (contributor.amount as u128)
-        .checked_mul(escrow_balance as u128)
+        .checked_mul(refundable as u128)
         .and_then(|v| v.checked_div(proposal.amount_raised as u128))
         .ok_or(ErrorCode::MathOverflow)? as u64;
 
@@ -91,24 +101,26 @@ pub fn withdraw_funds_handler(ctx: Context<WithdrawFunds>) -> Result<()> {
         refund,
     )?;
 
-    // Probably set amount to 0 to prevent further withdrawals?
-    // ctx.accounts.contributor_account.amount = 0;
+    // Retire the contribution so it can't be refunded twice, and so later
+    // refunds split the remaining escrow over the contributions still in it
+    let contributed = ctx.accounts.contributor_account.amount;
+    ctx.accounts.contributor_account.amount = 0;
+    ctx.accounts.proposal.amount_raised = ctx
+        .accounts
+        .proposal
+        .amount_raised
+        .checked_sub(contributed)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(RefundWithdrawn {
+        proposal: ctx.accounts.proposal.key(),
+        contributor: ctx.accounts.contributor.key(),
+        contributor_account: ctx.accounts.contributor_account.key(),
+        amount: refund,
+        contributed,
+        escrow_balance: escrow_balance - refund,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Proposal is not failed")]
-    ProposalNotFailed,
-    #[msg("Contributor already withdrawn")]
-    AlreadyWithdrawn,
-    #[msg("Math overflow")]
-    MathOverflow,
-    #[msg("No refund available")]
-    NoRefundAvailable,
-    #[msg("Invalid escrow account")]
-    InvalidEscrow,
-    #[msg("Invalid mint")]
-    InvalidMint,
-}
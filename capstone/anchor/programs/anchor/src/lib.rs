@@ -1,11 +1,22 @@
+// `#[program]` generates dispatch code with `create_proposal`'s arguments
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 
+pub mod allowlist;
 pub mod constants;
+pub mod content_id;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod receipt;
 pub mod state;
+pub mod state_machine;
 
+pub use allowlist::AllowlistProof;
 pub use constants::*;
+pub use content_id::ContentId;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -25,6 +36,10 @@ pub mod anchor {
         amount_asked: u64,
         total_milestones: u8,
         security_deposit: u64,
+        challenge_period: i64,
+        allowlist_root: Option<[u8; 32]>,
+        contribution_limits: ContributionLimits,
+        stretch_goals: Vec<StretchGoal>,
     ) -> Result<()> {
         create_proposal_handler(
             ctx,
@@ -34,12 +49,20 @@ pub mod anchor {
             amount_asked,
             total_milestones,
             security_deposit,
+            challenge_period,
+            allowlist_root,
+            contribution_limits,
+            stretch_goals,
         )
     }
 
     // 2. Fund Proposal
-    pub fn fund_proposal(ctx: Context<FundProposal>, amount: u64) -> Result<()> {
-        fund_proposal_handler(ctx, amount)
+    pub fn fund_proposal(
+        ctx: Context<FundProposal>,
+        amount: u64,
+        allowance: Option<AllowlistProof>,
+    ) -> Result<()> {
+        fund_proposal_handler(ctx, amount, allowance)
     }
 
     // 3. Submit Milestone
@@ -47,17 +70,24 @@ pub mod anchor {
         ctx: Context<SubmitMilestone>,
         milestone_number: u8,
         evidence_hash: String,
+        commit_reveal: bool,
     ) -> Result<()> {
-        submit_milestone_handler(ctx, milestone_number, evidence_hash)
+        submit_milestone_handler(ctx, milestone_number, evidence_hash, commit_reveal)
     }
 
     // 4. Validate Milestone
-    pub fn validate_milestone(ctx: Context<ValidateMilestone>, approved: bool) -> Result<()> {
-        validate_milestone_handler(ctx, approved)
+    pub fn validate_milestone(
+        ctx: Context<ValidateMilestone>,
+        approved: bool,
+        review: Option<Review>,
+    ) -> Result<()> {
+        validate_milestone_handler(ctx, approved, review)
     }
 
     // 5. Release Funds
-    pub fn release_funds(ctx: Context<ReleaseFunds>) -> Result<()> {
+    pub fn release_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseFunds<'info>>,
+    ) -> Result<()> {
         release_funds_handler(ctx)
     }
 
@@ -85,4 +115,143 @@ pub mod anchor {
     pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
         close_proposal_handler(ctx)
     }
+
+    // 10. Update Team
+    pub fn update_team<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateTeam<'info>>,
+        members: Vec<TeamMember>,
+    ) -> Result<()> {
+        update_team_handler(ctx, members)
+    }
+
+    // 11. Migrate Account
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        migrate_account_handler(ctx)
+    }
+
+    // 12. Create Funding Round
+    pub fn create_funding_round(
+        ctx: Context<CreateFundingRound>,
+        round_id: u64,
+        matching_pool: u64,
+        starts_at: i64,
+        ends_at: i64,
+        proposals: Vec<Pubkey>,
+    ) -> Result<()> {
+        create_funding_round_handler(ctx, round_id, matching_pool, starts_at, ends_at, proposals)
+    }
+
+    // 13. Distribute Matching
+    pub fn distribute_matching<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeMatching<'info>>,
+    ) -> Result<()> {
+        distribute_matching_handler(ctx)
+    }
+
+    // 14. Commit Vote
+    pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+        commit_vote_handler(ctx, commitment)
+    }
+
+    // 15. Reveal Vote
+    pub fn reveal_vote(
+        ctx: Context<RevealVote>,
+        approved: bool,
+        salt: [u8; 32],
+        review: Option<Review>,
+    ) -> Result<()> {
+        reveal_vote_handler(ctx, approved, salt, review)
+    }
+
+    // 16. Penalize Unrevealed Vote
+    pub fn penalize_unrevealed_vote(ctx: Context<PenalizeUnrevealedVote>) -> Result<()> {
+        penalize_unrevealed_vote_handler(ctx)
+    }
+
+    // 17. Add Evidence
+    pub fn add_evidence(
+        ctx: Context<AddEvidence>,
+        milestone_number: u8,
+        attachment: Attachment,
+    ) -> Result<()> {
+        add_evidence_handler(ctx, milestone_number, attachment)
+    }
+
+    // 18. Cancel Proposal
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        cancel_proposal_handler(ctx)
+    }
+
+    // 19. Close Milestone
+    pub fn close_milestone(ctx: Context<CloseMilestone>) -> Result<()> {
+        close_milestone_handler(ctx)
+    }
+
+    // 20. Close Vote
+    pub fn close_vote(ctx: Context<CloseVote>) -> Result<()> {
+        close_vote_handler(ctx)
+    }
+
+    // 21. Close Contributor
+    pub fn close_contributor(ctx: Context<CloseContributor>) -> Result<()> {
+        close_contributor_handler(ctx)
+    }
+
+    // 22. Close Accounts
+    pub fn close_accounts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseAccounts<'info>>,
+    ) -> Result<()> {
+        close_accounts_handler(ctx)
+    }
+
+    // 23. Challenge Milestone
+    pub fn challenge_milestone(ctx: Context<ChallengeMilestone>) -> Result<()> {
+        challenge_milestone_handler(ctx)
+    }
+
+    // 24. Resolve Challenge
+    pub fn resolve_challenge(ctx: Context<ResolveChallenge>) -> Result<()> {
+        resolve_challenge_handler(ctx)
+    }
+    // 25. Propose Authority
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        propose_authority_handler(ctx, new_authority)
+    }
+
+    // 26. Accept Authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        accept_authority_handler(ctx)
+    }
+    // 27. Fund Proposal For
+    pub fn fund_proposal_for(
+        ctx: Context<FundProposalFor>,
+        amount: u64,
+        memo: Option<String>,
+        allowance: Option<AllowlistProof>,
+    ) -> Result<()> {
+        fund_proposal_for_handler(ctx, amount, memo, allowance)
+    }
+
+    // 28. Create Pledge
+    pub fn create_pledge(
+        ctx: Context<CreatePledge>,
+        amount: u64,
+        installments: u16,
+        allowance: Option<AllowlistProof>,
+    ) -> Result<()> {
+        create_pledge_handler(ctx, amount, installments, allowance)
+    }
+
+    // 29. Execute Pledge
+    pub fn execute_pledge(ctx: Context<ExecutePledge>) -> Result<()> {
+        execute_pledge_handler(ctx)
+    }
+
+    // 30. Cancel Pledge
+    pub fn cancel_pledge(ctx: Context<CancelPledge>) -> Result<()> {
+        cancel_pledge_handler(ctx)
+    }
 }
@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::associated_token::{self, Create};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::ExtensionType, instruction::AuthorityType, state::Mint,
+};
+use anchor_spl::token_2022::{self, InitializeMint2, MintTo, SetAuthority};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    metadata_pointer_initialize, non_transferable_mint_initialize, token_metadata_initialize,
+    token_metadata_update_field, MetadataPointerInitialize, NonTransferableMintInitialize,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+};
+
+use crate::error::ErrorCode;
+use crate::{RECEIPT_AMOUNT_KEY, RECEIPT_PROPOSAL_KEY, RECEIPT_SYMBOL};
+
+// Soulbound proof-of-funding receipts.
+//
+// Every contributor gets one Token-2022 NFT per proposal, minted to their
+// associated account on their first contribution. The mint is a PDA at
+// `[b"receipt", contributor_account]` and is its own metadata account
+// (MetadataPointer + TokenMetadata), its own update authority, and
+// NonTransferable so the receipt can't leave the wallet. The metadata
+// carries the proposal address and the running total contributed.
+
+/// Longest decimal rendering of a `u64`, the amount field is funded for it
+/// up front so later updates never need more rent.
+const MAX_AMOUNT_DIGITS: usize = 20;
+
+pub struct ReceiptAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub token_account: &'a AccountInfo<'info>,
+    pub token_2022_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+fn metadata(
+    mint: &Pubkey,
+    proposal: &Pubkey,
+    title: &str,
+    uri: &str,
+    amount: String,
+) -> TokenMetadata {
+    TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(*mint),
+        mint: *mint,
+        name: title.to_string(),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri: uri.to_string(),
+        additional_metadata: vec![
+            (RECEIPT_PROPOSAL_KEY.to_string(), proposal.to_string()),
+            (RECEIPT_AMOUNT_KEY.to_string(), amount),
+        ],
+    }
+}
+
+/// Creates the receipt mint, mints the single token to `owner` and drops the
+/// mint authority so the supply stays at one.
+pub fn mint_receipt<'info>(
+    accounts: &ReceiptAccounts<'_, 'info>,
+    signer: &[&[&[u8]]],
+    proposal: &Pubkey,
+    title: &str,
+    uri: &str,
+    amount: u64,
+) -> Result<()> {
+    let mint = accounts.mint.key();
+    let program = accounts.token_2022_program;
+
+    // 1. Allocate the fixed size extensions, with rent for the metadata the
+    //    token program appends once the mint is initialized
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::NonTransferable,
+        ExtensionType::MetadataPointer,
+    ])?;
+    let full = metadata(&mint, proposal, title, uri, "0".repeat(MAX_AMOUNT_DIGITS));
+    let lamports = Rent::get()?.minimum_balance(space + full.tlv_size_of()?);
+    create_pda(accounts, signer, space, lamports)?;
+
+    // 2. Extensions go in before the mint itself is initialized
+    non_transferable_mint_initialize(CpiContext::new(
+        program.clone(),
+        NonTransferableMintInitialize {
+            token_program_id: program.clone(),
+            mint: accounts.mint.clone(),
+        },
+    ))?;
+    metadata_pointer_initialize(
+        CpiContext::new(
+            program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(mint),
+        Some(mint),
+    )?;
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            program.clone(),
+            InitializeMint2 {
+                mint: accounts.mint.clone(),
+            },
+        ),
+        0,
+        &mint,
+        None,
+    )?;
+
+    // 3. Metadata lives in the mint account
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            program.clone(),
+            TokenMetadataInitialize {
+                program_id: program.clone(),
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.mint.clone(),
+                mint: accounts.mint.clone(),
+                mint_authority: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        full.name,
+        full.symbol,
+        full.uri,
+    )?;
+    update_field(accounts, signer, RECEIPT_PROPOSAL_KEY, proposal.to_string())?;
+    update_amount(accounts, signer, amount)?;
+
+    // 4. Mint the one token and retire the mint authority
+    associated_token::create_idempotent(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        Create {
+            payer: accounts.payer.clone(),
+            associated_token: accounts.token_account.clone(),
+            authority: accounts.owner.clone(),
+            mint: accounts.mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: program.clone(),
+        },
+    ))?;
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            program.clone(),
+            MintTo {
+                mint: accounts.mint.clone(),
+                to: accounts.token_account.clone(),
+                authority: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        1,
+    )?;
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            program.clone(),
+            SetAuthority {
+                current_authority: accounts.mint.clone(),
+                account_or_mint: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    Ok(())
+}
+
+/// Writes the contributor's running total into the receipt's metadata.
+pub fn update_amount(
+    accounts: &ReceiptAccounts<'_, '_>,
+    signer: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    update_field(accounts, signer, RECEIPT_AMOUNT_KEY, amount.to_string())
+}
+
+fn update_field(
+    accounts: &ReceiptAccounts<'_, '_>,
+    signer: &[&[&[u8]]],
+    key: &str,
+    value: String,
+) -> Result<()> {
+    token_metadata_update_field(
+        CpiContext::new_with_signer(
+            accounts.token_2022_program.clone(),
+            TokenMetadataUpdateField {
+                program_id: accounts.token_2022_program.clone(),
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        Field::Key(key.to_string()),
+        value,
+    )
+}
+
+/// Creates the mint PDA owned by Token-2022. Lamports someone already sent to
+/// the address are kept, so a dusted address can't block the first
+/// contribution.
+fn create_pda(
+    accounts: &ReceiptAccounts<'_, '_>,
+    signer: &[&[&[u8]]],
+    space: usize,
+    lamports: u64,
+) -> Result<()> {
+    let system = accounts.system_program.clone();
+    let current = accounts.mint.lamports();
+
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system,
+                CreateAccount {
+                    from: accounts.payer.clone(),
+                    to: accounts.mint.clone(),
+                },
+                signer,
+            ),
+            lamports,
+            space as u64,
+            &token_2022::ID,
+        );
+    }
+
+    require!(accounts.mint.data_is_empty(), ErrorCode::InvalidReceiptMint);
+    if lamports > current {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                Transfer {
+                    from: accounts.payer.clone(),
+                    to: accounts.mint.clone(),
+                },
+            ),
+            lamports - current,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.clone(),
+            Allocate {
+                account_to_allocate: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system,
+            Assign {
+                account_to_assign: accounts.mint.clone(),
+            },
+            signer,
+        ),
+        &token_2022::ID,
+    )
+}
@@ -3,10 +3,24 @@
     #[account]
     #[derive(InitSpace)]
     pub struct Contributor {
+        pub version: u8,      //Layout version, see `Versioned`          //1 byte
         pub wallet: Pubkey,   //Wallet key of the contributor             //32 bytes
         pub proposal: Pubkey, //The proposal/research he/she voted for    //32 bytes
         pub amount: u64,      //Amount the contributor contributed        //8 bytes
         pub timestamp: i64,   //When was the contribution made            //8 bytes
         pub shares: u64,      //How much proportion the contributor owns  //8 bytes
         pub bump: u8,
+        pub payer: Pubkey,    //Paid the rent, default before v2          //32 bytes
+        pub sponsor: Option<Pubkey>, //First wallet to fund on its behalf (v3) //33 bytes
+    }
+
+    impl Contributor {
+        /// Wallet the rent goes back to, the contributor itself before v2.
+        pub fn rent_payer(&self) -> Pubkey {
+            if self.payer == Pubkey::default() {
+                self.wallet
+            } else {
+                self.payer
+            }
+        }
     }
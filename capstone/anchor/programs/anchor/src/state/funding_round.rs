@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_ROUND_PROPOSALS;
+
+#[account]
+#[derive(InitSpace)]
+pub struct FundingRound {
+    pub version: u8,              // Layout version, see `Versioned`
+    pub sponsor: Pubkey,          // Grant provider who deposited the pool
+    pub round_id: u64,            // Sponsor's own numbering, part of the seeds
+    pub mint: Pubkey,             // Token the pool and the proposals raise in
+    pub vault: Pubkey,            // Holds the matching pool until distribution
+    pub matching_pool: u64,
+
+    // Contributions through `fund_proposal` count inside [starts_at, ends_at)
+    pub starts_at: i64,
+    pub ends_at: i64,
+
+    #[max_len(MAX_ROUND_PROPOSALS)]
+    pub entries: Vec<RoundEntry>, // Eligible proposals and their tallies
+
+    pub distributed: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct RoundEntry {
+    pub proposal: Pubkey,
+    pub contributors: u32,  // Unique contributors during the round
+    pub contributed: u64,   // Sum of their contributions
+    pub sqrt_sum: u128,     // Sum of the square roots of each contributor's total
+    pub matched: u64,       // Share of the pool paid out
+}
+
+/// One contributor's running total towards a proposal within a round.
+#[account]
+#[derive(InitSpace)]
+pub struct RoundContribution {
+    pub version: u8,
+    pub round: Pubkey,
+    pub proposal: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl FundingRound {
+    pub fn entry_mut(&mut self, proposal: &Pubkey) -> Option<&mut RoundEntry> {
+        self.entries.iter_mut().find(|entry| entry.proposal == *proposal)
+    }
+
+    pub fn is_open(&self, now: i64) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// Splits `pool` across the entries by the quadratic-funding formula.
+    ///
+    /// Each proposal's ideal match is `(Σ √cᵢ)² - Σ cᵢ`, the amount that makes
+    /// many small contributions outweigh a few large ones. The pool is
+    /// divided pro rata to those ideal matches, entries with `eligible` false
+    /// get nothing. Rounding dust is left unallocated.
+    pub fn matching_shares(pool: u64, entries: &[RoundEntry], eligible: &[bool]) -> Vec<u64> {
+        let weights: Vec<u128> = entries
+            .iter()
+            .zip(eligible)
+            .map(|(entry, eligible)| {
+                if !eligible {
+                    return 0;
+                }
+                entry
+                    .sqrt_sum
+                    .saturating_mul(entry.sqrt_sum)
+                    .saturating_sub(entry.contributed as u128)
+            })
+            .collect();
+        let total: u128 = weights.iter().fold(0u128, |sum, w| sum.saturating_add(*w));
+
+        weights
+            .iter()
+            .map(|weight| {
+                if total == 0 {
+                    return 0;
+                }
+                // Scale the weights down together if the product would overflow
+                let shift = (128 - total.leading_zeros()).saturating_sub(64);
+                ((pool as u128 * (weight >> shift)) / (total >> shift).max(1)) as u64
+            })
+            .collect()
+    }
+}
+
+/// Integer square root, rounded down.
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = 1u128 << (128 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
@@ -0,0 +1,220 @@
+//! Layouts that changed a field in place rather than appending one.
+//!
+//! `migrate_account` decodes accounts still on these layouts and converts
+//! them to the current one, see `Versioned::upgrade`. Older versions of the
+//! same accounts decode as these too, with their appended fields zeroed.
+//! The `V0` layouts are the accounts from before the version header, they
+//! are decoded after the header `migrate_account` inserts.
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    ContentId, ContributionLimits, Milestone, MilestoneStatus, Proposal, ProposalStatus,
+    ReviewTotals, TeamMember, Versioned, TEAM_SHARE_TOTAL_BPS,
+};
+
+/// `Proposal` before the version header and the team roster.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalV0 {
+    pub title: String,
+    pub abstract_text: String,
+    pub researcher: Pubkey,
+    pub contributors_count: u32,
+    pub initial_security_deposit: u64,
+    pub current_security_deposit: u64,
+    pub amount_asked: u64,
+    pub amount_raised: u64,
+    pub funds_escrow: Pubkey,
+    pub total_milestones: u8,
+    pub current_milestone: u8,
+    pub ipfs_hash: String,
+    pub ip_nft_mint: Option<Pubkey>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub status: ProposalStatus,
+    pub penalty_counter: u8,
+}
+
+// The researcher gets the full split, as on a new proposal
+impl From<ProposalV0> for Proposal {
+    fn from(old: ProposalV0) -> Self {
+        Proposal::from(ProposalV2 {
+            version: Proposal::VERSION,
+            title: old.title,
+            abstract_text: old.abstract_text,
+            researcher: old.researcher,
+            contributors_count: old.contributors_count,
+            initial_security_deposit: old.initial_security_deposit,
+            current_security_deposit: old.current_security_deposit,
+            amount_asked: old.amount_asked,
+            amount_raised: old.amount_raised,
+            funds_escrow: old.funds_escrow,
+            total_milestones: old.total_milestones,
+            current_milestone: old.current_milestone,
+            ipfs_hash: old.ipfs_hash,
+            ip_nft_mint: old.ip_nft_mint,
+            created_at: old.created_at,
+            updated_at: old.updated_at,
+            status: old.status,
+            penalty_counter: old.penalty_counter,
+            team: vec![TeamMember {
+                wallet: old.researcher,
+                share_bps: TEAM_SHARE_TOTAL_BPS,
+            }],
+            matched_amount: 0,
+            matched_released: 0,
+        })
+    }
+}
+
+/// `Proposal` up to v2, when `ipfs_hash` was free text.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalV2 {
+    pub version: u8,
+    pub title: String,
+    pub abstract_text: String,
+    pub researcher: Pubkey,
+    pub contributors_count: u32,
+    pub initial_security_deposit: u64,
+    pub current_security_deposit: u64,
+    pub amount_asked: u64,
+    pub amount_raised: u64,
+    pub funds_escrow: Pubkey,
+    pub total_milestones: u8,
+    pub current_milestone: u8,
+    pub ipfs_hash: String,
+    pub ip_nft_mint: Option<Pubkey>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub status: ProposalStatus,
+    pub penalty_counter: u8,
+    pub team: Vec<TeamMember>,
+    pub matched_amount: u64,
+    pub matched_released: u64,
+}
+
+impl From<ProposalV2> for Proposal {
+    fn from(old: ProposalV2) -> Self {
+        Self {
+            version: old.version,
+            title: old.title,
+            abstract_text: old.abstract_text,
+            researcher: old.researcher,
+            contributors_count: old.contributors_count,
+            initial_security_deposit: old.initial_security_deposit,
+            current_security_deposit: old.current_security_deposit,
+            amount_asked: old.amount_asked,
+            amount_raised: old.amount_raised,
+            funds_escrow: old.funds_escrow,
+            total_milestones: old.total_milestones,
+            current_milestone: old.current_milestone,
+            ipfs_hash: ContentId::parse(&old.ipfs_hash).ok(),
+            ip_nft_mint: old.ip_nft_mint,
+            created_at: old.created_at,
+            updated_at: old.updated_at,
+            status: old.status,
+            penalty_counter: old.penalty_counter,
+            team: old.team,
+            matched_amount: old.matched_amount,
+            matched_released: old.matched_released,
+            challenge_period: 0,
+            authority: old.researcher,
+            pending_authority: None,
+            allowlist_root: None,
+            contribution_limits: ContributionLimits::default(),
+            stretch_goals: Vec::new(),
+            stretch_goals_unlocked: 0,
+            open_accounts: 0,
+            milestones_released: None,
+        }
+    }
+}
+
+/// `Milestone` before the version header and `rejection_votes`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MilestoneV0 {
+    pub proposal: Pubkey,
+    pub milestone_number: u8,
+    pub evidence_hash: String,
+    pub validation_votes: u32,
+    pub total_validators: u32,
+    pub funds_released: bool,
+    pub deadline: i64,
+    pub status: MilestoneStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<MilestoneV0> for Milestone {
+    fn from(old: MilestoneV0) -> Self {
+        Milestone::from(MilestoneV3 {
+            version: Milestone::VERSION,
+            proposal: old.proposal,
+            milestone_number: old.milestone_number,
+            evidence_hash: old.evidence_hash,
+            validation_votes: old.validation_votes,
+            rejection_votes: 0,
+            total_validators: old.total_validators,
+            funds_released: old.funds_released,
+            deadline: old.deadline,
+            status: old.status,
+            created_at: old.created_at,
+            updated_at: old.updated_at,
+            commit_deadline: 0,
+            commits: 0,
+            reveals: 0,
+            evidence_version: 0,
+        })
+    }
+}
+
+/// `Milestone` up to v3, when `evidence_hash` was free text.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MilestoneV3 {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub milestone_number: u8,
+    pub evidence_hash: String,
+    pub validation_votes: u32,
+    pub rejection_votes: u32,
+    pub total_validators: u32,
+    pub funds_released: bool,
+    pub deadline: i64,
+    pub status: MilestoneStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub commit_deadline: i64,
+    pub commits: u32,
+    pub reveals: u32,
+    pub evidence_version: u16,
+}
+
+impl From<MilestoneV3> for Milestone {
+    fn from(old: MilestoneV3) -> Self {
+        Self {
+            version: old.version,
+            proposal: old.proposal,
+            milestone_number: old.milestone_number,
+            evidence_hash: ContentId::parse(&old.evidence_hash).ok(),
+            validation_votes: old.validation_votes,
+            rejection_votes: old.rejection_votes,
+            total_validators: old.total_validators,
+            funds_released: old.funds_released,
+            deadline: old.deadline,
+            status: old.status,
+            created_at: old.created_at,
+            updated_at: old.updated_at,
+            commit_deadline: old.commit_deadline,
+            commits: old.commits,
+            reveals: old.reveals,
+            evidence_version: old.evidence_version,
+            validated_at: 0,
+            challenger: None,
+            challenge_bond: 0,
+            reviews: 0,
+            review_totals: ReviewTotals::default(),
+            open_votes: 0,
+            payer: Pubkey::default(),
+        }
+    }
+}
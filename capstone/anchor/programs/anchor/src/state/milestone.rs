@@ -1,18 +1,22 @@
 use anchor_lang::prelude::*;
 
+use crate::error::ErrorCode;
+use crate::{ContentId, ReviewScores};
+
 
 #[account]
 #[derive(InitSpace)]
 pub struct Milestone {
+    pub version: u8,              // Layout version, see `Versioned`
     pub proposal: Pubkey,         // Associated proposal
     pub milestone_number: u8,     // 1-based index
     
-    // IPFS/Arweave Storage
-    #[max_len(128)]
-    pub evidence_hash: String,    // Researcher's evidence
+    // IPFS/Arweave Storage (v4), `None` only for legacy hashes that never parsed
+    pub evidence_hash: Option<ContentId>, // Researcher's evidence
     
     // Validation
     pub validation_votes: u32,    // Successful votes 
+    pub rejection_votes: u32,     // Votes against
     pub total_validators: u32,
     pub funds_released: bool,
     pub deadline: i64,           //Considered as 14 day default throughtout the program
@@ -21,12 +25,91 @@ pub struct Milestone {
     pub status: MilestoneStatus,
     pub created_at: i64,
     pub updated_at: i64,
+
+    // Commit-reveal voting (v2), `commit_deadline` is 0 for open voting
+    pub commit_deadline: i64,     // Commits close and reveals open, until `deadline`
+    pub commits: u32,             // Validators who committed a vote
+    pub reveals: u32,             // Commitments opened, only these are tallied
+
+    // Evidence set under review (v3), 0 when no attachments were added
+    pub evidence_version: u16,
+
+    // Challenge window (v5), funds wait `Proposal.challenge_period` after validation
+    pub validated_at: i64,
+    pub challenger: Option<Pubkey>, // Contributor who disputed the verdict, once per milestone
+    pub challenge_bond: u64,        // Held in the challenge vault until resolved
+
+    // Validator reviews (v6), averages are `review_totals` over `reviews`
+    pub reviews: u32,
+    pub review_totals: ReviewTotals,
+
+    // Vote accounts still open (v7), they need the milestone to close
+    pub open_votes: u32,
+
+    // Paid the rent (v8), it goes back to them on close
+    pub payer: Pubkey,
+}
+
+impl Milestone {
+    /// Wallet the rent goes back to, the proposal's `authority` before v8.
+    pub fn rent_payer(&self, authority: &Pubkey) -> Pubkey {
+        if self.payer == Pubkey::default() {
+            *authority
+        } else {
+            self.payer
+        }
+    }
+}
+
+/// Sums of the scores of every review on a milestone.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct ReviewTotals {
+    pub reproducibility: u32,
+    pub completeness: u32,
+    pub quality: u32,
+}
+
+impl ReviewTotals {
+    pub fn add(&mut self, scores: &ReviewScores) -> Result<()> {
+        self.reproducibility = self
+            .reproducibility
+            .checked_add(scores.reproducibility as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.completeness = self
+            .completeness
+            .checked_add(scores.completeness as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.quality = self
+            .quality
+            .checked_add(scores.quality as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, scores: &ReviewScores) -> Result<()> {
+        self.reproducibility = self
+            .reproducibility
+            .checked_sub(scores.reproducibility as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.completeness = self
+            .completeness
+            .checked_sub(scores.completeness as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.quality = self
+            .quality
+            .checked_sub(scores.quality as u32)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
 pub enum MilestoneStatus {
     Pending,
     Active,
     Validated,
     Failed,
+    Disputed,
 }
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{ContentId, MAX_EVIDENCE_ATTACHMENTS};
+
+/// Attachments backing a milestone, appended before it is submitted.
+#[account]
+#[derive(InitSpace)]
+pub struct MilestoneEvidence {
+    pub version: u8, // Layout version, see `Versioned`
+    pub proposal: Pubkey,
+    pub milestone_number: u8, // Milestone the evidence is for, submitted or not
+    pub set_version: u16,     // Bumped on every append, votes record the one they saw
+
+    #[max_len(MAX_EVIDENCE_ATTACHMENTS)]
+    pub attachments: Vec<Attachment>,
+
+    pub bump: u8,
+    pub payer: Pubkey, // Paid the rent (v2)
+}
+
+impl MilestoneEvidence {
+    /// Wallet the rent goes back to, the proposal's `authority` before v2.
+    pub fn rent_payer(&self, authority: &Pubkey) -> Pubkey {
+        if self.payer == Pubkey::default() {
+            *authority
+        } else {
+            self.payer
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct Attachment {
+    pub content_type: ContentType,
+    pub cid: ContentId,   // IPFS CID or Arweave transaction id
+    pub size: u64,        // Bytes
+    pub sha256: [u8; 32], // Digest of the content itself
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ContentType {
+    Report,
+    Dataset,
+    Code,
+    Image,
+    Other,
+}
@@ -9,3 +9,17 @@ pub use contributor::*;
 
 pub mod vote;
 pub use vote::*;
+
+pub mod versioned;
+pub use versioned::*;
+
+pub mod funding_round;
+pub use funding_round::*;
+
+pub mod milestone_evidence;
+pub use milestone_evidence::*;
+
+pub mod pledge;
+pub use pledge::*;
+
+pub mod legacy;
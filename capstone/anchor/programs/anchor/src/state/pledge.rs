@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+/// Recurring contribution. The pledge PDA is approved as delegate on
+/// `token_account` and `execute_pledge` pulls one installment per interval.
+#[account]
+#[derive(InitSpace)]
+pub struct Pledge {
+    pub version: u8,         // Layout version, see `Versioned`
+    pub contributor: Pubkey, // Wallet credited with every installment
+    pub proposal: Pubkey,
+    pub token_account: Pubkey, // Source of the installments
+    pub amount: u64,           // Per installment
+    pub installments: u16,     // Total, the first one is paid on creation
+    pub installments_paid: u16,
+    pub next_due: i64,
+    pub created_at: i64,
+    pub bump: u8,
+    // Why the pledge ended while its allowance kept it open (v2)
+    pub ended: Option<PledgeEnd>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PledgeEnd {
+    Completed,
+    ProposalInactive, // The proposal left `Active` before the last installment
+    Cancelled,
+    GoalReached, // A hard-capped proposal raised its full amount
+}
@@ -1,8 +1,13 @@
 use anchor_lang::prelude::*;
 
+use crate::{ContentId, MAX_STRETCH_GOALS, MAX_TEAM_MEMBERS};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Proposal {
+    //Layout version, see `Versioned`
+    pub version: u8, //1 byte
+
     //Metadata
     #[max_len(50)]
     pub title: String, //32 bytes
@@ -22,9 +27,8 @@ pub struct Proposal {
     pub total_milestones: u8,  //1 byte
     pub current_milestone: u8, //1 byte
 
-    //Storage
-    #[max_len(128)]
-    pub ipfs_hash: String, //128 bytes
+    //Storage, `None` only for legacy hashes that never parsed (v3)
+    pub ipfs_hash: Option<ContentId>, //50 bytes
     pub ip_nft_mint: Option<Pubkey>, //33 bytes(1+32)
 
     //Timestamps
@@ -36,9 +40,115 @@ pub struct Proposal {
 
     //Penalty counter
     pub penalty_counter: u8, //Penalize researcher
+
+    //Team roster, payout splits in basis points
+    #[max_len(MAX_TEAM_MEMBERS)]
+    pub team: Vec<TeamMember>, //4 + 34 bytes per member
+
+    //Quadratic-funding matches received, paid out with the milestones (v2)
+    pub matched_amount: u64,   //8 bytes
+    pub matched_released: u64, //8 bytes
+
+    //Seconds contributors can challenge a validated milestone for (v4)
+    pub challenge_period: i64, //8 bytes
+
+    //Signs for the proposal, `researcher` only seeds its address (v5)
+    pub authority: Pubkey,                 //32 bytes
+    pub pending_authority: Option<Pubkey>, //33 bytes(1+32)
+
+    //Merkle root of the wallets allowed to contribute, see `allowlist` (v6)
+    pub allowlist_root: Option<[u8; 32]>, //33 bytes(1+32)
+
+    //Bounds on what each contributor and the proposal raise (v7)
+    pub contribution_limits: ContributionLimits, //17 bytes
+
+    //Milestones funded by raising past `amount_asked`, in threshold order (v8)
+    #[max_len(MAX_STRETCH_GOALS)]
+    pub stretch_goals: Vec<StretchGoal>, //4 + 16 bytes per goal
+    pub stretch_goals_unlocked: u8,      //Leading goals whose threshold was reached
+
+    //Milestone, evidence and contributor accounts still open, they need the
+    //proposal to close so it goes last (v9)
+    pub open_accounts: u32, //4 bytes
+
+    //Milestones paid out so far, `None` when migrated from before v10
+    pub milestones_released: Option<u8>, //2 bytes(1+1)
+}
+
+impl Proposal {
+    /// What a hard-capped proposal can still raise, `None` without a cap.
+    pub fn remaining_to_cap(&self) -> Option<u64> {
+        self.contribution_limits
+            .hard_cap
+            .then(|| self.amount_asked.saturating_sub(self.amount_raised))
+    }
+
+    /// Milestones the researcher has to deliver, unlocked stretch goals
+    /// included. `create_proposal` keeps the total within a u8.
+    pub fn milestone_count(&self) -> u8 {
+        self.total_milestones.saturating_add(self.stretch_goals_unlocked)
+    }
+
+    /// Whether the next release pays the last milestone still owed. Releases
+    /// can come out of order, e.g. a later milestone validated first.
+    /// Proposals migrated from before v10 never counted them and go by the
+    /// last milestone submitted instead.
+    pub fn is_last_release(&self) -> bool {
+        match self.milestones_released {
+            Some(released) => released.saturating_add(1) == self.milestone_count(),
+            None => self.current_milestone == self.milestone_count(),
+        }
+    }
+
+    /// Stretch goal funding `milestone_number`, `None` for the base milestones.
+    pub fn stretch_goal(&self, milestone_number: u8) -> Option<&StretchGoal> {
+        let index = milestone_number.checked_sub(self.total_milestones.checked_add(1)?)?;
+        self.stretch_goals.get(index as usize)
+    }
+
+    /// What contributors can still withdraw from an escrow holding
+    /// `escrow_amount`: all of it once the proposal failed, whatever a
+    /// completed one didn't pay out, e.g. overfunding or locked stretch goals.
+    pub fn refundable(&self, escrow_amount: u64) -> u64 {
+        match self.status {
+            ProposalStatus::Failed => escrow_amount,
+            ProposalStatus::Completed => {
+                escrow_amount.saturating_sub(self.current_security_deposit)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn is_team_member(&self, wallet: &Pubkey) -> bool {
+        self.team.iter().any(|member| member.wallet == *wallet)
+    }
+}
+
+/// Zero leaves a bound unset.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct ContributionLimits {
+    pub min: u64,       //Smallest total a contributor can hold
+    pub max: u64,       //Largest total a contributor can hold
+    pub hard_cap: bool, //Trim contributions to what `amount_asked` still needs
+}
+
+/// An optional milestone that joins the plan once `amount_raised` reaches
+/// `threshold`, paying `budget` on release.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct StretchGoal {
+    pub threshold: u64, //8 bytes
+    pub budget: u64,    //8 bytes
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct TeamMember {
+    pub wallet: Pubkey,  //32 bytes
+    pub share_bps: u16, //Share of every release, out of 10_000
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
 pub enum ProposalStatus {
     Draft,
     Pending,
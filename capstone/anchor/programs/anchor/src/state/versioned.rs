@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::legacy::{MilestoneV0, MilestoneV3, ProposalV0, ProposalV2};
+use crate::{
+    ContributionLimits, Contributor, FundingRound, Milestone, MilestoneEvidence, Pledge, Proposal,
+    ReviewTotals, RoundContribution, Vote,
+};
+
+/// Accounts carry their layout version in the byte after the discriminator.
+///
+/// Accounts created before the header existed are recognised by their size.
+/// Most layout changes only append fields, `migrate_account` grows the
+/// account, resets the appended fields with `reset_fields_since` and bumps
+/// `VERSION`. Appended fields are reset explicitly because variable length
+/// fields before them can leave stale bytes behind, e.g. a shrunk `Vec`.
+/// Layouts that changed a field in place override `upgrade` and decode the
+/// old layout from `crate::state::legacy`.
+pub trait Versioned: Discriminator + AccountSerialize + AccountDeserialize {
+    /// Version written by this build of the program.
+    const VERSION: u8;
+
+    /// Allocated size of the current layout, discriminator included.
+    const SPACE: usize;
+
+    /// Allocated size of accounts created before the version header.
+    const UNVERSIONED_SPACE: usize;
+
+    /// Sets the fields added after `version` to their defaults.
+    fn reset_fields_since(&mut self, _version: u8) {}
+
+    /// Decodes an account written at `version`, padded with zeros to at
+    /// least `SPACE` and with the header in place.
+    fn upgrade(data: &[u8], version: u8) -> Result<Self> {
+        let mut account = Self::try_deserialize(&mut &data[..])?;
+        account.reset_fields_since(version);
+        Ok(account)
+    }
+}
+
+impl Versioned for Proposal {
+    const VERSION: u8 = 10;
+    const SPACE: usize = 8 + Proposal::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 601;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.matched_amount = 0;
+            self.matched_released = 0;
+        }
+        if version < 4 {
+            self.challenge_period = 0;
+        }
+        if version < 5 {
+            self.authority = self.researcher;
+            self.pending_authority = None;
+        }
+        if version < 6 {
+            self.allowlist_root = None;
+        }
+        if version < 7 {
+            self.contribution_limits = ContributionLimits::default();
+        }
+        if version < 8 {
+            self.stretch_goals = Vec::new();
+            self.stretch_goals_unlocked = 0;
+        }
+        // Children opened before v9 weren't counted
+        if version < 9 {
+            self.open_accounts = 0;
+        }
+        // Releases before v10 weren't counted either
+        if version < 10 {
+            self.milestones_released = None;
+        }
+    }
+
+    // v3 stores `ipfs_hash` as a `ContentId`
+    fn upgrade(data: &[u8], version: u8) -> Result<Self> {
+        let mut account = match version {
+            0 => Proposal::from(ProposalV0::deserialize(&mut &data[9..])?),
+            1 | 2 => Proposal::from(ProposalV2::deserialize(&mut &data[8..])?),
+            _ => Proposal::try_deserialize(&mut &data[..])?,
+        };
+        account.reset_fields_since(version);
+        Ok(account)
+    }
+}
+
+impl Versioned for Milestone {
+    const VERSION: u8 = 8;
+    const SPACE: usize = 8 + Milestone::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 207;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.commit_deadline = 0;
+            self.commits = 0;
+            self.reveals = 0;
+        }
+        if version < 3 {
+            self.evidence_version = 0;
+        }
+        if version < 5 {
+            self.validated_at = 0;
+            self.challenger = None;
+            self.challenge_bond = 0;
+        }
+        if version < 6 {
+            self.reviews = 0;
+            self.review_totals = ReviewTotals::default();
+        }
+        if version < 7 {
+            self.open_votes = 0;
+        }
+        if version < 8 {
+            self.payer = Pubkey::default();
+        }
+    }
+
+    // v4 stores `evidence_hash` as a `ContentId`
+    fn upgrade(data: &[u8], version: u8) -> Result<Self> {
+        let mut account = match version {
+            0 => Milestone::from(MilestoneV0::deserialize(&mut &data[9..])?),
+            1..=3 => Milestone::from(MilestoneV3::deserialize(&mut &data[8..])?),
+            _ => Milestone::try_deserialize(&mut &data[..])?,
+        };
+        account.reset_fields_since(version);
+        Ok(account)
+    }
+}
+
+impl Versioned for Contributor {
+    const VERSION: u8 = 3;
+    const SPACE: usize = 8 + Contributor::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 97;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.payer = Pubkey::default();
+        }
+        if version < 3 {
+            self.sponsor = None;
+        }
+    }
+}
+
+impl Versioned for Vote {
+    const VERSION: u8 = 4;
+    const SPACE: usize = 8 + Vote::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 81;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.commitment = [0; 32];
+            self.revealed = false;
+        }
+        if version < 3 {
+            self.evidence_version = 0;
+        }
+        if version < 4 {
+            self.review = None;
+        }
+    }
+}
+
+// Funding rounds were introduced with the header, `UNVERSIONED_SPACE` is 0 so
+// no real account ever matches it.
+
+impl Versioned for FundingRound {
+    const VERSION: u8 = 1;
+    const SPACE: usize = 8 + FundingRound::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 0;
+}
+
+impl Versioned for RoundContribution {
+    const VERSION: u8 = 1;
+    const SPACE: usize = 8 + RoundContribution::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 0;
+}
+
+impl Versioned for MilestoneEvidence {
+    const VERSION: u8 = 2;
+    const SPACE: usize = 8 + MilestoneEvidence::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 0;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.payer = Pubkey::default();
+        }
+    }
+}
+
+impl Versioned for Pledge {
+    const VERSION: u8 = 2;
+    const SPACE: usize = 8 + Pledge::INIT_SPACE;
+    const UNVERSIONED_SPACE: usize = 0;
+
+    fn reset_fields_since(&mut self, version: u8) {
+        if version < 2 {
+            self.ended = None;
+        }
+    }
+}
@@ -1,10 +1,61 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::ContentId;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Vote {
+    pub version: u8,       // 1 byte, layout version
     pub validator: Pubkey, // 32 bytes
     pub milestone: Pubkey, // 32 bytes
     pub voted_at: i64,     // 8 bytes
     pub approved: bool,    // 1 byte
+
+    // Commit-reveal voting (v2), zero for open votes
+    pub commitment: [u8; 32], // 32 bytes, see `Vote::commitment`
+    pub revealed: bool,       // 1 byte
+
+    // Evidence set version the validator reviewed (v3)
+    pub evidence_version: u16, // 2 bytes
+
+    // Structured review behind the verdict (v4), `None` when none was given
+    pub review: Option<Review>, // 1 + 52 bytes
+}
+
+/// Scores from 0 to `MAX_REVIEW_SCORE`.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct ReviewScores {
+    pub reproducibility: u8,
+    pub completeness: u8,
+    pub quality: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct Review {
+    pub scores: ReviewScores,
+    pub rationale: ContentId, // Written reasoning, on IPFS or Arweave
+}
+
+impl Vote {
+    /// Hash a validator commits to before revealing `approved` and `salt`.
+    ///
+    /// The milestone and validator are part of the preimage, so copying
+    /// someone else's commitment doesn't let a validator reveal their vote.
+    pub fn commitment(
+        milestone: &Pubkey,
+        validator: &Pubkey,
+        approved: bool,
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        hashv(&[
+            milestone.as_ref(),
+            validator.as_ref(),
+            &[approved as u8],
+            salt,
+        ])
+        .to_bytes()
+    }
 }
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+use crate::{MilestoneStatus, ProposalStatus};
+
+// Legal status transitions for proposals and milestones. Handlers never
+// assign a status directly, they go through `transition`.
+//
+// Proposal:  Draft -> Pending -> Active -> Completed
+//            Draft -> Active,    Pending | Active -> Failed
+// Milestone: Pending -> Active -> Validated <-> Disputed
+//                       Active -> Failed,       Disputed -> Failed
+//
+// Building with the `skip-status-checks` feature turns every check here into
+// a no-op, so tests can drive accounts through states out of order.
+
+pub trait Status: Clone + PartialEq + std::fmt::Debug {
+    fn can_transition_to(&self, next: &Self) -> bool;
+
+    /// Error raised when `can_transition_to` refuses a transition.
+    fn illegal_transition() -> ErrorCode;
+}
+
+impl Status for ProposalStatus {
+    fn can_transition_to(&self, next: &Self) -> bool {
+        use ProposalStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Pending)
+                | (Draft, Active)
+                | (Pending, Active)
+                | (Pending, Failed)
+                | (Active, Completed)
+                | (Active, Failed)
+        )
+    }
+
+    fn illegal_transition() -> ErrorCode {
+        ErrorCode::IllegalProposalTransition
+    }
+}
+
+impl Status for MilestoneStatus {
+    fn can_transition_to(&self, next: &Self) -> bool {
+        use MilestoneStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Active)
+                | (Active, Validated)
+                | (Active, Failed)
+                | (Validated, Disputed)
+                | (Disputed, Validated)
+                | (Disputed, Failed)
+        )
+    }
+
+    fn illegal_transition() -> ErrorCode {
+        ErrorCode::IllegalMilestoneTransition
+    }
+}
+
+/// Moves `status` to `next`, failing if the state machine doesn't allow it.
+pub fn transition<S: Status>(status: &mut S, next: S) -> Result<()> {
+    if !cfg!(feature = "skip-status-checks") && !status.can_transition_to(&next) {
+        msg!("Illegal transition {:?} -> {:?}", status, next);
+        return Err(S::illegal_transition().into());
+    }
+    *status = next;
+    Ok(())
+}
+
+/// Fails with `error` unless `status` is one of `allowed`.
+pub fn require_status<S: Status>(status: &S, allowed: &[S], error: ErrorCode) -> Result<()> {
+    if !cfg!(feature = "skip-status-checks") && !allowed.contains(status) {
+        msg!("Status {:?} not in {:?}", status, allowed);
+        return Err(error.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,833 @@
+//! LiteSVM harness shared by the integration tests.
+//!
+//! The program is loaded from `target/deploy/anchor.so`, so run `anchor build`
+//! first. Metaplex Token Metadata is loaded from the checked-in `metadata.so`
+//! and SPL Token ships with LiteSVM, so nothing touches the network. The
+//! tests are ignored by default, run them with `cargo test -- --ignored`
+//! after the build, as the `litesvm` CI job does.
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use std::path::PathBuf;
+
+use anchor::{
+    AllowlistProof, Attachment, ContentId, ContentType, ContributionLimits, Contributor,
+    FundingRound, Milestone, MilestoneEvidence, MilestoneStatus, Proposal, Review, StretchGoal,
+    Vote,
+};
+use anchor_lang::solana_program::{
+    clock::Clock, hash::hashv, instruction::Instruction, program_pack::Pack,
+};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AccountSerialize};
+use anchor_spl::token::spl_token;
+use litesvm::{
+    types::{FailedTransactionMetadata, TransactionMetadata},
+    LiteSVM,
+};
+use researchchain_client::{instructions, instructions::Closable, pda};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const DAY: i64 = 86_400;
+
+/// Unix time the tests start at.
+pub const GENESIS: i64 = 1_700_000_000;
+
+/// Tokens minted to every wallet created by [`Harness::wallet`].
+pub const STARTING_BALANCE: u64 = 1_000_000;
+
+/// Raw CIDv1 of `label`, distinct labels give distinct identifiers.
+pub fn cid(label: &str) -> ContentId {
+    ContentId::IpfsV1 {
+        codec: 0x55,
+        hash: 0x12,
+        digest: hashv(&[label.as_bytes()]).to_bytes(),
+    }
+}
+
+pub type TxResult = Result<TransactionMetadata, FailedTransactionMetadata>;
+
+pub struct Harness {
+    pub svm: LiteSVM,
+    pub mint: Pubkey,
+    mint_authority: Keypair,
+}
+
+fn program_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+impl Harness {
+    /// Panics when the program has not been built yet. Tests using the
+    /// harness are ignored by default, run them with
+    /// `cargo test -- --ignored` after `anchor build`.
+    pub fn new() -> Self {
+        let program = program_path("../../target/deploy/anchor.so");
+        assert!(
+            program.exists(),
+            "{} is missing, run `anchor build`",
+            program.display()
+        );
+
+        let mut svm = LiteSVM::new();
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = GENESIS;
+        svm.set_sysvar(&clock);
+        svm.add_program_from_file(anchor::ID, &program)
+            .expect("loading the program");
+        svm.add_program_from_file(mpl_token_metadata::ID, program_path("../../metadata.so"))
+            .expect("loading metaplex token metadata");
+
+        let mint_authority = Keypair::new();
+        svm.airdrop(&mint_authority.pubkey(), 10_000_000_000)
+            .unwrap();
+
+        let mint = Keypair::new();
+        let rent = svm.minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN);
+        let ixs = [
+            system_instruction::create_account(
+                &mint_authority.pubkey(),
+                &mint.pubkey(),
+                rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &mint_authority.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ];
+
+        let mut harness = Self {
+            svm,
+            mint: mint.pubkey(),
+            mint_authority,
+        };
+        let payer = harness.mint_authority.insecure_clone();
+        harness
+            .send(&payer, &ixs, &[&mint])
+            .expect("creating the mint");
+        harness
+    }
+
+    /// Funds a new wallet with SOL and [`STARTING_BALANCE`] tokens in its
+    /// associated token account.
+    pub fn wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        self.svm.airdrop(&wallet.pubkey(), 10_000_000_000).unwrap();
+
+        let owner = wallet.pubkey();
+        let ixs = [
+            anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+                &owner,
+                &owner,
+                &self.mint,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &self.mint,
+                &self.token_account(&owner),
+                &self.mint_authority.pubkey(),
+                &[],
+                STARTING_BALANCE,
+            )
+            .unwrap(),
+        ];
+        let authority = self.mint_authority.insecure_clone();
+        self.send(&wallet, &ixs, &[&authority])
+            .expect("funding the wallet");
+        wallet
+    }
+
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        anchor_spl::associated_token::get_associated_token_address(owner, &self.mint)
+    }
+
+    pub fn send(&mut self, payer: &Keypair, ixs: &[Instruction], extra: &[&Keypair]) -> TxResult {
+        let mut signers = vec![payer];
+        signers.extend_from_slice(extra);
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&payer.pubkey()),
+            &signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        // Identical transactions would otherwise be rejected as already processed
+        self.svm.expire_blockhash();
+        result
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    pub fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        clock.slot += 1;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn balance(&self, token_account: &Pubkey) -> u64 {
+        let account = self.svm.get_account(token_account).expect("token account");
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    /// The token account's delegate and the amount it may still move.
+    pub fn delegate(&self, token_account: &Pubkey) -> Option<(Pubkey, u64)> {
+        let account = self.svm.get_account(token_account).expect("token account");
+        let state = spl_token::state::Account::unpack(&account.data).unwrap();
+        Option::<Pubkey>::from(state.delegate).map(|delegate| (delegate, state.delegated_amount))
+    }
+
+    /// Zero once the account is closed.
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm
+            .get_account(address)
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub fn escrow_balance(&self, proposal: &Pubkey) -> u64 {
+        self.balance(&pda::escrow(proposal).0)
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self.svm.get_account(address).expect("program account");
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub fn proposal(&self, address: &Pubkey) -> Proposal {
+        self.account(address)
+    }
+
+    pub fn milestone(&self, proposal: &Pubkey, number: u8) -> Milestone {
+        self.account(&pda::milestone(proposal, number).0)
+    }
+
+    pub fn contributor(&self, wallet: &Pubkey, proposal: &Pubkey) -> Contributor {
+        self.account(&pda::contributor(wallet, proposal).0)
+    }
+
+    /// Overwrites a milestone's status in place, e.g. to fail a milestone
+    /// whose deadline passed without a verdict.
+    pub fn force_milestone_status(
+        &mut self,
+        proposal: &Pubkey,
+        number: u8,
+        status: MilestoneStatus,
+    ) {
+        let address = pda::milestone(proposal, number).0;
+        let mut milestone: Milestone = self.account(&address);
+        milestone.status = status;
+
+        let mut account = self.svm.get_account(&address).unwrap();
+        let mut data = Vec::with_capacity(account.data.len());
+        milestone.try_serialize(&mut data).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    /// Milestone funds can be released as soon as they are validated.
+    pub fn create_proposal(
+        &mut self,
+        researcher: &Keypair,
+        title: &str,
+        amount_asked: u64,
+        total_milestones: u8,
+        security_deposit: u64,
+    ) -> TxResult {
+        let args = proposal_args(title, amount_asked, total_milestones, security_deposit);
+        self.create_proposal_with(researcher, args)
+    }
+
+    pub fn create_proposal_with_challenge_period(
+        &mut self,
+        researcher: &Keypair,
+        title: &str,
+        amount_asked: u64,
+        total_milestones: u8,
+        security_deposit: u64,
+        challenge_period: i64,
+    ) -> TxResult {
+        let args = instructions::CreateProposalArgs {
+            challenge_period,
+            ..proposal_args(title, amount_asked, total_milestones, security_deposit)
+        };
+        self.create_proposal_with(researcher, args)
+    }
+
+    pub fn create_proposal_with_allowlist(
+        &mut self,
+        researcher: &Keypair,
+        title: &str,
+        amount_asked: u64,
+        total_milestones: u8,
+        security_deposit: u64,
+        allowlist_root: [u8; 32],
+    ) -> TxResult {
+        let args = instructions::CreateProposalArgs {
+            allowlist_root: Some(allowlist_root),
+            ..proposal_args(title, amount_asked, total_milestones, security_deposit)
+        };
+        self.create_proposal_with(researcher, args)
+    }
+
+    pub fn create_proposal_with_limits(
+        &mut self,
+        researcher: &Keypair,
+        title: &str,
+        amount_asked: u64,
+        total_milestones: u8,
+        security_deposit: u64,
+        contribution_limits: ContributionLimits,
+    ) -> TxResult {
+        let args = instructions::CreateProposalArgs {
+            contribution_limits,
+            ..proposal_args(title, amount_asked, total_milestones, security_deposit)
+        };
+        self.create_proposal_with(researcher, args)
+    }
+
+    pub fn create_proposal_with_stretch_goals(
+        &mut self,
+        researcher: &Keypair,
+        title: &str,
+        amount_asked: u64,
+        total_milestones: u8,
+        security_deposit: u64,
+        stretch_goals: Vec<StretchGoal>,
+    ) -> TxResult {
+        let args = instructions::CreateProposalArgs {
+            stretch_goals,
+            ..proposal_args(title, amount_asked, total_milestones, security_deposit)
+        };
+        self.create_proposal_with(researcher, args)
+    }
+
+    fn create_proposal_with(
+        &mut self,
+        researcher: &Keypair,
+        args: instructions::CreateProposalArgs,
+    ) -> TxResult {
+        let ix = instructions::create_proposal(
+            &researcher.pubkey(),
+            &self.mint,
+            &self.token_account(&researcher.pubkey()),
+            args,
+        );
+        self.send(researcher, &[ix], &[])
+    }
+
+    pub fn fund(&mut self, contributor: &Keypair, proposal: &Pubkey, amount: u64) -> TxResult {
+        let ix = instructions::fund_proposal(
+            &contributor.pubkey(),
+            proposal,
+            &self.mint,
+            &self.token_account(&contributor.pubkey()),
+            amount,
+        );
+        self.send(contributor, &[ix], &[])
+    }
+
+    pub fn fund_allowlisted(
+        &mut self,
+        contributor: &Keypair,
+        proposal: &Pubkey,
+        amount: u64,
+        allowance: AllowlistProof,
+    ) -> TxResult {
+        let ix = instructions::fund_proposal_allowlisted(
+            &contributor.pubkey(),
+            proposal,
+            &self.mint,
+            &self.token_account(&contributor.pubkey()),
+            amount,
+            None,
+            allowance,
+        );
+        self.send(contributor, &[ix], &[])
+    }
+
+    pub fn fund_for(
+        &mut self,
+        sponsor: &Keypair,
+        beneficiary: &Pubkey,
+        proposal: &Pubkey,
+        amount: u64,
+        memo: Option<&str>,
+    ) -> TxResult {
+        let ix = instructions::fund_proposal_for(
+            &sponsor.pubkey(),
+            beneficiary,
+            proposal,
+            &self.mint,
+            &self.token_account(&sponsor.pubkey()),
+            amount,
+            memo.map(str::to_string),
+            None,
+        );
+        self.send(sponsor, &[ix], &[])
+    }
+
+    pub fn pledge(
+        &mut self,
+        contributor: &Keypair,
+        proposal: &Pubkey,
+        amount: u64,
+        installments: u16,
+    ) -> TxResult {
+        let ix = instructions::create_pledge(
+            &contributor.pubkey(),
+            proposal,
+            &self.mint,
+            &self.token_account(&contributor.pubkey()),
+            amount,
+            installments,
+            None,
+        );
+        self.send(contributor, &[ix], &[])
+    }
+
+    pub fn execute_pledge(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        contributor: &Pubkey,
+    ) -> TxResult {
+        let ix = instructions::execute_pledge(
+            &cranker.pubkey(),
+            proposal,
+            contributor,
+            &self.token_account(contributor),
+        );
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn cancel_pledge(&mut self, contributor: &Keypair, proposal: &Pubkey) -> TxResult {
+        let ix = instructions::cancel_pledge(
+            &contributor.pubkey(),
+            proposal,
+            &self.token_account(&contributor.pubkey()),
+        );
+        self.send(contributor, &[ix], &[])
+    }
+
+    pub fn fund_in_round(
+        &mut self,
+        contributor: &Keypair,
+        proposal: &Pubkey,
+        amount: u64,
+        round: &Pubkey,
+    ) -> TxResult {
+        let ix = instructions::fund_proposal_in_round(
+            &contributor.pubkey(),
+            proposal,
+            &self.mint,
+            &self.token_account(&contributor.pubkey()),
+            amount,
+            round,
+        );
+        self.send(contributor, &[ix], &[])
+    }
+
+    /// Opens round `round_id` now, for `days` days.
+    pub fn create_round(
+        &mut self,
+        sponsor: &Keypair,
+        round_id: u64,
+        matching_pool: u64,
+        days: i64,
+        proposals: &[Pubkey],
+    ) -> TxResult {
+        let now = self.now();
+        let ix = instructions::create_funding_round(
+            &sponsor.pubkey(),
+            &self.mint,
+            &self.token_account(&sponsor.pubkey()),
+            instructions::CreateFundingRoundArgs {
+                round_id,
+                matching_pool,
+                starts_at: now,
+                ends_at: now + days * DAY,
+                proposals: proposals.to_vec(),
+            },
+        );
+        self.send(sponsor, &[ix], &[])
+    }
+
+    pub fn distribute(&mut self, cranker: &Keypair, round: &Pubkey) -> TxResult {
+        let state: FundingRound = self.account(round);
+        let proposals: Vec<Pubkey> = state.entries.iter().map(|e| e.proposal).collect();
+        let ix = instructions::distribute_matching(
+            round,
+            &self.token_account(&state.sponsor),
+            &proposals,
+        );
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn submit(&mut self, researcher: &Keypair, proposal: &Pubkey, number: u8) -> TxResult {
+        self.submit_with(researcher, proposal, number, false)
+    }
+
+    pub fn submit_with(
+        &mut self,
+        researcher: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        commit_reveal: bool,
+    ) -> TxResult {
+        let ix = instructions::submit_milestone(
+            &researcher.pubkey(),
+            &researcher.pubkey(),
+            proposal,
+            number,
+            cid(&format!("evidence-{number}")).to_string(),
+            commit_reveal,
+        );
+        self.send(researcher, &[ix], &[])
+    }
+
+    /// Adds a report stored at [`cid`] of `label`.
+    pub fn add_evidence(
+        &mut self,
+        researcher: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        label: &str,
+    ) -> TxResult {
+        let ix = instructions::add_evidence(
+            &researcher.pubkey(),
+            &researcher.pubkey(),
+            proposal,
+            number,
+            Attachment {
+                content_type: ContentType::Report,
+                cid: cid(label),
+                size: 1_024,
+                sha256: hashv(&[label.as_bytes()]).to_bytes(),
+            },
+        );
+        self.send(researcher, &[ix], &[])
+    }
+
+    /// Commits to `approved` with a salt derived from the validator's key.
+    pub fn commit(
+        &mut self,
+        validator: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        approved: bool,
+    ) -> TxResult {
+        let milestone = pda::milestone(proposal, number).0;
+        let commitment = Vote::commitment(
+            &milestone,
+            &validator.pubkey(),
+            approved,
+            &validator.pubkey().to_bytes(),
+        );
+        let ix = instructions::commit_vote(&validator.pubkey(), proposal, &milestone, commitment);
+        self.send(validator, &[ix], &[])
+    }
+
+    /// Reveals with the salt [`Harness::commit`] used.
+    pub fn reveal(
+        &mut self,
+        validator: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        approved: bool,
+    ) -> TxResult {
+        let milestone = pda::milestone(proposal, number).0;
+        let ix = instructions::reveal_vote(
+            &validator.pubkey(),
+            proposal,
+            &milestone,
+            approved,
+            validator.pubkey().to_bytes(),
+            None,
+        );
+        self.send(validator, &[ix], &[])
+    }
+
+    pub fn forfeit(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        validator: &Pubkey,
+    ) -> TxResult {
+        let milestone = pda::milestone(proposal, number).0;
+        let ix = instructions::penalize_unrevealed_vote(
+            &cranker.pubkey(),
+            proposal,
+            &milestone,
+            validator,
+        );
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn vote(
+        &mut self,
+        validator: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        approved: bool,
+    ) -> TxResult {
+        self.vote_with_review(validator, proposal, number, approved, None)
+    }
+
+    pub fn vote_with_review(
+        &mut self,
+        validator: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        approved: bool,
+        review: Option<Review>,
+    ) -> TxResult {
+        let milestone = pda::milestone(proposal, number).0;
+        let ix = instructions::validate_milestone(
+            &validator.pubkey(),
+            proposal,
+            &milestone,
+            approved,
+            review,
+        );
+        self.send(validator, &[ix], &[])
+    }
+
+    /// Pays the team members other than the researcher into their
+    /// associated token accounts.
+    pub fn release(&mut self, researcher: &Keypair, proposal: &Pubkey, number: u8) -> TxResult {
+        let members: Vec<Pubkey> = self
+            .proposal(proposal)
+            .team
+            .iter()
+            .filter(|member| member.wallet != researcher.pubkey())
+            .map(|member| self.token_account(&member.wallet))
+            .collect();
+        let ix = instructions::release_funds(
+            &researcher.pubkey(),
+            proposal,
+            &pda::milestone(proposal, number).0,
+            &self.token_account(&researcher.pubkey()),
+            &members,
+        );
+        self.send(researcher, &[ix], &[])
+    }
+
+    pub fn challenge(&mut self, challenger: &Keypair, proposal: &Pubkey, number: u8) -> TxResult {
+        let ix = instructions::challenge_milestone(
+            &challenger.pubkey(),
+            proposal,
+            number,
+            &self.mint,
+            &self.token_account(&challenger.pubkey()),
+        );
+        self.send(challenger, &[ix], &[])
+    }
+
+    pub fn resolve(&mut self, cranker: &Keypair, proposal: &Pubkey, number: u8) -> TxResult {
+        let challenger = self.milestone(proposal, number).challenger.unwrap();
+        let authority = self.proposal(proposal).authority;
+        let ix = instructions::resolve_challenge(
+            &cranker.pubkey(),
+            proposal,
+            number,
+            &challenger,
+            &self.token_account(&challenger),
+            &self.token_account(&authority),
+        );
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn penalize(
+        &mut self,
+        authority: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        treasury: &Pubkey,
+    ) -> TxResult {
+        let ix = instructions::apply_penalty(
+            &authority.pubkey(),
+            proposal,
+            &pda::milestone(proposal, number).0,
+            treasury,
+        );
+        self.send(authority, &[ix], &[])
+    }
+
+    pub fn refund(&mut self, contributor: &Keypair, proposal: &Pubkey) -> TxResult {
+        self.refund_for(contributor, &contributor.pubkey(), proposal)
+    }
+
+    /// Sends `wallet` its refund, `cranker` only pays the fee.
+    pub fn refund_for(
+        &mut self,
+        cranker: &Keypair,
+        wallet: &Pubkey,
+        proposal: &Pubkey,
+    ) -> TxResult {
+        let ix = instructions::withdraw_funds(wallet, proposal, &self.token_account(wallet));
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn cancel(&mut self, researcher: &Keypair, proposal: &Pubkey) -> TxResult {
+        let ix = instructions::cancel_proposal(
+            &researcher.pubkey(),
+            proposal,
+            &self.token_account(&researcher.pubkey()),
+        );
+        self.send(researcher, &[ix], &[])
+    }
+
+    /// Rent goes to the proposal's researcher, whoever signs as authority.
+    pub fn close(&mut self, authority: &Keypair, proposal: &Pubkey) -> TxResult {
+        let researcher = self.proposal(proposal).researcher;
+        let ix = instructions::close_proposal(&authority.pubkey(), proposal, &researcher);
+        self.send(authority, &[ix], &[])
+    }
+
+    /// Rent goes to `payer`, whoever cranks it. The evidence's payer is read
+    /// from the account.
+    pub fn close_milestone(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        payer: &Pubkey,
+        number: u8,
+    ) -> TxResult {
+        let authority = self.proposal(proposal).authority;
+        let evidence_payer = self
+            .svm
+            .get_account(&pda::evidence(proposal, number).0)
+            .filter(|account| !account.data.is_empty())
+            .map(|account| {
+                MilestoneEvidence::try_deserialize(&mut &account.data[..])
+                    .unwrap()
+                    .rent_payer(&authority)
+            });
+        let ix = instructions::close_milestone(
+            &cranker.pubkey(),
+            proposal,
+            payer,
+            evidence_payer.as_ref(),
+            number,
+        );
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn close_vote(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+        validator: &Pubkey,
+    ) -> TxResult {
+        let ix = instructions::close_vote(&cranker.pubkey(), proposal, number, validator);
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn close_contributor(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        wallet: &Pubkey,
+    ) -> TxResult {
+        let payer = self.contributor(wallet, proposal).rent_payer();
+        let ix = instructions::close_contributor(&cranker.pubkey(), proposal, wallet, &payer);
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn close_accounts(
+        &mut self,
+        cranker: &Keypair,
+        proposal: &Pubkey,
+        closables: &[Closable],
+    ) -> TxResult {
+        let ix = instructions::close_accounts(&cranker.pubkey(), proposal, closables);
+        self.send(cranker, &[ix], &[])
+    }
+
+    pub fn propose_authority(
+        &mut self,
+        authority: &Keypair,
+        proposal: &Pubkey,
+        new_authority: Option<Pubkey>,
+    ) -> TxResult {
+        let ix = instructions::propose_authority(&authority.pubkey(), proposal, new_authority);
+        self.send(authority, &[ix], &[])
+    }
+
+    pub fn accept_authority(&mut self, new_authority: &Keypair, proposal: &Pubkey) -> TxResult {
+        let ix = instructions::accept_authority(&new_authority.pubkey(), proposal);
+        self.send(new_authority, &[ix], &[])
+    }
+
+    /// Submits as `authority` with `payer` covering the rent, the way a
+    /// multisig whose vault can't pay would.
+    pub fn submit_paid_by(
+        &mut self,
+        authority: &Keypair,
+        payer: &Keypair,
+        proposal: &Pubkey,
+        number: u8,
+    ) -> TxResult {
+        let ix = instructions::submit_milestone(
+            &authority.pubkey(),
+            &payer.pubkey(),
+            proposal,
+            number,
+            cid(&format!("evidence-{number}")).to_string(),
+            false,
+        );
+        self.send(payer, &[ix], &[authority])
+    }
+}
+
+/// No challenge period, allowlist, contribution limits or stretch goals.
+fn proposal_args(
+    title: &str,
+    amount_asked: u64,
+    total_milestones: u8,
+    security_deposit: u64,
+) -> instructions::CreateProposalArgs {
+    instructions::CreateProposalArgs {
+        title: title.to_string(),
+        abstract_text: "Abstract".to_string(),
+        ipfs_hash: cid("proposal").to_string(),
+        amount_asked,
+        total_milestones,
+        security_deposit,
+        challenge_period: 0,
+        allowlist_root: None,
+        contribution_limits: ContributionLimits::default(),
+        stretch_goals: Vec::new(),
+    }
+}
+
+/// Asserts that a transaction failed with the given Anchor error code.
+pub fn assert_error(result: TxResult, code: u32) {
+    match result {
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            "logs: {:#?}",
+            failed.meta.logs
+        ),
+        Ok(meta) => panic!("expected error {code}, logs: {:#?}", meta.logs),
+    }
+}
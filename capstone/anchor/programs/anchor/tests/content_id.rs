@@ -0,0 +1,177 @@
+//! Parsing and rendering of IPFS and Arweave identifiers.
+
+use anchor::error::ErrorCode;
+use anchor::ContentId;
+use anchor_lang::error::Error;
+
+const CID_V0: &str = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR";
+/// `CID_V0` as CIDv1, in base32 and base58btc.
+const CID_V1: &str = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+const CID_V1_BASE58: &str = "zdj7Wic6KcJAfWz1c9o4M6kq9Lwd5BfbxkVafnrojaaGiSFxM";
+/// Raw block of "hello".
+const CID_RAW: &str = "bafkreibm6jg3ux5qumhcn2b3flc3tyu6dmlb4xa7u5bf44yegnrjhc4yeq";
+/// Bytes 0 to 31.
+const ARWEAVE_ID: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+
+fn hex(digits: &str) -> [u8; 32] {
+    let bytes: Vec<u8> = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect();
+    bytes.try_into().unwrap()
+}
+
+fn error(result: anchor_lang::Result<impl std::fmt::Debug>) -> ErrorCode {
+    match result.unwrap_err() {
+        Error::AnchorError(err) => ErrorCode::from_code(err.error_code_number).unwrap(),
+        err => panic!("unexpected error {err:?}"),
+    }
+}
+
+#[test]
+fn parses_every_supported_form() {
+    let digest = hex("c3c4733ec8affd06cf9e9ff50ffc6bcd2ec85a6170004bb709669c31de94391a");
+    assert_eq!(
+        ContentId::parse(CID_V0).unwrap(),
+        ContentId::IpfsV0 { digest }
+    );
+
+    let v1 = ContentId::IpfsV1 {
+        codec: 0x70,
+        hash: 0x12,
+        digest,
+    };
+    assert_eq!(ContentId::parse(CID_V1).unwrap(), v1);
+    assert_eq!(ContentId::parse(CID_V1_BASE58).unwrap(), v1);
+    assert_eq!(
+        ContentId::parse(CID_RAW).unwrap(),
+        ContentId::IpfsV1 {
+            codec: 0x55,
+            hash: 0x12,
+            digest: hex("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"),
+        }
+    );
+
+    let mut id = [0; 32];
+    id.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    assert_eq!(
+        ContentId::parse(ARWEAVE_ID).unwrap(),
+        ContentId::Arweave { id }
+    );
+}
+
+#[test]
+fn renders_the_canonical_form() {
+    for input in [CID_V0, CID_V1, CID_RAW, ARWEAVE_ID] {
+        assert_eq!(ContentId::parse(input).unwrap().to_string(), input);
+    }
+    // base58btc CIDv1 comes back in base32
+    assert_eq!(ContentId::parse(CID_V1_BASE58).unwrap().to_string(), CID_V1);
+
+    assert_eq!(
+        ContentId::parse(CID_V0).unwrap().uri(),
+        format!("ipfs://{CID_V0}")
+    );
+    assert_eq!(
+        ContentId::parse(ARWEAVE_ID).unwrap().uri(),
+        format!("ar://{ARWEAVE_ID}")
+    );
+}
+
+#[test]
+fn accepts_uris() {
+    assert_eq!(
+        ContentId::parse(&format!("ipfs://{CID_V1}")).unwrap(),
+        ContentId::parse(CID_V1).unwrap()
+    );
+    assert_eq!(
+        ContentId::parse(&format!("ar://{ARWEAVE_ID}")).unwrap(),
+        ContentId::parse(ARWEAVE_ID).unwrap()
+    );
+
+    let uri = format!("ipfs://{CID_V0}/metadata.json");
+    let (id, path) = ContentId::parse_uri(&uri).unwrap();
+    assert_eq!(id, ContentId::parse(CID_V0).unwrap());
+    assert_eq!(path, "/metadata.json");
+    assert_eq!(error(ContentId::parse(&uri)), ErrorCode::UnexpectedUriPath);
+
+    assert_eq!(
+        error(ContentId::parse_uri(&format!(
+            "https://ipfs.io/ipfs/{CID_V0}"
+        ))),
+        ErrorCode::UnsupportedUriScheme
+    );
+    assert_eq!(
+        error(ContentId::parse_uri(CID_V0)),
+        ErrorCode::UnsupportedUriScheme
+    );
+    assert_eq!(
+        error(ContentId::parse_uri("ipfs:///metadata.json")),
+        ErrorCode::EmptyContentId
+    );
+    // Arweave ids are not CIDs and the other way around
+    assert_eq!(
+        error(ContentId::parse(&format!("ar://{CID_V0}"))),
+        ErrorCode::InvalidArweaveId
+    );
+    assert_eq!(
+        error(ContentId::parse(&format!("ipfs://{ARWEAVE_ID}"))),
+        ErrorCode::UnknownContentIdFormat
+    );
+}
+
+#[test]
+fn reports_what_is_wrong() {
+    let cases = [
+        ("", ErrorCode::EmptyContentId),
+        ("QmTestHash", ErrorCode::UnknownContentIdFormat),
+        ("é", ErrorCode::UnknownContentIdFormat),
+        (&"b".repeat(201), ErrorCode::ContentIdTooLong),
+        // '0' is not in the base58 alphabet
+        (
+            "Qm0WqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR",
+            ErrorCode::InvalidBase58,
+        ),
+        // Upper case is a different multibase
+        (
+            "bAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI",
+            ErrorCode::InvalidBase32,
+        ),
+        // Spare bits of the last character set, and a character that adds no byte
+        (
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdj",
+            ErrorCode::InvalidBase32,
+        ),
+        (&format!("{CID_V1}a"), ErrorCode::InvalidBase32),
+        (
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh+",
+            ErrorCode::InvalidBase64Url,
+        ),
+        // Spare bits of the last character set
+        (
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh9",
+            ErrorCode::InvalidArweaveId,
+        ),
+        // Version 2
+        (
+            "bajybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            ErrorCode::InvalidCidVersion,
+        ),
+        // Digest cut short, and one byte too many
+        (&CID_V1[..CID_V1.len() - 2], ErrorCode::InvalidCidLength),
+        (&format!("{CID_V1}aa"), ErrorCode::InvalidCidLength),
+        // Identity multihash, sha2-256 code with a 20-byte digest
+        ("bafkqaaa", ErrorCode::UnsupportedMultihash),
+        (
+            "bafkrefaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            ErrorCode::UnsupportedMultihash,
+        ),
+        // Version varint that never ends, and a padded one
+        ("b7777777777777777", ErrorCode::InvalidVarint),
+        ("bqeaa", ErrorCode::InvalidVarint),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(error(ContentId::parse(input)), expected, "{input}");
+    }
+}
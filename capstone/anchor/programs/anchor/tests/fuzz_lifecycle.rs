@@ -0,0 +1,245 @@
+//! Property-based exploration of the proposal lifecycle.
+//!
+//! Random sequences of actions from random actors are replayed against the
+//! program in LiteSVM. Failed transactions are fine, but after every step the
+//! escrow must reconcile with the tokens that actually moved, refunds must stay
+//! within a contributor's share and statuses may only move forward.
+
+mod common;
+
+use anchor::{MilestoneStatus, ProposalStatus};
+use anchor_lang::prelude::Pubkey;
+use common::{Harness, DAY};
+use proptest::prelude::*;
+use researchchain_client::accounts::{milestone_status_name, proposal_status_name};
+use researchchain_client::pda;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const CONTRIBUTORS: usize = 3;
+const VALIDATORS: usize = 3;
+const AMOUNT_ASKED: u64 = 1_000;
+const MILESTONES: u8 = 3;
+const DEPOSIT: u64 = 100;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Fund {
+        who: usize,
+        amount: u64,
+    },
+    Submit,
+    Vote {
+        who: usize,
+        approve: bool,
+    },
+    Release,
+    Penalize,
+    Refund {
+        who: usize,
+    },
+    Warp {
+        hours: i64,
+    },
+    /// Lets the current milestone's deadline pass, failing it if still undecided
+    Expire,
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        3 => (0..CONTRIBUTORS, 1..=400u64).prop_map(|(who, amount)| Action::Fund { who, amount }),
+        2 => Just(Action::Submit),
+        4 => (0..VALIDATORS, any::<bool>()).prop_map(|(who, approve)| Action::Vote { who, approve }),
+        2 => Just(Action::Release),
+        2 => Just(Action::Penalize),
+        1 => (0..CONTRIBUTORS).prop_map(|who| Action::Refund { who }),
+        1 => (1..72i64).prop_map(|hours| Action::Warp { hours }),
+        1 => Just(Action::Expire),
+    ]
+}
+
+/// Tokens that have moved in and out of escrow, measured on the other side
+/// of each transfer.
+#[derive(Default)]
+struct Ledger {
+    contributions: u64,
+    releases: u64,
+    penalties: u64,
+    deposit_returned: u64,
+    refunds: u64,
+    refund_count: u64,
+}
+
+impl Ledger {
+    fn escrow(&self) -> u64 {
+        DEPOSIT + self.contributions
+            - self.releases
+            - self.penalties
+            - self.deposit_returned
+            - self.refunds
+    }
+}
+
+struct Snapshot {
+    proposal: ProposalStatus,
+    milestones: Vec<(MilestoneStatus, bool)>,
+}
+
+impl Snapshot {
+    fn take(h: &Harness, proposal: &Pubkey) -> Self {
+        let state = h.proposal(proposal);
+        let milestones = (1..=state.current_milestone)
+            .map(|number| {
+                let milestone = h.milestone(proposal, number);
+                (milestone.status, milestone.funds_released)
+            })
+            .collect();
+        Self {
+            proposal: state.status,
+            milestones,
+        }
+    }
+}
+
+fn legal_proposal_transition(from: &ProposalStatus, to: &ProposalStatus) -> bool {
+    use ProposalStatus::*;
+    from == to || matches!((from, to), (Active, Completed) | (Active, Failed))
+}
+
+fn legal_milestone_transition(from: &MilestoneStatus, to: &MilestoneStatus) -> bool {
+    use MilestoneStatus::*;
+    from == to || matches!((from, to), (Pending, Active) | (Active, Validated | Failed))
+}
+
+fn check_transitions(before: &Snapshot, after: &Snapshot) {
+    assert!(
+        legal_proposal_transition(&before.proposal, &after.proposal),
+        "proposal went from {} to {}",
+        proposal_status_name(&before.proposal),
+        proposal_status_name(&after.proposal)
+    );
+    for (number, ((from, released), (to, now_released))) in
+        before.milestones.iter().zip(&after.milestones).enumerate()
+    {
+        assert!(
+            legal_milestone_transition(from, to),
+            "milestone {} went from {} to {}",
+            number + 1,
+            milestone_status_name(from),
+            milestone_status_name(to)
+        );
+        assert!(
+            !released || *now_released,
+            "milestone {} released twice",
+            number + 1
+        );
+    }
+}
+
+fn run(actions: Vec<Action>) {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let contributors: Vec<Keypair> = (0..CONTRIBUTORS).map(|_| h.wallet()).collect();
+    let validators: Vec<Keypair> = (0..VALIDATORS).map(|_| h.wallet()).collect();
+    let treasury = h.wallet();
+    let treasury = h.token_account(&treasury.pubkey());
+    let researcher_account = h.token_account(&researcher.pubkey());
+
+    h.create_proposal(&researcher, "Fuzz", AMOUNT_ASKED, MILESTONES, DEPOSIT)
+        .unwrap();
+    let proposal = pda::proposal(&researcher.pubkey(), "Fuzz").0;
+
+    let mut ledger = Ledger::default();
+    let mut before = Snapshot::take(&h, &proposal);
+
+    for action in actions {
+        let current = h.proposal(&proposal).current_milestone;
+        match action {
+            Action::Fund { who, amount } => {
+                let account = h.token_account(&contributors[who].pubkey());
+                let balance = h.balance(&account);
+                if h.fund(&contributors[who], &proposal, amount).is_ok() {
+                    assert_eq!(balance - h.balance(&account), amount);
+                    ledger.contributions += amount;
+                }
+            }
+            Action::Submit => {
+                let _ = h.submit(&researcher, &proposal, current + 1);
+            }
+            Action::Vote { who, approve } if current > 0 => {
+                let _ = h.vote(&validators[who], &proposal, current, approve);
+            }
+            Action::Release if current > 0 => {
+                let balance = h.balance(&researcher_account);
+                let deposit = h.proposal(&proposal).current_security_deposit;
+                if h.release(&researcher, &proposal, current).is_ok() {
+                    let paid = h.balance(&researcher_account) - balance;
+                    // The release that completes the proposal also returns the deposit
+                    let returned = match h.proposal(&proposal).status {
+                        ProposalStatus::Completed => deposit,
+                        _ => 0,
+                    };
+                    assert_eq!(paid, AMOUNT_ASKED / MILESTONES as u64 + returned);
+                    ledger.releases += paid - returned;
+                    ledger.deposit_returned += returned;
+                }
+            }
+            Action::Penalize if current > 0 => {
+                let balance = h.balance(&treasury);
+                if h.penalize(&researcher, &proposal, current, &treasury)
+                    .is_ok()
+                {
+                    ledger.penalties += h.balance(&treasury) - balance;
+                }
+            }
+            Action::Refund { who } => {
+                let wallet = contributors[who].pubkey();
+                let account = h.token_account(&wallet);
+                let balance = h.balance(&account);
+                let outstanding = h.proposal(&proposal).amount_raised;
+                let contributed = h
+                    .svm
+                    .get_account(&pda::contributor(&wallet, &proposal).0)
+                    .map(|_| h.contributor(&wallet, &proposal).amount);
+
+                if h.refund(&contributors[who], &proposal).is_ok() {
+                    let refund = h.balance(&account) - balance;
+                    let contributed = contributed.unwrap();
+                    // Their contribution plus a pro-rata share of the deposit,
+                    // allowing for one token of rounding per earlier refund
+                    let share = (contributed * DEPOSIT).div_ceil(outstanding);
+                    assert!(
+                        refund <= contributed + share + ledger.refund_count,
+                        "refunded {refund} for a contribution of {contributed}"
+                    );
+                    ledger.refunds += refund;
+                    ledger.refund_count += 1;
+                }
+            }
+            Action::Warp { hours } => h.warp(hours * 3_600),
+            Action::Expire if current > 0 => {
+                h.warp(15 * DAY);
+                if h.milestone(&proposal, current).status == MilestoneStatus::Active {
+                    h.force_milestone_status(&proposal, current, MilestoneStatus::Failed);
+                }
+            }
+            _ => {}
+        }
+
+        assert_eq!(h.escrow_balance(&proposal), ledger.escrow());
+        assert!(ledger.penalties <= DEPOSIT);
+
+        let after = Snapshot::take(&h, &proposal);
+        check_transitions(&before, &after);
+        before = after;
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    #[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+    fn lifecycle_invariants_hold(actions in prop::collection::vec(action(), 1..40)) {
+        run(actions);
+    }
+}
@@ -0,0 +1,1698 @@
+mod common;
+
+use anchor::error::ErrorCode;
+use anchor::legacy::{MilestoneV0, ProposalV0, ProposalV2};
+use anchor::{
+    ContributionLimits, MilestoneEvidence, MilestoneStatus, PledgeEnd, ProposalStatus, Review,
+    ReviewScores, ReviewTotals, StretchGoal, TeamMember, Versioned, Vote, COMMIT_PHASE_SECONDS,
+};
+use anchor_lang::{prelude::Pubkey, AnchorSerialize, Discriminator};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{non_transferable::NonTransferable, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use common::{assert_error, cid, Harness, DAY, STARTING_BALANCE};
+use researchchain_client::{instructions, instructions::Closable, pda, Allowlist};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+fn proposal_address(researcher: &Keypair, title: &str) -> Pubkey {
+    pda::proposal(&researcher.pubkey(), title).0
+}
+
+/// Two of the three validators reject the milestone, returns who voted.
+fn reject(h: &mut Harness, proposal: &Pubkey, number: u8) -> Vec<Pubkey> {
+    let mut validators = Vec::new();
+    for _ in 0..2 {
+        let validator = h.wallet();
+        h.vote(&validator, proposal, number, false).unwrap();
+        validators.push(validator.pubkey());
+    }
+    assert!(h.milestone(proposal, number).status == MilestoneStatus::Failed);
+    validators
+}
+
+/// Applies the four escalating penalties that fail a proposal.
+fn penalize_until_failed(h: &mut Harness, proposal: &Pubkey, number: u8, treasury: &Pubkey) {
+    let authority = h.wallet();
+    for _ in 0..4 {
+        h.penalize(&authority, proposal, number, treasury).unwrap();
+    }
+    assert!(h.proposal(proposal).status == ProposalStatus::Failed);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn funded_proposal_releases_every_milestone_and_closes() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+
+    h.create_proposal(&researcher, "Protein folding", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Protein folding");
+    assert_eq!(h.escrow_balance(&proposal), 100);
+
+    h.fund(&alice, &proposal, 600).unwrap();
+    h.fund(&bob, &proposal, 400).unwrap();
+    let state = h.proposal(&proposal);
+    assert_eq!(state.amount_raised, 1_000);
+    assert_eq!(state.contributors_count, 2);
+    assert_eq!(h.contributor(&alice.pubkey(), &proposal).amount, 600);
+    assert_eq!(h.escrow_balance(&proposal), 1_100);
+
+    for number in 1..=2 {
+        h.submit(&researcher, &proposal, number).unwrap();
+        assert!(h.milestone(&proposal, number).status == MilestoneStatus::Active);
+
+        for validator in &validators {
+            h.vote(validator, &proposal, number, true).unwrap();
+        }
+        let milestone = h.milestone(&proposal, number);
+        assert_eq!(milestone.validation_votes, 2);
+        assert!(milestone.status == MilestoneStatus::Validated);
+        assert!(!milestone.funds_released);
+
+        h.release(&researcher, &proposal, number).unwrap();
+        assert!(h.milestone(&proposal, number).funds_released);
+    }
+
+    // Both milestone payments went out and the last release returned the deposit
+    assert_eq!(h.escrow_balance(&proposal), 0);
+    assert_eq!(
+        h.balance(&h.token_account(&researcher.pubkey())),
+        STARTING_BALANCE + 1_000
+    );
+    let state = h.proposal(&proposal);
+    assert!(state.status == ProposalStatus::Completed);
+    assert_eq!(state.current_security_deposit, 0);
+
+    // Votes, milestones and contributors close before the proposal they point to
+    let mut closables = Vec::new();
+    for number in 1..=2 {
+        for validator in &validators {
+            closables.push(Closable::Vote {
+                milestone_number: number,
+                validator: validator.pubkey(),
+            });
+        }
+        closables.push(Closable::Milestone {
+            number,
+            payer: researcher.pubkey(),
+        });
+    }
+    for wallet in [alice.pubkey(), bob.pubkey()] {
+        closables.push(Closable::Contributor {
+            wallet,
+            payer: wallet,
+        });
+    }
+    h.close_accounts(&alice, &proposal, &closables).unwrap();
+    assert_eq!(h.proposal(&proposal).open_accounts, 0);
+
+    let lamports_before = h.svm.get_balance(&researcher.pubkey()).unwrap();
+    let proposal_rent = h.svm.get_balance(&proposal).unwrap();
+    h.close(&researcher, &proposal).unwrap();
+    assert!(h.svm.get_account(&proposal).is_none_or(|a| a.lamports == 0));
+    // Rent comes back to the researcher, less the transaction fee
+    assert!(
+        h.svm.get_balance(&researcher.pubkey()).unwrap() > lamports_before + proposal_rent - 10_000
+    );
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn milestones_are_submitted_in_order() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    h.create_proposal(&researcher, "Ordering", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Ordering");
+
+    let ix = instructions::submit_milestone(
+        &researcher.pubkey(),
+        &researcher.pubkey(),
+        &proposal,
+        2,
+        cid("early").to_string(),
+        false,
+    );
+    assert!(h.send(&researcher, &[ix], &[]).is_err());
+
+    h.submit(&researcher, &proposal, 1).unwrap();
+    h.submit(&researcher, &proposal, 2).unwrap();
+    assert_eq!(h.proposal(&proposal).current_milestone, 2);
+    assert!(h.submit(&researcher, &proposal, 3).is_err());
+}
+
+fn review(reproducibility: u8, completeness: u8, quality: u8) -> Option<Review> {
+    Some(Review {
+        scores: ReviewScores {
+            reproducibility,
+            completeness,
+            quality,
+        },
+        rationale: cid("rationale"),
+    })
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn reviews_are_stored_on_votes_and_totalled_on_the_milestone() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let [a, b] = [h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Reviews", 1_000, 1, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Reviews");
+    h.submit(&researcher, &proposal, 1).unwrap();
+
+    assert_error(
+        h.vote_with_review(&a, &proposal, 1, true, review(11, 5, 5)),
+        ErrorCode::InvalidReviewScore.into(),
+    );
+    h.vote_with_review(&a, &proposal, 1, false, review(2, 4, 3))
+        .unwrap();
+    h.vote_with_review(&b, &proposal, 1, true, review(7, 10, 5))
+        .unwrap();
+    let milestone = pda::milestone(&proposal, 1).0;
+    let vote = h.account::<Vote>(&pda::vote(&a.pubkey(), &milestone).0);
+    assert_eq!(vote.review, review(2, 4, 3));
+    assert_eq!(h.milestone(&proposal, 1).reviews, 2);
+
+    // Voting again replaces the earlier review in the totals
+    h.vote_with_review(&a, &proposal, 1, true, review(9, 8, 7))
+        .unwrap();
+    let milestone = h.milestone(&proposal, 1);
+    assert!(milestone.status == MilestoneStatus::Validated);
+    assert_eq!(milestone.reviews, 2);
+    assert_eq!(
+        milestone.review_totals,
+        ReviewTotals {
+            reproducibility: 16,
+            completeness: 18,
+            quality: 12,
+        }
+    );
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn votes_close_at_the_milestone_deadline() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let early = h.wallet();
+    let late = h.wallet();
+    h.create_proposal(&researcher, "Deadlines", 1_000, 1, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Deadlines");
+    h.submit(&researcher, &proposal, 1).unwrap();
+    assert_eq!(h.milestone(&proposal, 1).deadline, h.now() + 14 * DAY);
+
+    h.warp(14 * DAY - 1);
+    h.vote(&early, &proposal, 1, true).unwrap();
+
+    h.warp(1);
+    assert_error(
+        h.vote(&late, &proposal, 1, true),
+        ErrorCode::ValidationDeadlinePassed.into(),
+    );
+    assert_eq!(h.milestone(&proposal, 1).validation_votes, 1);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn changing_a_vote_updates_the_tally() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let validator = h.wallet();
+    h.create_proposal(&researcher, "Recount", 1_000, 1, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Recount");
+    h.submit(&researcher, &proposal, 1).unwrap();
+
+    h.vote(&validator, &proposal, 1, true).unwrap();
+    assert_eq!(h.milestone(&proposal, 1).validation_votes, 1);
+    h.vote(&validator, &proposal, 1, false).unwrap();
+    assert_eq!(h.milestone(&proposal, 1).validation_votes, 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn evidence_is_locked_once_voting_opens() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let validator = h.wallet();
+    h.create_proposal(&researcher, "Evidence", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Evidence");
+
+    h.add_evidence(&researcher, &proposal, 1, "report").unwrap();
+    h.add_evidence(&researcher, &proposal, 1, "dataset")
+        .unwrap();
+    assert_error(
+        h.add_evidence(&researcher, &proposal, 2, "early"),
+        ErrorCode::InvalidMilestoneOrder.into(),
+    );
+    let evidence: MilestoneEvidence = h.account(&pda::evidence(&proposal, 1).0);
+    assert_eq!(evidence.set_version, 2);
+    assert_eq!(evidence.attachments[1].cid, cid("dataset"));
+
+    h.submit(&researcher, &proposal, 1).unwrap();
+    assert_eq!(h.milestone(&proposal, 1).evidence_version, 2);
+    assert_error(
+        h.add_evidence(&researcher, &proposal, 1, "late"),
+        ErrorCode::EvidenceLocked.into(),
+    );
+
+    h.vote(&validator, &proposal, 1, true).unwrap();
+    let milestone = pda::milestone(&proposal, 1).0;
+    let vote: Vote = h.account(&pda::vote(&validator.pubkey(), &milestone).0);
+    assert_eq!(vote.evidence_version, 2);
+
+    // Milestones submitted without attachments vote on version 0
+    h.submit(&researcher, &proposal, 2).unwrap();
+    assert_eq!(h.milestone(&proposal, 2).evidence_version, 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn commit_reveal_tallies_only_matching_reveals() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let [a, b, c, late, cranker] = [h.wallet(), h.wallet(), h.wallet(), h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Hidden", 1_000, 1, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Hidden");
+    h.submit_with(&researcher, &proposal, 1, true).unwrap();
+    assert_eq!(
+        h.milestone(&proposal, 1).commit_deadline,
+        h.now() + COMMIT_PHASE_SECONDS
+    );
+    assert_error(
+        h.vote(&a, &proposal, 1, true),
+        ErrorCode::CommitRevealVoting.into(),
+    );
+
+    // Commitments are hidden and don't move the tally
+    h.commit(&a, &proposal, 1, true).unwrap();
+    h.commit(&b, &proposal, 1, true).unwrap();
+    h.commit(&c, &proposal, 1, false).unwrap();
+    let milestone = h.milestone(&proposal, 1);
+    assert_eq!((milestone.commits, milestone.validation_votes), (3, 0));
+    assert_error(
+        h.reveal(&a, &proposal, 1, true),
+        ErrorCode::RevealPhaseNotOpen.into(),
+    );
+
+    h.warp(COMMIT_PHASE_SECONDS);
+    assert_error(
+        h.commit(&late, &proposal, 1, true),
+        ErrorCode::CommitPhaseClosed.into(),
+    );
+    assert_error(
+        h.reveal(&a, &proposal, 1, false),
+        ErrorCode::CommitmentMismatch.into(),
+    );
+    h.reveal(&a, &proposal, 1, true).unwrap();
+    assert_eq!(h.milestone(&proposal, 1).status, MilestoneStatus::Active);
+    h.reveal(&b, &proposal, 1, true).unwrap();
+    let milestone = h.milestone(&proposal, 1);
+    assert_eq!(milestone.status, MilestoneStatus::Validated);
+    assert_eq!((milestone.reveals, milestone.rejection_votes), (2, 0));
+
+    // `c` never reveals and forfeits the vote account once the window ends
+    let c_vote = pda::vote(&c.pubkey(), &pda::milestone(&proposal, 1).0).0;
+    assert_error(
+        h.forfeit(&cranker, &proposal, 1, &c.pubkey()),
+        ErrorCode::RevealPhaseOpen.into(),
+    );
+    h.warp(14 * DAY - COMMIT_PHASE_SECONDS);
+    assert_error(
+        h.forfeit(&cranker, &proposal, 1, &a.pubkey()),
+        ErrorCode::VoteAlreadyRevealed.into(),
+    );
+    let rent = h.svm.get_account(&c_vote).unwrap().lamports;
+    let before = h.svm.get_balance(&cranker.pubkey()).unwrap();
+    h.forfeit(&cranker, &proposal, 1, &c.pubkey()).unwrap();
+    assert!(h
+        .svm
+        .get_account(&c_vote)
+        .is_none_or(|account| account.lamports == 0));
+    assert!(h.svm.get_balance(&cranker.pubkey()).unwrap() > before + rent - 10_000);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn funds_are_released_once_per_validated_milestone() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let contributor = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Release", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Release");
+    h.fund(&contributor, &proposal, 1_000).unwrap();
+    h.submit(&researcher, &proposal, 1).unwrap();
+
+    h.vote(&validators[0], &proposal, 1, true).unwrap();
+    assert_error(
+        h.release(&researcher, &proposal, 1),
+        ErrorCode::MilestoneNotValidated.into(),
+    );
+
+    h.vote(&validators[1], &proposal, 1, true).unwrap();
+    h.release(&researcher, &proposal, 1).unwrap();
+    assert_eq!(h.escrow_balance(&proposal), 1_100 - 500);
+    assert_error(
+        h.release(&researcher, &proposal, 1),
+        ErrorCode::FundsAlreadyReleased.into(),
+    );
+    assert!(h.proposal(&proposal).status == ProposalStatus::Active);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn the_proposal_completes_once_every_milestone_is_released() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let contributor = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Out of order", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Out of order");
+    h.fund(&contributor, &proposal, 1_000).unwrap();
+    h.submit(&researcher, &proposal, 1).unwrap();
+    h.submit(&researcher, &proposal, 2).unwrap();
+
+    // The last milestone is validated first, the first one is still owed
+    for validator in &validators {
+        h.vote(validator, &proposal, 2, true).unwrap();
+    }
+    let account = h.token_account(&researcher.pubkey());
+    let before = h.balance(&account);
+    h.release(&researcher, &proposal, 2).unwrap();
+    assert_eq!(h.balance(&account) - before, 500);
+    let state = h.proposal(&proposal);
+    assert!(state.status == ProposalStatus::Active);
+    assert_eq!(state.milestones_released, Some(1));
+    assert_eq!(h.escrow_balance(&proposal), 1_100 - 500);
+
+    for validator in &validators {
+        h.vote(validator, &proposal, 1, true).unwrap();
+    }
+    let before = h.balance(&account);
+    h.release(&researcher, &proposal, 1).unwrap();
+    assert_eq!(h.balance(&account) - before, 500 + 100);
+    assert!(h.proposal(&proposal).status == ProposalStatus::Completed);
+    assert_eq!(h.escrow_balance(&proposal), 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn contributors_can_challenge_a_validated_milestone() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let stranger = h.wallet();
+    let cranker = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    for title in ["Upheld", "Dismissed"] {
+        h.create_proposal_with_challenge_period(&researcher, title, 1_000, 2, 100, 10 * DAY)
+            .unwrap();
+        let proposal = proposal_address(&researcher, title);
+        h.fund(&alice, &proposal, 1_000).unwrap();
+        h.submit(&researcher, &proposal, 1).unwrap();
+        for validator in &validators {
+            h.vote(validator, &proposal, 1, true).unwrap();
+        }
+    }
+    let upheld = proposal_address(&researcher, "Upheld");
+    let dismissed = proposal_address(&researcher, "Dismissed");
+
+    // Funds wait for the challenge period, only contributors can challenge
+    assert_error(
+        h.release(&researcher, &upheld, 1),
+        ErrorCode::ChallengePeriodActive.into(),
+    );
+    assert!(h.challenge(&stranger, &upheld, 1).is_err());
+
+    // The bond is 5% of the milestone's payout
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.challenge(&alice, &upheld, 1).unwrap();
+    assert_eq!(before - h.balance(&alice_account), 25);
+    assert!(h.milestone(&upheld, 1).status == MilestoneStatus::Disputed);
+    assert_error(
+        h.release(&researcher, &upheld, 1),
+        ErrorCode::MilestoneNotValidated.into(),
+    );
+
+    // Validators vote again until the dispute deadline
+    for validator in &validators {
+        h.vote(validator, &upheld, 1, false).unwrap();
+    }
+    assert_error(
+        h.resolve(&cranker, &upheld, 1),
+        ErrorCode::DisputeOpen.into(),
+    );
+    h.challenge(&alice, &dismissed, 1).unwrap();
+    h.warp(7 * DAY);
+
+    // Upheld: the milestone fails and the bond is returned
+    h.resolve(&cranker, &upheld, 1).unwrap();
+    assert!(h.milestone(&upheld, 1).status == MilestoneStatus::Failed);
+    assert_eq!(h.balance(&alice_account), before);
+
+    // Dismissed: the researcher keeps the bond and the funds are released,
+    // without waiting out the rest of the challenge period
+    let researcher_account = h.token_account(&researcher.pubkey());
+    let before = h.balance(&researcher_account);
+    h.resolve(&cranker, &dismissed, 1).unwrap();
+    assert!(h.milestone(&dismissed, 1).status == MilestoneStatus::Validated);
+    assert_eq!(h.balance(&researcher_account) - before, 25);
+    assert_error(
+        h.challenge(&alice, &dismissed, 1),
+        ErrorCode::AlreadyChallenged.into(),
+    );
+    h.release(&researcher, &dismissed, 1).unwrap();
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn authority_changes_hands_in_two_steps() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let multisig = h.wallet();
+    let payer = h.wallet();
+    let stranger = h.wallet();
+    let member = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Handover", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Handover");
+    h.fund(&stranger, &proposal, 1_000).unwrap();
+    let ix = instructions::update_team(
+        &researcher.pubkey(),
+        &proposal,
+        vec![
+            TeamMember {
+                wallet: researcher.pubkey(),
+                share_bps: 6_000,
+            },
+            TeamMember {
+                wallet: member.pubkey(),
+                share_bps: 4_000,
+            },
+        ],
+        &[],
+    );
+    h.send(&researcher, &[ix], &[]).unwrap();
+
+    // Only the authority proposes, only the pending authority accepts
+    assert!(h
+        .propose_authority(&stranger, &proposal, Some(stranger.pubkey()))
+        .is_err());
+    assert_error(
+        h.accept_authority(&multisig, &proposal),
+        ErrorCode::NoPendingAuthority.into(),
+    );
+    h.propose_authority(&researcher, &proposal, Some(multisig.pubkey()))
+        .unwrap();
+    assert_error(
+        h.accept_authority(&stranger, &proposal),
+        ErrorCode::NotPendingAuthority.into(),
+    );
+
+    // Cancelling leaves nothing to accept
+    h.propose_authority(&researcher, &proposal, None).unwrap();
+    assert_error(
+        h.accept_authority(&multisig, &proposal),
+        ErrorCode::NoPendingAuthority.into(),
+    );
+    h.propose_authority(&researcher, &proposal, Some(multisig.pubkey()))
+        .unwrap();
+    h.accept_authority(&multisig, &proposal).unwrap();
+
+    // The address stays and so does the roster, only `update_team` changes it
+    let state = h.proposal(&proposal);
+    assert_eq!(state.researcher, researcher.pubkey());
+    assert_eq!(state.authority, multisig.pubkey());
+    assert_eq!(state.pending_authority, None);
+    assert!(!state.is_team_member(&multisig.pubkey()));
+    assert!(state.team[0].wallet == researcher.pubkey() && state.team[0].share_bps == 6_000);
+    assert!(state.team[1].wallet == member.pubkey() && state.team[1].share_bps == 4_000);
+
+    // The old researcher is locked out, a separate payer covers the rent
+    assert!(h.submit(&researcher, &proposal, 1).is_err());
+    let rent = h.lamports(&payer.pubkey());
+    h.submit_paid_by(&multisig, &payer, &proposal, 1).unwrap();
+    assert!(h.lamports(&payer.pubkey()) < rent);
+    for validator in &validators {
+        h.vote(validator, &proposal, 1, true).unwrap();
+    }
+
+    // The shares still go to the roster, the new authority only gets the dust
+    let lead = h.token_account(&researcher.pubkey());
+    let account = h.token_account(&multisig.pubkey());
+    let (lead_before, before) = (h.balance(&lead), h.balance(&account));
+    h.release(&multisig, &proposal, 1).unwrap();
+    assert_eq!(h.balance(&lead) - lead_before, 300);
+    assert_eq!(h.balance(&account) - before, 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn penalties_escalate_until_the_proposal_fails() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let authority = h.wallet();
+    let treasury = h.wallet();
+    let treasury_account = h.token_account(&treasury.pubkey());
+    h.create_proposal(&researcher, "Penalties", 1_000, 1, 200)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Penalties");
+    h.submit(&researcher, &proposal, 1).unwrap();
+
+    // Only failed milestones can be penalized
+    assert!(h
+        .penalize(&authority, &proposal, 1, &treasury_account)
+        .is_err());
+    reject(&mut h, &proposal, 1);
+
+    // 5%, 10% and 15% of the initial deposit, then whatever is left
+    let mut paid = 0;
+    for (expected, remaining) in [(10, 190), (20, 170), (30, 140), (140, 0)] {
+        h.penalize(&authority, &proposal, 1, &treasury_account)
+            .unwrap();
+        paid += expected;
+        assert_eq!(h.balance(&treasury_account), STARTING_BALANCE + paid);
+        assert_eq!(h.proposal(&proposal).current_security_deposit, remaining);
+    }
+
+    let state = h.proposal(&proposal);
+    assert_eq!(state.penalty_counter, 4);
+    assert!(state.status == ProposalStatus::Failed);
+    assert_eq!(h.escrow_balance(&proposal), 0);
+    assert_error(
+        h.penalize(&authority, &proposal, 1, &treasury_account),
+        ErrorCode::MaxPenaltiesReached.into(),
+    );
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn refunds_share_what_is_left_once_the_proposal_fails() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    let treasury = h.wallet();
+    let treasury_account = h.token_account(&treasury.pubkey());
+    h.create_proposal(&researcher, "Refunds", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Refunds");
+    h.fund(&alice, &proposal, 600).unwrap();
+    h.fund(&bob, &proposal, 400).unwrap();
+
+    // The first milestone is paid out, the second one fails
+    h.submit(&researcher, &proposal, 1).unwrap();
+    for validator in &validators {
+        h.vote(validator, &proposal, 1, true).unwrap();
+    }
+    h.release(&researcher, &proposal, 1).unwrap();
+    h.submit(&researcher, &proposal, 2).unwrap();
+    reject(&mut h, &proposal, 2);
+
+    assert_error(
+        h.refund(&alice, &proposal),
+        ErrorCode::ProposalNotFailed.into(),
+    );
+    penalize_until_failed(&mut h, &proposal, 2, &treasury_account);
+    // The whole deposit went to the treasury
+    let escrow = h.escrow_balance(&proposal);
+    assert_eq!(escrow, 1_100 - 500 - 100);
+
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.refund(&alice, &proposal).unwrap();
+    let refund = h.balance(&alice_account) - before;
+    assert_eq!(refund, 600 * escrow / 1_000);
+    assert_eq!(h.escrow_balance(&proposal), escrow - refund);
+    assert_error(
+        h.refund(&alice, &proposal),
+        ErrorCode::AlreadyWithdrawn.into(),
+    );
+
+    // The last contributor takes whatever is left
+    let bob_account = h.token_account(&bob.pubkey());
+    let before = h.balance(&bob_account);
+    h.refund(&bob, &proposal).unwrap();
+    assert_eq!(h.balance(&bob_account) - before, escrow - refund);
+    assert_eq!(h.escrow_balance(&proposal), 0);
+
+    // Only recorded contributors can withdraw
+    let stranger = h.wallet();
+    assert!(h.refund(&stranger, &proposal).is_err());
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn cancelling_forfeits_part_of_the_deposit_to_contributors() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let researcher_account = h.token_account(&researcher.pubkey());
+    h.create_proposal(&researcher, "Early exit", 1_000, 2, 200)
+        .unwrap();
+    h.create_proposal(&researcher, "Late exit", 1_000, 2, 200)
+        .unwrap();
+    let early = proposal_address(&researcher, "Early exit");
+    let late = proposal_address(&researcher, "Late exit");
+    h.fund(&alice, &early, 500).unwrap();
+    h.fund(&alice, &late, 500).unwrap();
+
+    // Only the researcher can cancel
+    assert!(h.cancel(&alice, &early).is_err());
+
+    // Before any milestone 10% of the deposit stays with the contributors
+    let before = h.balance(&researcher_account);
+    h.cancel(&researcher, &early).unwrap();
+    assert_eq!(h.balance(&researcher_account) - before, 180);
+    assert!(h.proposal(&early).status == ProposalStatus::Failed);
+    assert_eq!(h.proposal(&early).current_security_deposit, 0);
+    assert_eq!(h.escrow_balance(&early), 520);
+    assert_error(
+        h.cancel(&researcher, &early),
+        ErrorCode::ProposalNotActive.into(),
+    );
+
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.refund(&alice, &early).unwrap();
+    assert_eq!(h.balance(&alice_account) - before, 520);
+
+    // Once a milestone is submitted half of it does
+    h.submit(&researcher, &late, 1).unwrap();
+    let before = h.balance(&researcher_account);
+    h.cancel(&researcher, &late).unwrap();
+    assert_eq!(h.balance(&researcher_account) - before, 100);
+    assert_eq!(h.escrow_balance(&late), 600);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn closing_a_finished_proposal_returns_rent_to_its_payers() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let validator = h.wallet();
+    let cranker = h.wallet();
+    h.create_proposal(&researcher, "Wind down", 1_000, 2, 200)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Wind down");
+    h.fund(&alice, &proposal, 300).unwrap();
+    h.fund(&bob, &proposal, 200).unwrap();
+    h.add_evidence(&researcher, &proposal, 1, "notebook")
+        .unwrap();
+    h.submit(&researcher, &proposal, 1).unwrap();
+    h.vote(&validator, &proposal, 1, true).unwrap();
+
+    // Nothing can be closed while the proposal is running
+    assert_error(
+        h.close_milestone(&cranker, &proposal, &researcher.pubkey(), 1),
+        ErrorCode::ProposalNotClosable.into(),
+    );
+    h.cancel(&researcher, &proposal).unwrap();
+
+    // Rent goes back to whoever paid it, not to the cranker
+    assert_error(
+        h.close_milestone(&cranker, &proposal, &cranker.pubkey(), 1),
+        ErrorCode::InvalidRentRecipient.into(),
+    );
+    let milestone = pda::milestone(&proposal, 1).0;
+    let vote = pda::vote(&validator.pubkey(), &milestone).0;
+    let evidence = pda::evidence(&proposal, 1).0;
+    // Votes close against their milestone, so it waits for them
+    assert_error(
+        h.close_milestone(&cranker, &proposal, &researcher.pubkey(), 1),
+        ErrorCode::MilestoneHasOpenVotes.into(),
+    );
+    let rent = h.lamports(&vote);
+    let before = h.lamports(&validator.pubkey());
+    h.close_vote(&cranker, &proposal, 1, &validator.pubkey())
+        .unwrap();
+    assert_eq!(h.lamports(&validator.pubkey()) - before, rent);
+    assert_eq!(h.lamports(&vote), 0);
+
+    // The milestone takes its evidence with it
+    let rent = h.lamports(&milestone) + h.lamports(&evidence);
+    let before = h.lamports(&researcher.pubkey());
+    h.close_milestone(&cranker, &proposal, &researcher.pubkey(), 1)
+        .unwrap();
+    assert_eq!(h.lamports(&researcher.pubkey()) - before, rent);
+    assert_eq!(h.lamports(&evidence), 0);
+
+    // Contributor accounts are the claim on the escrow until refunded
+    assert_error(
+        h.close_contributor(&cranker, &proposal, &alice.pubkey()),
+        ErrorCode::RefundNotWithdrawn.into(),
+    );
+    h.refund(&alice, &proposal).unwrap();
+    h.refund(&bob, &proposal).unwrap();
+    assert_error(
+        h.close(&researcher, &proposal),
+        ErrorCode::ProposalHasOpenAccounts.into(),
+    );
+    let contributors = [alice.pubkey(), bob.pubkey()];
+    let rents: Vec<u64> = contributors
+        .iter()
+        .map(|wallet| h.lamports(&pda::contributor(wallet, &proposal).0))
+        .collect();
+    let before: Vec<u64> = contributors.iter().map(|w| h.lamports(w)).collect();
+    let closables: Vec<Closable> = contributors
+        .iter()
+        .map(|wallet| Closable::Contributor {
+            wallet: *wallet,
+            payer: *wallet,
+        })
+        .collect();
+    h.close_accounts(&cranker, &proposal, &closables).unwrap();
+    for ((wallet, rent), before) in contributors.iter().zip(rents).zip(before) {
+        assert_eq!(h.lamports(wallet) - before, rent);
+    }
+
+    // With everything refunded the escrow goes with the proposal. The
+    // researcher paid for both, so gets the rent even after a handover
+    assert_eq!(h.escrow_balance(&proposal), 0);
+    let successor = h.wallet();
+    h.propose_authority(&researcher, &proposal, Some(successor.pubkey()))
+        .unwrap();
+    h.accept_authority(&successor, &proposal).unwrap();
+    let rent = h.lamports(&proposal) + h.lamports(&pda::escrow(&proposal).0);
+    let before = h.lamports(&researcher.pubkey());
+    h.close(&successor, &proposal).unwrap();
+    assert_eq!(h.lamports(&researcher.pubkey()) - before, rent);
+    assert_eq!(h.lamports(&pda::escrow(&proposal).0), 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn status_checks_guard_each_instruction() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let validator = h.wallet();
+    let treasury = h.wallet();
+    let treasury_account = h.token_account(&treasury.pubkey());
+    h.create_proposal(&researcher, "Guards", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Guards");
+    assert!(h.proposal(&proposal).status == ProposalStatus::Active);
+
+    assert_error(
+        h.close(&researcher, &proposal),
+        ErrorCode::ProposalNotClosable.into(),
+    );
+
+    // Once a milestone has a verdict, voting on it is over
+    h.submit(&researcher, &proposal, 1).unwrap();
+    let validators = reject(&mut h, &proposal, 1);
+    assert_error(
+        h.vote(&validator, &proposal, 1, true),
+        ErrorCode::MilestoneNotActive.into(),
+    );
+
+    // A failed proposal takes no new milestones and can be closed
+    penalize_until_failed(&mut h, &proposal, 1, &treasury_account);
+    assert_error(
+        h.submit(&researcher, &proposal, 2),
+        ErrorCode::ProposalNotActive.into(),
+    );
+    assert_error(
+        h.close(&researcher, &proposal),
+        ErrorCode::ProposalHasOpenAccounts.into(),
+    );
+    // Votes may close in the same batch as their milestone, ahead of it
+    let closables: Vec<Closable> = validators
+        .iter()
+        .map(|validator| Closable::Vote {
+            milestone_number: 1,
+            validator: *validator,
+        })
+        .chain([Closable::Milestone {
+            number: 1,
+            payer: researcher.pubkey(),
+        }])
+        .collect();
+    h.close_accounts(&validator, &proposal, &closables).unwrap();
+    h.close(&researcher, &proposal).unwrap();
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn matching_round_rewards_broad_support() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let sponsor = h.wallet();
+    let crowd: Vec<Keypair> = (0..4).map(|_| h.wallet()).collect();
+    let whale = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+
+    h.create_proposal(&researcher, "Crowd", 1_000, 2, 100)
+        .unwrap();
+    h.create_proposal(&researcher, "Whale", 1_000, 2, 100)
+        .unwrap();
+    let crowd_proposal = proposal_address(&researcher, "Crowd");
+    let whale_proposal = proposal_address(&researcher, "Whale");
+
+    h.create_round(&sponsor, 1, 1_000, 7, &[crowd_proposal, whale_proposal])
+        .unwrap();
+    let round = pda::funding_round(&sponsor.pubkey(), 1).0;
+    assert_eq!(h.balance(&pda::round_vault(&round).0), 1_000);
+
+    for backer in &crowd {
+        h.fund_in_round(backer, &crowd_proposal, 100, &round)
+            .unwrap();
+    }
+    h.fund_in_round(&whale, &whale_proposal, 200, &round)
+        .unwrap();
+    h.fund_in_round(&whale, &whale_proposal, 200, &round)
+        .unwrap();
+
+    let state: anchor::FundingRound = h.account(&round);
+    assert_eq!(state.entries[0].contributors, 4);
+    assert_eq!(state.entries[0].sqrt_sum, 40);
+    assert_eq!(state.entries[1].contributors, 1);
+    assert_eq!(state.entries[1].contributed, 400);
+    assert_eq!(state.entries[1].sqrt_sum, 20);
+
+    let cranker = h.wallet();
+    assert_error(
+        h.distribute(&cranker, &round),
+        ErrorCode::RoundNotEnded.code(),
+    );
+
+    h.warp(8 * DAY);
+    assert_error(
+        h.fund_in_round(&crowd[0], &crowd_proposal, 100, &round),
+        ErrorCode::RoundNotOpen.code(),
+    );
+
+    // One contributor earns no match, the whole pool goes to the crowd
+    h.distribute(&cranker, &round).unwrap();
+    assert_eq!(h.escrow_balance(&crowd_proposal), 100 + 400 + 1_000);
+    assert_eq!(h.escrow_balance(&whale_proposal), 100 + 400);
+    assert_eq!(h.proposal(&crowd_proposal).matched_amount, 1_000);
+    assert_eq!(h.balance(&pda::round_vault(&round).0), 0);
+    assert_error(
+        h.distribute(&cranker, &round),
+        ErrorCode::RoundAlreadyDistributed.code(),
+    );
+
+    // The match is paid out alongside the milestones
+    let researcher_account = h.token_account(&researcher.pubkey());
+    let balance = h.balance(&researcher_account);
+    h.submit(&researcher, &crowd_proposal, 1).unwrap();
+    for validator in &validators {
+        h.vote(validator, &crowd_proposal, 1, true).unwrap();
+    }
+    h.release(&researcher, &crowd_proposal, 1).unwrap();
+    assert_eq!(h.balance(&researcher_account) - balance, 500 + 500);
+    assert_eq!(h.proposal(&crowd_proposal).matched_released, 500);
+}
+
+/// The `amount` recorded in a contributor's receipt metadata.
+fn receipt_amount(h: &Harness, wallet: &Pubkey, proposal: &Pubkey) -> String {
+    let receipt = pda::receipt(&pda::contributor(wallet, proposal).0).0;
+    let account = h.svm.get_account(&receipt).unwrap();
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    metadata
+        .additional_metadata
+        .iter()
+        .find(|(key, _)| key == anchor::RECEIPT_AMOUNT_KEY)
+        .map(|(_, value)| value.clone())
+        .unwrap()
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn sponsors_fund_on_behalf_of_a_beneficiary() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let foundation = h.wallet();
+    let alice = h.wallet();
+    let dao_treasury = Pubkey::new_unique();
+    h.create_proposal(&researcher, "Sponsored", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Sponsored");
+
+    assert_error(
+        h.fund_for(
+            &foundation,
+            &alice.pubkey(),
+            &proposal,
+            300,
+            Some(&"x".repeat(anchor::MAX_SPONSOR_MEMO_LEN + 1)),
+        ),
+        ErrorCode::MemoTooLong.into(),
+    );
+    let foundation_account = h.token_account(&foundation.pubkey());
+    let before = h.balance(&foundation_account);
+    h.fund_for(
+        &foundation,
+        &alice.pubkey(),
+        &proposal,
+        300,
+        Some("grant #12"),
+    )
+    .unwrap();
+    h.fund_for(&foundation, &dao_treasury, &proposal, 200, None)
+        .unwrap();
+    h.fund(&alice, &proposal, 100).unwrap();
+    assert_eq!(before - h.balance(&foundation_account), 500);
+
+    // The beneficiaries hold the contributions and their receipts
+    assert!(h
+        .svm
+        .get_account(&pda::contributor(&foundation.pubkey(), &proposal).0)
+        .is_none());
+    assert_eq!(h.contributor(&alice.pubkey(), &proposal).amount, 400);
+    assert_eq!(h.contributor(&dao_treasury, &proposal).wallet, dao_treasury);
+    assert_eq!(
+        h.contributor(&alice.pubkey(), &proposal).sponsor,
+        Some(foundation.pubkey())
+    );
+    assert_eq!(receipt_amount(&h, &alice.pubkey(), &proposal), "400");
+    assert_eq!(receipt_amount(&h, &dao_treasury, &proposal), "200");
+    let state = h.proposal(&proposal);
+    assert_eq!((state.amount_raised, state.contributors_count), (600, 2));
+
+    // Refunds go to the beneficiary, not the sponsor
+    h.cancel(&researcher, &proposal).unwrap();
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.refund(&alice, &proposal).unwrap();
+    assert!(h.balance(&alice_account) - before >= 400);
+    assert!(h.refund(&foundation, &proposal).is_err());
+
+    // A treasury that never signs still gets its refund, anyone can send it
+    let ix = anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+        &foundation.pubkey(),
+        &dao_treasury,
+        &h.mint,
+        &anchor_spl::token::ID,
+    );
+    h.send(&foundation, &[ix], &[]).unwrap();
+    h.refund_for(&foundation, &dao_treasury, &proposal).unwrap();
+    assert!(h.balance(&h.token_account(&dao_treasury)) >= 200);
+    assert_eq!(h.contributor(&dao_treasury, &proposal).amount, 0);
+
+    // The sponsor paid for the contributor account and gets its rent back
+    let contributor = pda::contributor(&alice.pubkey(), &proposal).0;
+    assert_eq!(
+        h.contributor(&alice.pubkey(), &proposal).payer,
+        foundation.pubkey()
+    );
+    let rent = h.lamports(&contributor);
+    let before = h.lamports(&foundation.pubkey());
+    h.close_contributor(&alice, &proposal, &alice.pubkey())
+        .unwrap();
+    assert_eq!(h.lamports(&foundation.pubkey()) - before, rent);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn pledges_pay_installments_until_complete() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let cranker = h.wallet();
+    h.create_proposal(&researcher, "Pledged", 10_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Pledged");
+    let pledge = pda::pledge(&alice.pubkey(), &proposal).0;
+
+    assert_error(
+        h.pledge(&alice, &proposal, 100, 1),
+        ErrorCode::InvalidPledge.into(),
+    );
+    h.pledge(&alice, &proposal, 100, 3).unwrap();
+    assert_eq!(h.escrow_balance(&proposal), 100);
+    assert_eq!(
+        h.delegate(&h.token_account(&alice.pubkey())),
+        Some((pledge, 200))
+    );
+
+    // Nothing is due until the interval has passed
+    assert_error(
+        h.execute_pledge(&cranker, &proposal, &alice.pubkey()),
+        ErrorCode::PledgeNotDue.into(),
+    );
+    h.warp(anchor::PLEDGE_INTERVAL_SECONDS);
+    h.execute_pledge(&cranker, &proposal, &alice.pubkey())
+        .unwrap();
+    assert_eq!(h.contributor(&alice.pubkey(), &proposal).amount, 200);
+    assert_eq!(receipt_amount(&h, &alice.pubkey(), &proposal), "200");
+
+    // The last installment closes the pledge back to the contributor
+    let before = h.lamports(&alice.pubkey());
+    h.warp(anchor::PLEDGE_INTERVAL_SECONDS);
+    h.execute_pledge(&cranker, &proposal, &alice.pubkey())
+        .unwrap();
+    assert_eq!(h.escrow_balance(&proposal), 300);
+    assert_eq!(h.proposal(&proposal).amount_raised, 300);
+    assert_eq!(h.lamports(&pledge), 0);
+    assert!(h.lamports(&alice.pubkey()) > before);
+    assert_eq!(h.delegate(&h.token_account(&alice.pubkey())), None);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn pledges_stop_when_cancelled_or_the_proposal_ends() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let cranker = h.wallet();
+    h.create_proposal(&researcher, "Stopped", 10_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Stopped");
+
+    // Cancelling revokes the delegate and keeps what was already paid
+    h.pledge(&alice, &proposal, 100, 5).unwrap();
+    h.cancel_pledge(&alice, &proposal).unwrap();
+    assert_eq!(h.delegate(&h.token_account(&alice.pubkey())), None);
+    assert_eq!(h.lamports(&pda::pledge(&alice.pubkey(), &proposal).0), 0);
+    assert_eq!(h.escrow_balance(&proposal), 100);
+    h.warp(anchor::PLEDGE_INTERVAL_SECONDS);
+    assert!(h
+        .execute_pledge(&cranker, &proposal, &alice.pubkey())
+        .is_err());
+
+    // Once the proposal is no longer active the crank pulls nothing, the
+    // pledge stays open until bob revokes what is left of the allowance
+    h.pledge(&bob, &proposal, 100, 5).unwrap();
+    h.cancel(&researcher, &proposal).unwrap();
+    let bob_pledge = pda::pledge(&bob.pubkey(), &proposal).0;
+    let bob_account = h.token_account(&bob.pubkey());
+    let before = h.balance(&bob_account);
+    h.warp(anchor::PLEDGE_INTERVAL_SECONDS);
+    h.execute_pledge(&cranker, &proposal, &bob.pubkey())
+        .unwrap();
+    assert_eq!(h.balance(&bob_account), before);
+    assert_eq!(h.delegate(&bob_account), Some((bob_pledge, 400)));
+    let ended = h.account::<anchor::Pledge>(&bob_pledge).ended;
+    assert_eq!(ended, Some(PledgeEnd::ProposalInactive));
+    // It only ends once, later cranks are turned away
+    h.warp(anchor::PLEDGE_INTERVAL_SECONDS);
+    assert_error(
+        h.execute_pledge(&cranker, &proposal, &bob.pubkey()),
+        ErrorCode::PledgeAlreadyEnded.into(),
+    );
+    h.cancel_pledge(&bob, &proposal).unwrap();
+    assert_eq!(h.delegate(&bob_account), None);
+    assert_eq!(h.lamports(&bob_pledge), 0);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn allowlisted_proposals_only_accept_listed_wallets_up_to_their_cap() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let mallory = h.wallet();
+    let allowlist = Allowlist::new([(alice.pubkey(), 500), (bob.pubkey(), 300)]).unwrap();
+    h.create_proposal_with_allowlist(&researcher, "Clinical", 1_000, 2, 100, allowlist.root())
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Clinical");
+    assert_eq!(h.proposal(&proposal).allowlist_root, Some(allowlist.root()));
+
+    // Listed wallets contribute up to their cap, in as many parts as they like
+    let proof = allowlist.proof(&alice.pubkey()).unwrap();
+    h.fund_allowlisted(&alice, &proposal, 300, proof.clone())
+        .unwrap();
+    h.fund_allowlisted(&alice, &proposal, 200, proof.clone())
+        .unwrap();
+    assert_error(
+        h.fund_allowlisted(&alice, &proposal, 1, proof.clone()),
+        ErrorCode::AllowlistCapExceeded.into(),
+    );
+
+    // Proofs are bound to their wallet and cap
+    assert_error(
+        h.fund_allowlisted(&mallory, &proposal, 100, proof),
+        ErrorCode::NotAllowlisted.into(),
+    );
+    let mut inflated = allowlist.proof(&bob.pubkey()).unwrap();
+    inflated.cap = 1_000;
+    assert_error(
+        h.fund_allowlisted(&bob, &proposal, 400, inflated),
+        ErrorCode::NotAllowlisted.into(),
+    );
+
+    // Every other way in needs a proof too
+    assert_error(
+        h.fund(&bob, &proposal, 100),
+        ErrorCode::NotAllowlisted.into(),
+    );
+    assert_error(
+        h.fund_for(&mallory, &bob.pubkey(), &proposal, 100, None),
+        ErrorCode::NotAllowlisted.into(),
+    );
+    assert_error(
+        h.pledge(&bob, &proposal, 100, 2),
+        ErrorCode::NotAllowlisted.into(),
+    );
+    assert_eq!(h.proposal(&proposal).amount_raised, 500);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn contributions_respect_per_contributor_limits() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let limits = ContributionLimits {
+        min: 50,
+        max: 400,
+        hard_cap: false,
+    };
+    assert_error(
+        h.create_proposal_with_limits(
+            &researcher,
+            "Inverted",
+            1_000,
+            2,
+            100,
+            ContributionLimits { min: 500, ..limits },
+        ),
+        ErrorCode::InvalidContributionLimits.into(),
+    );
+    h.create_proposal_with_limits(&researcher, "Bounded", 1_000, 2, 100, limits)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Bounded");
+
+    // The bounds apply to the contributor's total, not to each contribution
+    assert_error(
+        h.fund(&alice, &proposal, 49),
+        ErrorCode::ContributionBelowMinimum.into(),
+    );
+    h.fund(&alice, &proposal, 50).unwrap();
+    h.fund(&alice, &proposal, 10).unwrap();
+    assert_error(
+        h.fund(&alice, &proposal, 341),
+        ErrorCode::ContributionAboveMaximum.into(),
+    );
+    h.fund(&alice, &proposal, 340).unwrap();
+    assert_eq!(h.contributor(&alice.pubkey(), &proposal).amount, 400);
+
+    // A pledge has to fit the maximum as a whole
+    let bob = h.wallet();
+    assert_error(
+        h.pledge(&bob, &proposal, 100, 5),
+        ErrorCode::ContributionAboveMaximum.into(),
+    );
+    h.pledge(&bob, &proposal, 100, 4).unwrap();
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn hard_caps_trim_contributions_beyond_the_goal() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let carol = h.wallet();
+    let cranker = h.wallet();
+    let limits = ContributionLimits {
+        hard_cap: true,
+        ..ContributionLimits::default()
+    };
+    h.create_proposal_with_limits(&researcher, "Capped", 1_000, 2, 100, limits)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Capped");
+
+    h.pledge(&carol, &proposal, 100, 3).unwrap();
+    h.fund(&alice, &proposal, 600).unwrap();
+
+    // Only what the goal still needs leaves bob's account
+    let bob_account = h.token_account(&bob.pubkey());
+    let before = h.balance(&bob_account);
+    h.fund(&bob, &proposal, 500).unwrap();
+    assert_eq!(before - h.balance(&bob_account), 300);
+    assert_eq!(h.contributor(&bob.pubkey(), &proposal).amount, 300);
+    assert_eq!(receipt_amount(&h, &bob.pubkey(), &proposal), "300");
+    let state = h.proposal(&proposal);
+    assert_eq!(state.amount_raised, state.amount_asked);
+    assert_eq!(h.escrow_balance(&proposal), 100 + 1_000);
+
+    // Once the goal is met contributions are refused and pledges end
+    assert_error(
+        h.fund(&alice, &proposal, 1),
+        ErrorCode::FundingGoalReached.into(),
+    );
+    h.execute_pledge(&cranker, &proposal, &carol.pubkey())
+        .unwrap();
+    assert_eq!(h.contributor(&carol.pubkey(), &proposal).amount, 100);
+    h.cancel_pledge(&carol, &proposal).unwrap();
+    assert_eq!(h.lamports(&pda::pledge(&carol.pubkey(), &proposal).0), 0);
+    assert_eq!(h.delegate(&h.token_account(&carol.pubkey())), None);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn overfunding_unlocks_stretch_goals_and_refunds_the_rest() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let cranker = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    let goals = vec![
+        StretchGoal {
+            threshold: 1_300,
+            budget: 200,
+        },
+        StretchGoal {
+            threshold: 2_000,
+            budget: 500,
+        },
+    ];
+
+    // Each threshold has to cover the goal and the budgets before it
+    let unfunded = vec![StretchGoal {
+        threshold: 1_100,
+        budget: 200,
+    }];
+    assert_error(
+        h.create_proposal_with_stretch_goals(&researcher, "Unfunded", 1_000, 2, 100, unfunded),
+        ErrorCode::InvalidStretchGoal.into(),
+    );
+    // Stretch milestones have to be numbered within a u8 too
+    assert_error(
+        h.create_proposal(&researcher, "Too long", 1_000, 253, 100),
+        ErrorCode::InvalidMilestoneCount.into(),
+    );
+    h.create_proposal_with_stretch_goals(&researcher, "Stretch", 1_000, 2, 100, goals)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Stretch");
+
+    h.fund(&alice, &proposal, 900).unwrap();
+    assert_eq!(h.proposal(&proposal).stretch_goals_unlocked, 0);
+    h.fund(&bob, &proposal, 500).unwrap();
+    let state = h.proposal(&proposal);
+    assert_eq!(state.stretch_goals_unlocked, 1);
+    assert_eq!(state.milestone_count(), 3);
+
+    for number in 1..=3 {
+        // The unlocked stretch milestone takes evidence like any other
+        h.add_evidence(&researcher, &proposal, number, "report")
+            .unwrap();
+        h.submit(&researcher, &proposal, number).unwrap();
+        for validator in &validators {
+            h.vote(validator, &proposal, number, true).unwrap();
+        }
+    }
+    // The second goal was never reached
+    assert_error(
+        h.add_evidence(&researcher, &proposal, 4, "report"),
+        ErrorCode::StretchGoalLocked.into(),
+    );
+    assert_error(
+        h.submit(&researcher, &proposal, 4),
+        ErrorCode::StretchGoalLocked.into(),
+    );
+
+    let researcher_account = h.token_account(&researcher.pubkey());
+    let before = h.balance(&researcher_account);
+    for number in 1..=3 {
+        h.release(&researcher, &proposal, number).unwrap();
+    }
+    // Both base milestones, the unlocked goal's budget and the deposit
+    assert_eq!(
+        h.balance(&researcher_account) - before,
+        500 + 500 + 200 + 100
+    );
+    assert!(h.proposal(&proposal).status == ProposalStatus::Completed);
+
+    // The overfunding the locked goal would have used goes back pro rata
+    assert_eq!(h.escrow_balance(&proposal), 200);
+    assert_error(
+        h.close_contributor(&cranker, &proposal, &alice.pubkey()),
+        ErrorCode::RefundNotWithdrawn.into(),
+    );
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.refund(&alice, &proposal).unwrap();
+    let refund = h.balance(&alice_account) - before;
+    assert_eq!(refund, 900 * 200 / 1_400);
+
+    let bob_account = h.token_account(&bob.pubkey());
+    let before = h.balance(&bob_account);
+    h.refund(&bob, &proposal).unwrap();
+    assert_eq!(h.balance(&bob_account) - before, 200 - refund);
+    assert_eq!(h.escrow_balance(&proposal), 0);
+    h.close_contributor(&cranker, &proposal, &alice.pubkey())
+        .unwrap();
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn overfunding_without_stretch_goals_is_refunded_once_complete() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+    let cranker = h.wallet();
+    let validators = [h.wallet(), h.wallet()];
+    h.create_proposal(&researcher, "Oversubscribed", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Oversubscribed");
+    h.fund(&alice, &proposal, 900).unwrap();
+    h.fund(&bob, &proposal, 400).unwrap();
+
+    for number in 1..=2 {
+        h.submit(&researcher, &proposal, number).unwrap();
+        for validator in &validators {
+            h.vote(validator, &proposal, number, true).unwrap();
+        }
+        h.release(&researcher, &proposal, number).unwrap();
+    }
+    assert!(h.proposal(&proposal).status == ProposalStatus::Completed);
+    assert_eq!(h.escrow_balance(&proposal), 300);
+
+    // Without locked stretch goals the overfunding is still the contributors' claim
+    assert_error(
+        h.close_contributor(&cranker, &proposal, &alice.pubkey()),
+        ErrorCode::RefundNotWithdrawn.into(),
+    );
+    assert_error(
+        h.close_accounts(
+            &cranker,
+            &proposal,
+            &[Closable::Contributor {
+                wallet: bob.pubkey(),
+                payer: bob.pubkey(),
+            }],
+        ),
+        ErrorCode::RefundNotWithdrawn.into(),
+    );
+
+    let alice_account = h.token_account(&alice.pubkey());
+    let before = h.balance(&alice_account);
+    h.refund(&alice, &proposal).unwrap();
+    let refund = h.balance(&alice_account) - before;
+    assert_eq!(refund, 900 * 300 / 1_300);
+    let bob_account = h.token_account(&bob.pubkey());
+    let before = h.balance(&bob_account);
+    h.refund(&bob, &proposal).unwrap();
+    assert_eq!(h.balance(&bob_account) - before, 300 - refund);
+    assert_eq!(h.escrow_balance(&proposal), 0);
+
+    for wallet in [alice.pubkey(), bob.pubkey()] {
+        h.close_contributor(&cranker, &proposal, &wallet).unwrap();
+    }
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn contributors_hold_a_soulbound_receipt() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let bob = h.wallet();
+
+    h.create_proposal(&researcher, "Protein folding", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Protein folding");
+    h.fund(&alice, &proposal, 600).unwrap();
+
+    let receipt = pda::receipt(&pda::contributor(&alice.pubkey(), &proposal).0).0;
+    let account = h.svm.get_account(&receipt).unwrap();
+    assert_eq!(account.owner, spl_token_2022::ID);
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert_eq!(mint.base.supply, 1);
+    assert!(mint.base.mint_authority.is_none());
+    assert!(mint.get_extension::<NonTransferable>().is_ok());
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.mint, receipt);
+    assert_eq!(metadata.symbol, anchor::RECEIPT_SYMBOL);
+    assert!(metadata.additional_metadata.contains(&(
+        anchor::RECEIPT_PROPOSAL_KEY.to_string(),
+        proposal.to_string()
+    )));
+    assert_eq!(receipt_amount(&h, &alice.pubkey(), &proposal), "600");
+
+    // Later contributions update the same receipt
+    h.fund(&alice, &proposal, 400).unwrap();
+    assert_eq!(receipt_amount(&h, &alice.pubkey(), &proposal), "1000");
+
+    // The receipt can't be moved to another wallet
+    let from = pda::receipt_token_account(&alice.pubkey(), &receipt);
+    let to = pda::receipt_token_account(&bob.pubkey(), &receipt);
+    let ixs = [
+        anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+            &alice.pubkey(),
+            &bob.pubkey(),
+            &receipt,
+            &spl_token_2022::ID,
+        ),
+        spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::ID,
+            &from,
+            &receipt,
+            &to,
+            &alice.pubkey(),
+            &[],
+            1,
+            0,
+        )
+        .unwrap(),
+    ];
+    assert!(h.send(&alice, &ixs, &[]).is_err());
+}
+
+/// Rewrites an account in the layout it had before the version header, rent
+/// exempt at the old size.
+fn downgrade_to_unversioned<T: Versioned>(h: &mut Harness, address: &Pubkey) {
+    let mut account = h.svm.get_account(address).unwrap();
+    account.data.remove(8);
+    account.data.truncate(T::UNVERSIONED_SPACE);
+    account.lamports = h
+        .svm
+        .minimum_balance_for_rent_exemption(T::UNVERSIONED_SPACE);
+    h.svm.set_account(*address, account).unwrap();
+}
+
+/// Replaces an account's data with `legacy` behind `T`'s discriminator,
+/// zero-padded to `space`.
+fn write_legacy<T: Discriminator>(
+    h: &mut Harness,
+    address: &Pubkey,
+    legacy: impl AnchorSerialize,
+    space: usize,
+) {
+    let mut account = h.svm.get_account(address).unwrap();
+    account.data = T::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut account.data).unwrap();
+    account.data.resize(space, 0);
+    account.lamports = h.svm.minimum_balance_for_rent_exemption(space);
+    h.svm.set_account(*address, account).unwrap();
+}
+
+/// Rewrites a proposal as created before the version header.
+fn downgrade_proposal_to_v0(h: &mut Harness, address: &Pubkey) {
+    let p = h.proposal(address);
+    let legacy = ProposalV0 {
+        title: p.title,
+        abstract_text: p.abstract_text,
+        researcher: p.researcher,
+        contributors_count: p.contributors_count,
+        initial_security_deposit: p.initial_security_deposit,
+        current_security_deposit: p.current_security_deposit,
+        amount_asked: p.amount_asked,
+        amount_raised: p.amount_raised,
+        funds_escrow: p.funds_escrow,
+        total_milestones: p.total_milestones,
+        current_milestone: p.current_milestone,
+        ipfs_hash: p.ipfs_hash.unwrap().to_string(),
+        ip_nft_mint: p.ip_nft_mint,
+        created_at: p.created_at,
+        updated_at: p.updated_at,
+        status: p.status,
+        penalty_counter: p.penalty_counter,
+    };
+    let space = anchor::Proposal::UNVERSIONED_SPACE;
+    write_legacy::<anchor::Proposal>(h, address, legacy, space);
+}
+
+/// Rewrites a milestone as created before the version header.
+fn downgrade_milestone_to_v0(h: &mut Harness, address: &Pubkey) {
+    let m: anchor::Milestone = h.account(address);
+    let legacy = MilestoneV0 {
+        proposal: m.proposal,
+        milestone_number: m.milestone_number,
+        evidence_hash: m.evidence_hash.unwrap().to_string(),
+        validation_votes: m.validation_votes,
+        total_validators: m.total_validators,
+        funds_released: m.funds_released,
+        deadline: m.deadline,
+        status: m.status,
+        created_at: m.created_at,
+        updated_at: m.updated_at,
+    };
+    let space = anchor::Milestone::UNVERSIONED_SPACE;
+    write_legacy::<anchor::Milestone>(h, address, legacy, space);
+}
+
+/// Rewrites a proposal in the v2 layout, when `ipfs_hash` was text.
+fn downgrade_proposal_to_v2(h: &mut Harness, address: &Pubkey) {
+    let p = h.proposal(address);
+    let legacy = ProposalV2 {
+        version: 2,
+        title: p.title,
+        abstract_text: p.abstract_text,
+        researcher: p.researcher,
+        contributors_count: p.contributors_count,
+        initial_security_deposit: p.initial_security_deposit,
+        current_security_deposit: p.current_security_deposit,
+        amount_asked: p.amount_asked,
+        amount_raised: p.amount_raised,
+        funds_escrow: p.funds_escrow,
+        total_milestones: p.total_milestones,
+        current_milestone: p.current_milestone,
+        ipfs_hash: p.ipfs_hash.unwrap().to_string(),
+        ip_nft_mint: p.ip_nft_mint,
+        created_at: p.created_at,
+        updated_at: p.updated_at,
+        status: p.status,
+        penalty_counter: p.penalty_counter,
+        team: p.team,
+        matched_amount: p.matched_amount,
+        matched_released: p.matched_released,
+    };
+    // Allocated size of v2 proposals
+    write_legacy::<anchor::Proposal>(h, address, legacy, 776);
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn migrates_unversioned_accounts_in_place() {
+    let mut h = Harness::new();
+    let researcher = h.wallet();
+    let alice = h.wallet();
+    let payer = h.wallet();
+
+    h.create_proposal(&researcher, "Protein folding", 1_000, 2, 100)
+        .unwrap();
+    let proposal = proposal_address(&researcher, "Protein folding");
+    h.fund(&alice, &proposal, 600).unwrap();
+    h.submit(&researcher, &proposal, 1).unwrap();
+    let contributor = pda::contributor(&alice.pubkey(), &proposal).0;
+    let milestone = pda::milestone(&proposal, 1).0;
+    let before = h.proposal(&proposal);
+
+    downgrade_proposal_to_v0(&mut h, &proposal);
+    downgrade_milestone_to_v0(&mut h, &milestone);
+    downgrade_to_unversioned::<anchor::Contributor>(&mut h, &contributor);
+
+    let lamports = h.svm.get_balance(&payer.pubkey()).unwrap();
+    for address in [proposal, milestone, contributor] {
+        let ix = instructions::migrate_account(&payer.pubkey(), &address);
+        h.send(&payer, &[ix], &[]).unwrap();
+    }
+
+    let account = h.svm.get_account(&proposal).unwrap();
+    assert_eq!(account.data.len(), anchor::Proposal::SPACE);
+    let after = h.proposal(&proposal);
+    assert_eq!(after.version, anchor::Proposal::VERSION);
+    assert_eq!(after.title, before.title);
+    assert_eq!(after.amount_raised, 600);
+    assert_eq!(after.current_milestone, 1);
+    assert!(after.team == before.team);
+    assert_eq!(after.ipfs_hash, Some(cid("proposal")));
+    assert_eq!(after.authority, researcher.pubkey());
+    assert_eq!(after.pending_authority, None);
+    assert_eq!(after.milestones_released, None);
+    let after = h.milestone(&proposal, 1);
+    assert_eq!(after.version, anchor::Milestone::VERSION);
+    assert_eq!(after.milestone_number, 1);
+    assert_eq!(after.evidence_hash, Some(cid("evidence-1")));
+    assert!(after.status == MilestoneStatus::Active);
+    assert_eq!(after.rejection_votes, 0);
+    assert_eq!(after.payer, Pubkey::default());
+    assert_eq!(h.contributor(&alice.pubkey(), &proposal).amount, 600);
+
+    // The payer covered the accounts' growth on top of the fees
+    let rent = [
+        (anchor::Proposal::SPACE, anchor::Proposal::UNVERSIONED_SPACE),
+        (
+            anchor::Milestone::SPACE,
+            anchor::Milestone::UNVERSIONED_SPACE,
+        ),
+        (
+            anchor::Contributor::SPACE,
+            anchor::Contributor::UNVERSIONED_SPACE,
+        ),
+    ]
+    .iter()
+    .map(|(space, unversioned)| {
+        h.svm.minimum_balance_for_rent_exemption(*space)
+            - h.svm.minimum_balance_for_rent_exemption(*unversioned)
+    })
+    .sum::<u64>();
+    assert!(lamports - h.svm.get_balance(&payer.pubkey()).unwrap() > rent);
+
+    let ix = instructions::migrate_account(&payer.pubkey(), &proposal);
+    assert_error(
+        h.send(&payer, &[ix], &[]),
+        ErrorCode::AccountAlreadyMigrated.code(),
+    );
+
+    // Proposals from between the header and `ContentId` take the v2 path
+    downgrade_proposal_to_v2(&mut h, &proposal);
+    let ix = instructions::migrate_account(&payer.pubkey(), &proposal);
+    h.send(&payer, &[ix], &[]).unwrap();
+    let after = h.proposal(&proposal);
+    assert_eq!(after.version, anchor::Proposal::VERSION);
+    assert!(after.team == before.team);
+    assert_eq!(after.ipfs_hash, Some(cid("proposal")));
+}
+
+#[test]
+#[ignore = "needs target/deploy/anchor.so, run with --ignored after anchor build"]
+fn mints_an_ip_nft_through_metaplex() {
+    let mut h = Harness::new();
+    let payer = h.wallet();
+    let mint = Keypair::new();
+
+    let ix = instructions::mint_ip_nft(
+        &payer.pubkey(),
+        &mint.pubkey(),
+        "Protein folding".to_string(),
+        "FOLD".to_string(),
+        format!("{}/metadata.json", cid("metadata").uri()),
+    );
+    h.send(&payer, &[ix], &[&mint]).unwrap();
+
+    let metadata = h.svm.get_account(&pda::metadata(&mint.pubkey()).0).unwrap();
+    assert_eq!(metadata.owner, mpl_token_metadata::ID);
+    let metadata =
+        mpl_token_metadata::accounts::Metadata::safe_deserialize(&metadata.data).unwrap();
+    assert_eq!(metadata.mint, mint.pubkey());
+    assert_eq!(metadata.update_authority, payer.pubkey());
+    assert!(metadata.name.starts_with("Protein folding"));
+
+    let edition = h
+        .svm
+        .get_account(&pda::master_edition(&mint.pubkey()).0)
+        .unwrap();
+    assert_eq!(edition.owner, mpl_token_metadata::ID);
+}
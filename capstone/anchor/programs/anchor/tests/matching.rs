@@ -0,0 +1,87 @@
+//! Quadratic-funding arithmetic behind `distribute_matching`.
+
+use anchor::{isqrt, FundingRound, RoundEntry};
+use anchor_lang::prelude::Pubkey;
+
+/// Tallies contributions the way `fund_proposal` does.
+fn entry(contributions: &[u64]) -> RoundEntry {
+    RoundEntry {
+        proposal: Pubkey::new_unique(),
+        contributors: contributions.len() as u32,
+        contributed: contributions.iter().sum(),
+        sqrt_sum: contributions.iter().map(|c| isqrt(*c as u128)).sum(),
+        matched: 0,
+    }
+}
+
+#[test]
+fn integer_square_roots_round_down() {
+    for value in [
+        0u128,
+        1,
+        2,
+        3,
+        4,
+        15,
+        16,
+        17,
+        1_000_000,
+        u64::MAX as u128,
+        u128::MAX,
+    ] {
+        let root = isqrt(value);
+        assert!(root * root <= value, "isqrt({value}) = {root}");
+        assert!(
+            (root + 1).checked_mul(root + 1).is_none_or(|sq| sq > value),
+            "isqrt({value}) = {root}"
+        );
+    }
+}
+
+#[test]
+fn many_small_contributions_outweigh_one_large() {
+    // Same total raised, spread over 100 contributors vs a single whale
+    let crowd = entry(&[100; 100]);
+    let whale = entry(&[10_000]);
+
+    let shares = FundingRound::matching_shares(1_000_000, &[crowd, whale], &[true, true]);
+    assert_eq!(shares, vec![1_000_000, 0]);
+}
+
+#[test]
+fn pool_splits_pro_rata_to_ideal_matches() {
+    // Ideal matches: (4 * 10)² - 400 = 1200 and (2 * 10)² - 200 = 200
+    let entries = [entry(&[100; 4]), entry(&[100; 2])];
+
+    let shares = FundingRound::matching_shares(1_400, &entries, &[true, true]);
+    assert_eq!(shares, vec![1_200, 200]);
+
+    // Rounding never hands out more than the pool
+    let shares = FundingRound::matching_shares(1_000, &entries, &[true, true]);
+    assert_eq!(shares, vec![857, 142]);
+}
+
+#[test]
+fn ineligible_proposals_get_nothing() {
+    let entries = [entry(&[100; 4]), entry(&[100; 2])];
+
+    let shares = FundingRound::matching_shares(1_000, &entries, &[false, true]);
+    assert_eq!(shares, vec![0, 1_000]);
+
+    let shares = FundingRound::matching_shares(1_000, &entries, &[false, false]);
+    assert_eq!(shares, vec![0, 0]);
+}
+
+#[test]
+fn large_tallies_do_not_overflow() {
+    let big = RoundEntry {
+        sqrt_sum: u64::MAX as u128 * 4,
+        contributed: u64::MAX,
+        ..entry(&[])
+    };
+    let small = entry(&[u64::MAX / 2, u64::MAX / 2]);
+
+    let shares = FundingRound::matching_shares(u64::MAX, &[big, small], &[true, true]);
+    assert!(shares.iter().map(|s| *s as u128).sum::<u128>() <= u64::MAX as u128);
+    assert!(shares[0] > shares[1]);
+}
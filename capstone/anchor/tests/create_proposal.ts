@@ -239,7 +239,7 @@ describe("Create Proposal Test", () => {
         .rpc();
       assert.fail("Should have thrown error");
     } catch (err) {
-      assert.include(err.toString(), "MoreSecurityDeposit");
+      assert.include(err.toString(), "SecurityDepositTooLow");
     }
   });
 
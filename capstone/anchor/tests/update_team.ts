@@ -0,0 +1,223 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Anchor } from "../target/types/anchor";
+import { assert } from "chai";
+import {
+  createMint,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  createAssociatedTokenAccountInstruction,
+  mintTo,
+} from "@solana/spl-token";
+
+describe("update_team", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.anchor as Program<Anchor>;
+
+  let researcher = provider.wallet;
+  let coResearcher = anchor.web3.Keypair.generate();
+  let thirdMember = anchor.web3.Keypair.generate();
+  let proposalPda: anchor.web3.PublicKey;
+  let mint: anchor.web3.PublicKey;
+  let researcherTokenAccount: anchor.web3.PublicKey;
+  let escrowPda: anchor.web3.PublicKey;
+
+  // Generate a unique title for this test run
+  const uniqueId = Math.random().toString(36).substring(2, 8);
+  const title = `Test_${uniqueId}`;
+  const abstract = "Test Abstract";
+  const ipfsHash = "QmTestHash";
+  const amountAsked = new anchor.BN(1000000000);
+  const totalMilestones = 3;
+  const securityDeposit = new anchor.BN(100000000);
+
+  before(async () => {
+    // Create mint
+    mint = await createMint(
+      provider.connection,
+      researcher.payer,
+      researcher.publicKey,
+      null,
+      9
+    );
+
+    // Create researcher's token account
+    researcherTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      researcher.publicKey
+    );
+
+    try {
+      const tx = new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          researcher.publicKey,
+          researcherTokenAccount,
+          researcher.publicKey,
+          mint
+        )
+      );
+      await provider.sendAndConfirm(tx);
+    } catch (err) {
+      console.log(
+        "Token account creation error (may already exist):",
+        err.message
+      );
+    }
+
+    // Mint tokens to researcher
+    await mintTo(
+      provider.connection,
+      researcher.payer,
+      mint,
+      researcherTokenAccount,
+      researcher.publicKey,
+      2000000000
+    );
+
+    [proposalPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("proposal"),
+        researcher.publicKey.toBuffer(),
+        Buffer.from(title),
+      ],
+      program.programId
+    );
+
+    [escrowPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), proposalPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal(
+        title,
+        abstract,
+        ipfsHash,
+        amountAsked,
+        totalMilestones,
+        securityDeposit
+      )
+      .accounts({
+        researcher: researcher.publicKey,
+        proposal: proposalPda,
+        escrow: escrowPda,
+        mint: mint,
+        researcherTokenAccount: researcherTokenAccount,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  });
+
+  it("Starts with the researcher as the only team member", async () => {
+    const proposal = await program.account.proposal.fetch(proposalPda);
+
+    assert.equal(proposal.team.length, 1);
+    assert.equal(
+      proposal.team[0].wallet.toString(),
+      researcher.publicKey.toString()
+    );
+    assert.equal(proposal.team[0].shareBps, 10000);
+  });
+
+  it("Adds a co-researcher with a 40% split", async () => {
+    await program.methods
+      .updateTeam([
+        { wallet: researcher.publicKey, shareBps: 6000 },
+        { wallet: coResearcher.publicKey, shareBps: 4000 },
+      ])
+      .accounts({
+        researcher: researcher.publicKey,
+        proposal: proposalPda,
+      })
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+
+    assert.equal(proposal.team.length, 2);
+    assert.equal(
+      proposal.team[1].wallet.toString(),
+      coResearcher.publicKey.toString()
+    );
+    assert.equal(proposal.team[1].shareBps, 4000);
+  });
+
+  it("Fails if shares do not sum to 10000 basis points", async () => {
+    try {
+      await program.methods
+        .updateTeam([
+          { wallet: researcher.publicKey, shareBps: 5000 },
+          { wallet: coResearcher.publicKey, shareBps: 4000 },
+        ])
+        .accounts({
+          researcher: researcher.publicKey,
+          proposal: proposalPda,
+        })
+        .remainingAccounts([
+          {
+            pubkey: coResearcher.publicKey,
+            isSigner: true,
+            isWritable: false,
+          },
+        ])
+        .signers([coResearcher])
+        .rpc({ skipPreflight: true });
+      assert.fail("Should have thrown error");
+    } catch (err) {
+      assert.ok(err, "Expected an error but none was thrown");
+      console.log("Error received:", err.toString());
+    }
+  });
+
+  it("Fails if an existing member does not approve the change", async () => {
+    try {
+      await program.methods
+        .updateTeam([
+          { wallet: researcher.publicKey, shareBps: 5000 },
+          { wallet: thirdMember.publicKey, shareBps: 5000 },
+        ])
+        .accounts({
+          researcher: researcher.publicKey,
+          proposal: proposalPda,
+        })
+        .rpc({ skipPreflight: true });
+      assert.fail("Should have thrown error");
+    } catch (err) {
+      assert.ok(err, "Expected an error but none was thrown");
+      console.log("Error received:", err.toString());
+    }
+  });
+
+  it("Replaces the team once every member signs", async () => {
+    await program.methods
+      .updateTeam([
+        { wallet: researcher.publicKey, shareBps: 5000 },
+        { wallet: coResearcher.publicKey, shareBps: 2500 },
+        { wallet: thirdMember.publicKey, shareBps: 2500 },
+      ])
+      .accounts({
+        researcher: researcher.publicKey,
+        proposal: proposalPda,
+      })
+      .remainingAccounts([
+        {
+          pubkey: coResearcher.publicKey,
+          isSigner: true,
+          isWritable: false,
+        },
+      ])
+      .signers([coResearcher])
+      .rpc();
+
+    const proposal = await program.account.proposal.fetch(proposalPda);
+
+    assert.equal(proposal.team.length, 3);
+    assert.equal(
+      proposal.team[2].wallet.toString(),
+      thirdMember.publicKey.toString()
+    );
+  });
+});